@@ -0,0 +1,278 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::config::Config;
+use crate::storage;
+
+/// backups kept for each device, older ones get deleted except those taken before a reset
+pub const KEEP: usize = 50;
+
+/// What was about to happen when a backup was taken
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Reason {
+    /// a config was written to the device
+    Apply,
+    /// the firmware was reset to its defaults
+    Reset,
+}
+
+/// Config a device had before it was changed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Backup {
+    /// file name without the extension, set when listing
+    #[serde(skip)]
+    pub id: String,
+    /// milliseconds since the unix epoch
+    pub taken: u64,
+    pub reason: Reason,
+    /// name the device reported
+    pub device: String,
+    pub config: Config,
+}
+
+impl Backup {
+    /// When the backup was taken, as an rfc 3339 timestamp in utc
+    pub fn timestamp(&self) -> String {
+        let taken = UNIX_EPOCH + Duration::from_millis(self.taken);
+        humantime::format_rfc3339_seconds(taken).to_string()
+    }
+}
+
+/// Directory the backups of one device are stored in
+///
+/// `identity` is [`crate::device::Device::storage_identity`]
+pub fn backups_dir(identity: &str) -> Result<PathBuf, Error> {
+    // the identity has colons and slashes, which are not allowed in a directory name
+    let dir = identity.replace(|c: char| !c.is_ascii_alphanumeric(), "-");
+    Ok(storage::config_dir()?.join("backups").join(dir))
+}
+
+fn path(dir: &Path, id: &str) -> Result<PathBuf, Error> {
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_digit()) {
+        return Err(Error::InvalidId(id.to_string()));
+    }
+    Ok(dir.join(format!("{id}.toml")))
+}
+
+/// Store the config of a device, deleting the oldest backups past [`KEEP`]
+///
+/// Nothing is stored when the newest backup has the same config and reason, that one is
+/// returned
+pub fn create(
+    identity: &str,
+    device: &str,
+    reason: Reason,
+    config: &Config,
+) -> Result<Backup, Error> {
+    create_in(&backups_dir(identity)?, device, reason, config)
+}
+
+fn create_in(dir: &Path, device: &str, reason: Reason, config: &Config) -> Result<Backup, Error> {
+    // applying the same config again would push the older backups out, a reset still
+    // gets its own backup since those are never deleted
+    if let Some(newest) = ids(dir)?.first() {
+        match load_in(dir, newest) {
+            Ok(backup)
+                if backup.reason == reason
+                    && toml::to_string(&backup.config)? == toml::to_string(config)? =>
+            {
+                return Ok(backup)
+            }
+            Ok(_) => (),
+            Err(e) => log::warn!("could not read the backup {newest}: {e}"),
+        }
+    }
+
+    let taken = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_millis() as u64);
+    std::fs::create_dir_all(dir)?;
+
+    // two backups in the same millisecond get the next free one
+    let mut id = taken;
+    while dir.join(format!("{id}.toml")).exists() {
+        id += 1;
+    }
+    let backup = Backup {
+        id: id.to_string(),
+        taken,
+        reason,
+        device: device.to_string(),
+        config: config.clone(),
+    };
+    std::fs::write(
+        dir.join(format!("{id}.toml")),
+        toml::to_string_pretty(&backup)?,
+    )?;
+
+    for old in ids(dir)?.iter().skip(KEEP) {
+        // the config from before a reset is the one most likely to be missed
+        let result = load_in(dir, old).and_then(|old_backup| match old_backup.reason {
+            Reason::Reset => Ok(()),
+            Reason::Apply => delete_in(dir, old),
+        });
+        if let Err(e) = result {
+            log::warn!("could not delete the old backup {old}: {e}");
+        }
+    }
+    Ok(backup)
+}
+
+/// Ids of the stored backups in a directory, newest first
+fn ids(dir: &Path) -> Result<Vec<String>, Error> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    let mut ids = entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            (path.extension()? == "toml").then_some(())?;
+            let id = path.file_stem()?.to_string_lossy().into_owned();
+            Some((id.parse::<u64>().ok()?, id))
+        })
+        .collect::<Vec<_>>();
+    ids.sort_unstable_by_key(|(taken, _)| std::cmp::Reverse(*taken));
+    Ok(ids.into_iter().map(|(_, id)| id).collect())
+}
+
+/// Stored backups of a device, newest first
+///
+/// Files that can't be read are logged and left out
+pub fn list(identity: &str) -> Result<Vec<Backup>, Error> {
+    let dir = backups_dir(identity)?;
+    Ok(ids(&dir)?
+        .into_iter()
+        .filter_map(|id| match load_in(&dir, &id) {
+            Ok(backup) => Some(backup),
+            Err(e) => {
+                log::warn!("could not read the backup {id}: {e}");
+                None
+            }
+        })
+        .collect())
+}
+
+/// Read one backup
+pub fn load(identity: &str, id: &str) -> Result<Backup, Error> {
+    load_in(&backups_dir(identity)?, id)
+}
+
+fn load_in(dir: &Path, id: &str) -> Result<Backup, Error> {
+    let raw = std::fs::read_to_string(path(dir, id)?).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => Error::NotFound(id.to_string()),
+        _ => e.into(),
+    })?;
+    let mut backup: Backup = toml::from_str(&raw)?;
+    backup.id = id.to_string();
+    Ok(backup)
+}
+
+/// Remove a stored backup
+pub fn delete(identity: &str, id: &str) -> Result<(), Error> {
+    delete_in(&backups_dir(identity)?, id)
+}
+
+fn delete_in(dir: &Path, id: &str) -> Result<(), Error> {
+    std::fs::remove_file(path(dir, id)?)?;
+    Ok(())
+}
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Storage(#[from] storage::Error),
+    #[error("backup file io")]
+    Io(#[from] std::io::Error),
+    #[error("\"{0}\" is not a backup")]
+    InvalidId(String),
+    #[error("there is no backup \"{0}\"")]
+    NotFound(String),
+    #[error(transparent)]
+    Deserialize(#[from] toml::de::Error),
+    #[error(transparent)]
+    Serialize(#[from] toml::ser::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{HKey, Millimeter};
+
+    /// Empty directory that is removed again when dropped
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir()
+                .join(format!("crustility-backups-{name}-{}", std::process::id()));
+            let _ = std::fs::remove_dir_all(&dir);
+            Self(dir)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn config(lower: i32) -> Config {
+        let mut key = HKey::default();
+        key.hysterisis.lower = Millimeter::from_hundredths(lower);
+        Config {
+            hkeys: vec![key],
+            ..Default::default()
+        }
+    }
+
+    fn reasons(dir: &Path) -> Vec<Reason> {
+        ids(dir)
+            .unwrap()
+            .iter()
+            .map(|id| load_in(dir, id).unwrap().reason)
+            .collect()
+    }
+
+    #[test]
+    fn same_config_is_kept_once() {
+        let dir = TempDir::new("same");
+        let first = create_in(&dir.0, "minipad", Reason::Apply, &config(100)).unwrap();
+        let again = create_in(&dir.0, "minipad", Reason::Apply, &config(100)).unwrap();
+        assert_eq!(again.id, first.id);
+        assert_eq!(ids(&dir.0).unwrap().len(), 1);
+
+        create_in(&dir.0, "minipad", Reason::Reset, &config(100)).unwrap();
+        assert_eq!(ids(&dir.0).unwrap().len(), 2);
+
+        create_in(&dir.0, "minipad", Reason::Apply, &config(200)).unwrap();
+        // only the newest one is compared
+        create_in(&dir.0, "minipad", Reason::Apply, &config(100)).unwrap();
+        assert_eq!(ids(&dir.0).unwrap().len(), 4);
+    }
+
+    #[test]
+    fn resets_are_not_rotated_out() {
+        let dir = TempDir::new("retention");
+        create_in(&dir.0, "minipad", Reason::Reset, &config(0)).unwrap();
+        for i in 1..=KEEP as i32 + 5 {
+            create_in(&dir.0, "minipad", Reason::Apply, &config(i)).unwrap();
+        }
+
+        let reasons = reasons(&dir.0);
+        assert_eq!(reasons.len(), KEEP + 1);
+        assert_eq!(reasons.last(), Some(&Reason::Reset));
+        assert!(reasons[..KEEP].iter().all(|r| *r == Reason::Apply));
+        // the newest applies are the ones kept
+        let newest = load_in(&dir.0, &ids(&dir.0).unwrap()[0]).unwrap();
+        assert_eq!(
+            newest.config.hkeys[0].hysterisis.lower,
+            Millimeter::from_hundredths(KEEP as i32 + 5)
+        );
+    }
+}
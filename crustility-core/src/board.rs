@@ -0,0 +1,107 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// space between generated keys, in key units
+const GAP: f32 = 1. / 6.;
+/// hall effect keys are drawn tall to fit the travel visualizer
+const HKEY_HEIGHT: f32 = 3.;
+
+/// Physical arrangement of the keys of a device
+///
+/// Positions and sizes are in key units, one unit being the width of a regular key
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Board {
+    #[serde(default, rename = "key")]
+    pub keys: Vec<Placement>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyKind {
+    /// hall effect key
+    Hkey,
+    /// digital key
+    Dkey,
+}
+
+/// Where a key is on the board
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Placement {
+    pub kind: KeyKind,
+    /// index of the key in the config, starting at 0
+    pub index: usize,
+    pub x: f32,
+    pub y: f32,
+    #[serde(default = "one")]
+    pub width: f32,
+    #[serde(default = "one")]
+    pub height: f32,
+    /// degrees clockwise around the center of the key
+    #[serde(default)]
+    pub rotation: f32,
+}
+
+fn one() -> f32 {
+    1.
+}
+
+impl Board {
+    /// Single row of hall effect keys with the digital keys below
+    pub fn generate(hkeys: usize, dkeys: usize) -> Self {
+        let row = |kind, count, y, height| {
+            (0..count).map(move |index| Placement {
+                kind,
+                index,
+                x: index as f32 * (1. + GAP),
+                y,
+                width: 1.,
+                height,
+                rotation: 0.,
+            })
+        };
+        Self {
+            keys: row(KeyKind::Hkey, hkeys, 0., HKEY_HEIGHT)
+                .chain(row(KeyKind::Dkey, dkeys, HKEY_HEIGHT + GAP, 1.))
+                .collect(),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        Ok(toml::from_str(&std::fs::read_to_string(path)?)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        std::fs::write(path, toml::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Smallest rectangle containing every key as `(min, max)`, ignoring rotation
+    pub fn bounds(&self) -> ([f32; 2], [f32; 2]) {
+        if self.keys.is_empty() {
+            return ([0.; 2], [0.; 2]);
+        }
+        self.keys
+            .iter()
+            .fold(([f32::MAX; 2], [f32::MIN; 2]), |(min, max), key| {
+                (
+                    [min[0].min(key.x), min[1].min(key.y)],
+                    [
+                        max[0].max(key.x + key.width),
+                        max[1].max(key.y + key.height),
+                    ],
+                )
+            })
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("layout file io")]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Deserialize(#[from] toml::de::Error),
+    #[error(transparent)]
+    Serialize(#[from] toml::ser::Error),
+}
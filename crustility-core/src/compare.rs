@@ -0,0 +1,300 @@
+use crate::config::{Config, HKey, Millimeter, RapidTrigger};
+use crate::keycode::KeyCode;
+
+/// Setting of a hall effect key that can differ between two configs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Setting {
+    /// the whole key, for keys only one of the configs has
+    Key,
+    RapidTrigger,
+    Continuous,
+    UpSensitivity,
+    DownSensitivity,
+    HysteresisLower,
+    HysteresisUpper,
+    KeyCode,
+    Hid,
+}
+
+/// Value of a setting in one of the configs
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    /// the key exists, the value of [`Setting::Key`]
+    Present,
+    Switch(bool),
+    Distance(Millimeter),
+    KeyCode(KeyCode),
+}
+
+/// One setting of one key that is not the same in both configs
+#[derive(Debug, Clone, PartialEq)]
+pub struct Difference {
+    /// index of the hall effect key
+    pub key: usize,
+    pub setting: Setting,
+    /// value on the left, `None` when the key is missing
+    pub left: Option<Value>,
+    pub right: Option<Value>,
+}
+
+/// Values of the settings a key has, the rapid trigger settings only when it is on
+fn settings(key: &HKey) -> Vec<(Setting, Value)> {
+    let mut settings = vec![(Setting::RapidTrigger, Value::Switch(key.rt.is_some()))];
+    if let Some(rt) = &key.rt {
+        settings.extend([
+            (Setting::Continuous, Value::Switch(rt.continuos)),
+            (Setting::UpSensitivity, Value::Distance(rt.up_sensitivity)),
+            (
+                Setting::DownSensitivity,
+                Value::Distance(rt.down_sensitivity),
+            ),
+        ]);
+    }
+    settings.extend([
+        (
+            Setting::HysteresisLower,
+            Value::Distance(key.hysterisis.lower),
+        ),
+        (
+            Setting::HysteresisUpper,
+            Value::Distance(key.hysterisis.upper),
+        ),
+        (Setting::KeyCode, Value::KeyCode(key.keycode)),
+        (Setting::Hid, Value::Switch(key.hid)),
+    ]);
+    settings
+}
+
+/// Every setting that differs, in key order
+///
+/// The rapid trigger settings are only compared when both keys have it on
+pub fn diff(left: &Config, right: &Config) -> Vec<Difference> {
+    let mut differences = Vec::new();
+    for key in 0..left.hkeys.len().max(right.hkeys.len()) {
+        let (left_key, right_key) = match (left.hkeys.get(key), right.hkeys.get(key)) {
+            (Some(left), Some(right)) => (left, right),
+            (left, right) => {
+                let present = |key: Option<&HKey>| key.map(|_| Value::Present);
+                differences.push(Difference {
+                    key,
+                    setting: Setting::Key,
+                    left: present(left),
+                    right: present(right),
+                });
+                continue;
+            }
+        };
+
+        let right_settings = settings(right_key);
+        for (setting, left_value) in settings(left_key) {
+            let Some((_, right_value)) = right_settings.iter().find(|(s, _)| *s == setting) else {
+                continue;
+            };
+            if left_value != *right_value {
+                differences.push(Difference {
+                    key,
+                    setting,
+                    left: Some(left_value),
+                    right: Some(right_value.clone()),
+                });
+            }
+        }
+    }
+    differences
+}
+
+/// Outcome of [`merge`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Merge {
+    /// settings taken from the other config
+    pub copied: usize,
+    /// settings changed on both sides, the edited value was kept
+    pub conflicts: Vec<Difference>,
+}
+
+/// Bring the changes between `base` and `theirs` into `ours`
+///
+/// Settings `ours` changed itself are left alone and reported as conflicts,
+/// a key whose rapid trigger was switched counts as changed for all of its
+/// rapid trigger settings
+pub fn merge(base: &Config, theirs: &Config, ours: &mut Config) -> Merge {
+    let edited = diff(base, ours);
+    let is_edited = |key: usize, setting: Setting| {
+        edited.iter().any(|edit| {
+            edit.key == key
+                && (edit.setting == setting
+                    || edit.setting == Setting::Key
+                    || (edit.setting == Setting::RapidTrigger && is_rt_setting(setting)))
+        })
+    };
+
+    let mut merge = Merge::default();
+    for difference in diff(base, theirs) {
+        if !is_edited(difference.key, difference.setting)
+            && copy(theirs, ours, difference.key, difference.setting)
+        {
+            merge.copied += 1;
+        } else {
+            merge.conflicts.push(difference);
+        }
+    }
+    merge
+}
+
+fn is_rt_setting(setting: Setting) -> bool {
+    matches!(
+        setting,
+        Setting::Continuous | Setting::UpSensitivity | Setting::DownSensitivity
+    )
+}
+
+/// Copy one setting of a key from the left config to the right one
+///
+/// Returns if anything was copied, keys missing on either side can't be copied
+pub fn copy(from: &Config, to: &mut Config, key: usize, setting: Setting) -> bool {
+    let (Some(from), Some(to)) = (from.hkeys.get(key), to.hkeys.get_mut(key)) else {
+        return false;
+    };
+    match setting {
+        Setting::Key => return false,
+        Setting::RapidTrigger => to.rt = from.rt.clone(),
+        Setting::HysteresisLower => to.hysterisis.lower = from.hysterisis.lower,
+        Setting::HysteresisUpper => to.hysterisis.upper = from.hysterisis.upper,
+        Setting::KeyCode => to.keycode = from.keycode,
+        Setting::Hid => to.hid = from.hid,
+        Setting::Continuous | Setting::UpSensitivity | Setting::DownSensitivity => {
+            let (Some(from), Some(to)) = (&from.rt, &mut to.rt) else {
+                return false;
+            };
+            copy_rt(from, to, setting);
+        }
+    }
+    true
+}
+
+fn copy_rt(from: &RapidTrigger, to: &mut RapidTrigger, setting: Setting) {
+    match setting {
+        Setting::Continuous => to.continuos = from.continuos,
+        Setting::UpSensitivity => to.up_sensitivity = from.up_sensitivity,
+        Setting::DownSensitivity => to.down_sensitivity = from.down_sensitivity,
+        _ => (),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(rt: Option<i32>, lower: i32) -> HKey {
+        let mut key = HKey {
+            rt: rt.map(|sensitivity| RapidTrigger {
+                continuos: false,
+                up_sensitivity: Millimeter::from_hundredths(sensitivity),
+                down_sensitivity: Millimeter::from_hundredths(sensitivity),
+            }),
+            ..Default::default()
+        };
+        key.hysterisis.lower = Millimeter::from_hundredths(lower);
+        key
+    }
+
+    fn config(keys: Vec<HKey>) -> Config {
+        Config {
+            hkeys: keys,
+            ..Default::default()
+        }
+    }
+
+    fn settings(differences: &[Difference]) -> Vec<(usize, Setting)> {
+        differences.iter().map(|d| (d.key, d.setting)).collect()
+    }
+
+    #[test]
+    fn diff_settings() {
+        let left = config(vec![key(Some(10), 200), key(None, 200), key(None, 200)]);
+        let right = config(vec![key(Some(20), 200), key(Some(20), 250)]);
+        let differences = diff(&left, &right);
+        assert_eq!(
+            settings(&differences),
+            [
+                (0, Setting::UpSensitivity),
+                (0, Setting::DownSensitivity),
+                // only the switch is compared while one side has rapid trigger off
+                (1, Setting::RapidTrigger),
+                (1, Setting::HysteresisLower),
+                (2, Setting::Key),
+            ]
+        );
+        assert_eq!(
+            differences[0].left,
+            Some(Value::Distance(Millimeter::from_hundredths(10)))
+        );
+        assert_eq!(
+            differences[0].right,
+            Some(Value::Distance(Millimeter::from_hundredths(20)))
+        );
+        assert_eq!(differences[2].left, Some(Value::Switch(false)));
+        assert_eq!(differences[4].left, Some(Value::Present));
+        assert_eq!(differences[4].right, None);
+        assert!(diff(&left, &left).is_empty());
+    }
+
+    #[test]
+    fn copy_settings() {
+        let from = config(vec![key(Some(10), 200), key(None, 150)]);
+        let mut to = config(vec![key(None, 300), key(Some(20), 300)]);
+
+        // rapid trigger sub settings need rapid trigger on in the target
+        assert!(!copy(&from, &mut to, 0, Setting::UpSensitivity));
+        assert!(to.hkeys[0].rt.is_none());
+        assert!(copy(&from, &mut to, 0, Setting::RapidTrigger));
+        assert!(copy(&from, &mut to, 0, Setting::HysteresisLower));
+        assert!(diff(&from, &to).iter().all(|d| d.key != 0));
+
+        // or in the source
+        assert!(!copy(&from, &mut to, 1, Setting::Continuous));
+        // missing keys and whole keys can't be copied
+        assert!(!copy(&from, &mut to, 2, Setting::Hid));
+        assert!(!copy(&from, &mut to, 1, Setting::Key));
+    }
+
+    #[test]
+    fn merge_changes() {
+        let base = config(vec![key(Some(10), 200), key(None, 200)]);
+        // changed on the device
+        let theirs = config(vec![key(Some(30), 200), key(None, 250)]);
+        // edited in crustility, turning rapid trigger off on the first key
+        let mut ours = config(vec![key(None, 220), key(None, 200)]);
+
+        let merge = merge(&base, &theirs, &mut ours);
+        assert_eq!(merge.copied, 1);
+        assert_eq!(
+            settings(&merge.conflicts),
+            [(0, Setting::UpSensitivity), (0, Setting::DownSensitivity)]
+        );
+        assert!(ours.hkeys[0].rt.is_none());
+        assert_eq!(
+            ours.hkeys[0].hysterisis.lower,
+            Millimeter::from_hundredths(220)
+        );
+        assert_eq!(
+            ours.hkeys[1].hysterisis.lower,
+            Millimeter::from_hundredths(250)
+        );
+    }
+
+    #[test]
+    fn merge_conflicting_edit() {
+        let base = config(vec![key(None, 200)]);
+        let theirs = config(vec![key(None, 250)]);
+        let mut ours = config(vec![key(None, 220)]);
+
+        let merge = merge(&base, &theirs, &mut ours);
+        assert_eq!(merge.copied, 0);
+        assert_eq!(settings(&merge.conflicts), [(0, Setting::HysteresisLower)]);
+        assert_eq!(
+            ours.hkeys[0].hysterisis.lower,
+            Millimeter::from_hundredths(220)
+        );
+    }
+}
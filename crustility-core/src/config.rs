@@ -0,0 +1,512 @@
+use std::collections::BTreeMap;
+use std::ops::{Add, Div, Mul, Sub};
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use thiserror::Error;
+
+use crate::keycode::KeyCode;
+
+/// Settings of every key, serializes only what can be written to a device
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Config {
+    #[serde(default)]
+    pub hkeys: Vec<HKey>,
+    #[serde(default)]
+    pub dkeys: Vec<DKey>,
+    #[serde(skip)]
+    pub capabilities: Capabilities,
+    /// other values the firmware reports in `get`, like its version
+    #[serde(skip)]
+    pub firmware: BTreeMap<String, String>,
+}
+
+impl Config {
+    /// Copy the key settings of another config, keeping the live state and capabilities
+    ///
+    /// Returns how many keys were copied
+    pub fn apply_settings(&mut self, other: &Config) -> usize {
+        for (key, other) in self.hkeys.iter_mut().zip(&other.hkeys) {
+            key.rt = other.rt.clone();
+            key.hysterisis = other.hysterisis.clone();
+            key.hid = other.hid;
+            key.keycode = other.keycode;
+        }
+        self.hkeys.len().min(other.hkeys.len())
+    }
+}
+
+/// Limits of the firmware and the switches
+///
+/// The firmware doesn't report these over serial,
+/// the defaults are the compile time constants of the minipad firmware
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Capabilities {
+    /// total travel distance of a key
+    pub travel_distance: Millimeter,
+    /// minimum distance between the lower and upper hysteresis
+    pub hysteresis_tolerance: Millimeter,
+    /// smallest rapid trigger sensitivity
+    pub min_sensitivity: Millimeter,
+}
+
+impl Default for Capabilities {
+    fn default() -> Self {
+        Self {
+            travel_distance: Millimeter::from_serial(400),
+            hysteresis_tolerance: Millimeter::from_serial(10),
+            min_sensitivity: Millimeter::from_serial(1),
+        }
+    }
+}
+
+/// Hall effect key
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct HKey {
+    #[serde(default)]
+    pub rt: Option<RapidTrigger>,
+    pub hysterisis: Hysterisis,
+    pub hid: bool,
+    pub keycode: KeyCode,
+    #[serde(skip)]
+    pub rest: usize,
+    #[serde(skip)]
+    pub down: usize,
+    #[serde(skip)]
+    pub current_position: Millimeter,
+    #[serde(skip)]
+    pub target_position: Millimeter,
+    #[serde(skip)]
+    pub trigger: Trigger,
+}
+
+/// Pressed state of a key, worked out from the sensor readings the same way the firmware does
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Trigger {
+    pub pressed: bool,
+    /// rapid trigger took over from the hysteresis
+    pub rt_active: bool,
+    /// lowest position while pressed, highest while released
+    pub peak: Millimeter,
+}
+
+impl HKey {
+    /// Update the trigger with a new sensor reading
+    pub fn update_trigger(&mut self, capabilities: &Capabilities, position: Millimeter) {
+        let trigger = &mut self.trigger;
+        let Some(rt) = &self.rt else {
+            if position <= self.hysterisis.lower {
+                trigger.pressed = true;
+            } else if position >= self.hysterisis.upper {
+                trigger.pressed = false;
+            }
+            trigger.rt_active = false;
+            return;
+        };
+
+        if position <= self.hysterisis.lower && !trigger.rt_active {
+            trigger.rt_active = true;
+            trigger.pressed = true;
+            trigger.peak = position;
+        }
+        if !trigger.rt_active {
+            trigger.pressed = false;
+            return;
+        }
+
+        // continuous rapid trigger stays active until the key is back at rest
+        let reset = if rt.continuos {
+            capabilities.travel_distance - capabilities.hysteresis_tolerance
+        } else {
+            self.hysterisis.upper
+        };
+        if position >= reset {
+            *trigger = Trigger::default();
+            return;
+        }
+
+        if trigger.pressed {
+            if position >= trigger.peak + rt.up_sensitivity {
+                trigger.pressed = false;
+                trigger.peak = position;
+            } else {
+                trigger.peak = trigger.peak.min(position);
+            }
+        } else if position + rt.down_sensitivity <= trigger.peak {
+            trigger.pressed = true;
+            trigger.peak = position;
+        } else {
+            trigger.peak = trigger.peak.max(position);
+        }
+    }
+}
+
+/// Rapid trigger settings, the key is released and pressed again by direction changes
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct RapidTrigger {
+    pub continuos: bool, //continuos rapid trigger
+    pub down_sensitivity: Millimeter,
+    pub up_sensitivity: Millimeter,
+}
+
+/// The key is pressed below `lower` and released above `upper`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Hysterisis {
+    pub lower: Millimeter,
+    pub upper: Millimeter,
+}
+
+impl Default for Hysterisis {
+    /// Valid with the default [`Capabilities`], so a new key can be written as is
+    fn default() -> Self {
+        Self {
+            lower: Millimeter::from_serial(300),
+            upper: Millimeter::from_serial(330),
+        }
+    }
+}
+
+/// Distance in hundredths of a millimeter, the unit the firmware uses over serial
+///
+/// Stored as an integer so values read from the device are written back unchanged,
+/// the operators saturate instead of overflowing
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Millimeter(i32);
+
+/// How to round a value that falls between two hundredths
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rounding {
+    Nearest,
+    Down,
+    Up,
+    TowardZero,
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ParseMillimeterError {
+    #[error("no value")]
+    Empty,
+    #[error("not a number")]
+    Invalid,
+    #[error("more than two decimal places")]
+    TooPrecise,
+    #[error("value is too large")]
+    OutOfRange,
+}
+
+impl Millimeter {
+    pub const ZERO: Self = Self(0);
+
+    pub fn from_serial(value: u16) -> Self {
+        Self(value as i32)
+    }
+
+    /// Value sent to the firmware, `None` if it is negative or too large to send
+    pub fn to_serial(self) -> Option<u16> {
+        u16::try_from(self.0).ok()
+    }
+
+    pub fn from_hundredths(value: i32) -> Self {
+        Self(value)
+    }
+
+    pub fn hundredths(self) -> i32 {
+        self.0
+    }
+
+    /// Convert from a floating point value in millimeters
+    ///
+    /// Returns `None` for NaN and values that don't fit
+    pub fn from_mm(value: f64, rounding: Rounding) -> Option<Self> {
+        let hundredths = value * 100.;
+        let hundredths = match rounding {
+            Rounding::Nearest => hundredths.round(),
+            Rounding::Down => hundredths.floor(),
+            Rounding::Up => hundredths.ceil(),
+            Rounding::TowardZero => hundredths.trunc(),
+        };
+        (hundredths.is_finite() && hundredths >= i32::MIN as f64 && hundredths <= i32::MAX as f64)
+            .then_some(Self(hundredths as i32))
+    }
+
+    pub fn as_mm(self) -> f64 {
+        self.0 as f64 / 100.
+    }
+
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        self.0.checked_add(rhs.0).map(Self)
+    }
+
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        self.0.checked_sub(rhs.0).map(Self)
+    }
+
+    pub fn checked_mul(self, rhs: i32) -> Option<Self> {
+        self.0.checked_mul(rhs).map(Self)
+    }
+
+    pub fn checked_div(self, rhs: i32) -> Option<Self> {
+        self.0.checked_div(rhs).map(Self)
+    }
+
+    pub fn abs(self) -> Self {
+        Self(self.0.abs())
+    }
+}
+
+impl Add for Millimeter {
+    type Output = Millimeter;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self(self.0.saturating_add(rhs.0))
+    }
+}
+
+impl Sub for Millimeter {
+    type Output = Millimeter;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self(self.0.saturating_sub(rhs.0))
+    }
+}
+
+impl Mul<i32> for Millimeter {
+    type Output = Millimeter;
+
+    fn mul(self, rhs: i32) -> Self::Output {
+        Self(self.0.saturating_mul(rhs))
+    }
+}
+
+impl Div<i32> for Millimeter {
+    type Output = Millimeter;
+
+    fn div(self, rhs: i32) -> Self::Output {
+        Self(self.0.saturating_div(rhs))
+    }
+}
+
+impl std::fmt::Display for Millimeter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let abs = self.0.unsigned_abs();
+        write!(f, "{sign}{}.{:02} mm", abs / 100, abs % 100)
+    }
+}
+
+/// Parses `1.25`, `1.25mm` or `1.25 mm` without going through a float
+impl FromStr for Millimeter {
+    type Err = ParseMillimeterError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let s = s.strip_suffix("mm").unwrap_or(s).trim_end();
+        let (negative, s) = match s.strip_prefix('-') {
+            Some(s) => (true, s),
+            None => (false, s),
+        };
+        if s.is_empty() {
+            return Err(ParseMillimeterError::Empty);
+        }
+
+        let (whole, fraction) = s.split_once('.').unwrap_or((s, ""));
+        let digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+        if (whole.is_empty() && fraction.is_empty()) || !digits(whole) || !digits(fraction) {
+            return Err(ParseMillimeterError::Invalid);
+        }
+        if fraction.len() > 2 {
+            return Err(ParseMillimeterError::TooPrecise);
+        }
+
+        // wider than the value so the most negative one still fits before the sign
+        let whole = if whole.is_empty() {
+            0
+        } else {
+            whole
+                .parse::<i64>()
+                .map_err(|_| ParseMillimeterError::OutOfRange)?
+        };
+        let fraction = format!("{fraction:0<2}").parse::<i64>().unwrap_or(0);
+        let value = whole
+            .checked_mul(100)
+            .and_then(|v| v.checked_add(fraction))
+            .ok_or(ParseMillimeterError::OutOfRange)?;
+        i32::try_from(if negative { -value } else { value })
+            .map(Self)
+            .map_err(|_| ParseMillimeterError::OutOfRange)
+    }
+}
+
+/// Written as `"1.25 mm"` so files stay exact and readable
+impl Serialize for Millimeter {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Millimeter {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+impl From<Millimeter> for f32 {
+    fn from(value: Millimeter) -> Self {
+        value.as_mm() as f32
+    }
+}
+
+/// Digital key, not configurable yet
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct DKey {}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn parse_and_format() {
+        assert_eq!("1.25 mm".parse(), Ok(Millimeter::from_hundredths(125)));
+        assert_eq!("1.25mm".parse(), Ok(Millimeter::from_hundredths(125)));
+        assert_eq!("-.5".parse(), Ok(Millimeter::from_hundredths(-50)));
+        assert_eq!(Millimeter::from_hundredths(125).to_string(), "1.25 mm");
+        assert_eq!(Millimeter::from_hundredths(-5).to_string(), "-0.05 mm");
+        assert_eq!(
+            "1.255 mm".parse::<Millimeter>(),
+            Err(ParseMillimeterError::TooPrecise)
+        );
+        assert_eq!(
+            "21474836.48".parse::<Millimeter>(),
+            Err(ParseMillimeterError::OutOfRange)
+        );
+    }
+
+    #[test]
+    fn to_serial_rejects_what_does_not_fit() {
+        assert_eq!(Millimeter::from_hundredths(-1).to_serial(), None);
+        assert_eq!(
+            Millimeter::from_hundredths(u16::MAX as i32 + 1).to_serial(),
+            None
+        );
+        assert_eq!(
+            Millimeter::from_hundredths(u16::MAX as i32).to_serial(),
+            Some(u16::MAX)
+        );
+    }
+
+    /// Feed positions to a key, checking `(pressed, rt_active)` after each one
+    fn run_trigger(mut key: HKey, steps: &[(i32, bool, bool)]) {
+        let capabilities = Capabilities::default();
+        for (i, &(position, pressed, rt_active)) in steps.iter().enumerate() {
+            key.update_trigger(&capabilities, Millimeter::from_hundredths(position));
+            assert_eq!(
+                (key.trigger.pressed, key.trigger.rt_active),
+                (pressed, rt_active),
+                "step {i} at {position}"
+            );
+        }
+    }
+
+    fn trigger_key(rt: Option<bool>) -> HKey {
+        HKey {
+            rt: rt.map(|continuos| RapidTrigger {
+                continuos,
+                down_sensitivity: Millimeter::from_hundredths(20),
+                up_sensitivity: Millimeter::from_hundredths(20),
+            }),
+            hysterisis: Hysterisis {
+                lower: Millimeter::from_hundredths(200),
+                upper: Millimeter::from_hundredths(250),
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn hysteresis_trigger() {
+        run_trigger(
+            trigger_key(None),
+            &[
+                (400, false, false),
+                (220, false, false),
+                (200, true, false),
+                (230, true, false),
+                (250, false, false),
+                (240, false, false),
+                (100, true, false),
+            ],
+        );
+    }
+
+    #[test]
+    fn rapid_trigger() {
+        run_trigger(
+            trigger_key(Some(false)),
+            &[
+                // the hysteresis decides until rapid trigger takes over
+                (400, false, false),
+                (210, false, false),
+                (200, true, true),
+                (150, true, true),
+                // released once it moved up by the sensitivity from the lowest point
+                (165, true, true),
+                (170, false, true),
+                (180, false, true),
+                // pressed again once it moved down by the sensitivity from the highest point
+                (165, false, true),
+                (160, true, true),
+                // back above the upper hysteresis, the hysteresis decides again
+                (250, false, false),
+                (230, false, false),
+                (200, true, true),
+            ],
+        );
+    }
+
+    #[test]
+    fn continuous_rapid_trigger() {
+        run_trigger(
+            trigger_key(Some(true)),
+            &[
+                (200, true, true),
+                (240, false, true),
+                // above the upper hysteresis rapid trigger stays on
+                (260, false, true),
+                (240, true, true),
+                (300, false, true),
+                // until the key is back at rest
+                (390, false, false),
+                (380, false, false),
+                (220, false, false),
+            ],
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn serial_round_trip(value: u16) {
+            prop_assert_eq!(Millimeter::from_serial(value).to_serial(), Some(value));
+        }
+
+        #[test]
+        fn format_round_trip(hundredths: i32) {
+            let value = Millimeter::from_hundredths(hundredths);
+            prop_assert_eq!(value.to_string().parse(), Ok(value));
+        }
+
+        #[test]
+        fn operators_saturate(a: i32, b: i32, factor: i32) {
+            let (ma, mb) = (Millimeter::from_hundredths(a), Millimeter::from_hundredths(b));
+            prop_assert_eq!((ma + mb).hundredths(), a.saturating_add(b));
+            prop_assert_eq!((ma - mb).hundredths(), a.saturating_sub(b));
+            prop_assert_eq!((ma * factor).hundredths(), a.saturating_mul(factor));
+            if factor != 0 {
+                prop_assert_eq!((ma / factor).hundredths(), a.saturating_div(factor));
+            }
+        }
+    }
+}
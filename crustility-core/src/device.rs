@@ -0,0 +1,1362 @@
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::sync::mpsc::{Receiver, RecvTimeoutError, Sender};
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+use crate::board::Board;
+use crate::config::{self, Config, HKey, Millimeter};
+use crate::keycode::KeyCode;
+use crate::storage;
+
+/// Sensor polling rate statistics
+pub mod poll;
+/// Serial port settings
+pub mod transport;
+
+pub use poll::PollStats;
+#[cfg(any(test, debug_assertions))]
+use transport::MemoryTransport;
+use transport::SerialTransport;
+pub use transport::{SerialSettings, Transport};
+
+macro_rules! write_serial {
+    ( $string:ident; $($key:literal, $idx:expr, $field:literal, $value:expr);+) => {
+        $(
+            $string += &format!("{}{}.{} {}\n", $key, $idx, $field, &$value.to_string());
+        )*
+    };
+
+}
+
+/// how long to wait for a complete `out` frame
+const FRAME_TIMEOUT: Duration = Duration::from_millis(200);
+/// how long to wait for the `get` output to finish
+const CONFIG_TIMEOUT: Duration = Duration::from_secs(2);
+/// how often the event loop wakes up when it is not polling sensors
+const IDLE_TIMEOUT: Duration = Duration::from_millis(50);
+
+/// Line based connection to a device
+#[derive(Debug)]
+struct Port {
+    transport: Box<dyn Transport>,
+    /// data read past the last complete line
+    pending: String,
+}
+
+impl Port {
+    fn new(transport: impl Transport + 'static) -> Self {
+        Self {
+            transport: Box::new(transport),
+            pending: String::new(),
+        }
+    }
+
+    fn write(&mut self, data: impl Into<String>) -> Result<(), Error> {
+        self.transport.write(data.into().as_bytes())
+    }
+
+    /// Forget everything received so far, like the rest of an answer that timed out
+    fn clear_input(&mut self) -> Result<(), Error> {
+        self.pending.clear();
+        self.transport.clear_input()
+    }
+
+    /// Read whatever is available, returns an empty string if the read timed out
+    fn read(&mut self) -> Result<String, Error> {
+        let mut buf: Vec<u8> = vec![0; 1000];
+        let len = self.transport.read(buf.as_mut_slice())?;
+        Ok(String::from_utf8_lossy(&buf[..len]).into_owned())
+    }
+
+    /// Read the complete lines that are available
+    ///
+    /// Partial lines are kept until the rest of them arrives
+    fn read_lines(&mut self) -> Result<Vec<String>, Error> {
+        let chunk = self.read()?;
+        self.pending += &chunk;
+        let Some(end) = self.pending.rfind('\n') else {
+            return Ok(Vec::new());
+        };
+        let rest = self.pending.split_off(end + 1);
+        let lines = std::mem::replace(&mut self.pending, rest)
+            .lines()
+            .map(str::to_string)
+            .collect();
+        Ok(lines)
+    }
+}
+
+/// Device using the minipad serial protocol
+#[derive(Debug)]
+pub struct Device {
+    port: Arc<Mutex<Port>>,
+    port_name: String,
+    serial_settings: SerialSettings,
+    name: String,
+    manufacturer: Option<String>,
+    serial_number: Option<String>,
+    key_count: u16,
+    config: Option<Config>,
+    /// config the device is known to have, from the last read or write
+    synced: Option<Config>,
+    /// layout loaded for the device, generated from the config when missing
+    board: Option<Board>,
+    poll_stats: Option<PollStats>,
+    data_receiver: Option<std::sync::mpsc::Receiver<Event>>,
+    event_sender: Option<std::sync::mpsc::Sender<SendEvent>>,
+    /// checks queued that were not answered yet
+    pending_checks: usize,
+    /// pending checks queued before the last write, they report the config from before it
+    stale_checks: usize,
+}
+
+impl Device {
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+    pub fn key_count(&self) -> u16 {
+        self.key_count
+    }
+    pub fn port_name(&self) -> &str {
+        &self.port_name
+    }
+    pub fn manufacturer(&self) -> Option<&str> {
+        self.manufacturer.as_deref()
+    }
+    /// usb serial number, not every device reports one
+    pub fn serial_number(&self) -> Option<&str> {
+        self.serial_number.as_deref()
+    }
+    /// Name the backups, serial settings and layout of the device are kept under
+    pub fn storage_identity(&self, handle: &DeviceHandle) -> String {
+        storage_identity(handle, self.serial_number.as_deref(), &self.port_name)
+    }
+    pub fn serial_settings(&self) -> &SerialSettings {
+        &self.serial_settings
+    }
+    /// Use new serial settings, reopening the port if the event loop is running
+    fn set_serial_settings(&mut self, settings: SerialSettings) -> Result<(), Error> {
+        self.serial_settings = settings.clone();
+        if self.event_sender.is_some() {
+            self.send_event(SendEvent::Configure(settings))?;
+        }
+        Ok(())
+    }
+    pub fn config_mut(&mut self) -> Option<&mut Config> {
+        self.config.as_mut()
+    }
+    /// Use a config read from the device, replacing the edited one
+    pub fn set_config(&mut self, config: Config) {
+        self.synced = Some(config.clone());
+        self.config = Some(config);
+    }
+    /// Config the device had when it was last read or written
+    pub fn synced_config(&self) -> Option<&Config> {
+        self.synced.as_ref()
+    }
+    /// Remember the edited config as what the device has, after writing it
+    pub fn mark_synced(&mut self) {
+        self.synced = self.config.clone();
+    }
+    /// Remember a config read from the device without touching the edited one
+    pub fn set_synced_config(&mut self, config: Config) {
+        self.synced = Some(config);
+    }
+
+    /// Layout of the keys, a single row when none was loaded
+    pub fn board(&self) -> std::borrow::Cow<'_, Board> {
+        match (&self.board, &self.config) {
+            (Some(board), _) => std::borrow::Cow::Borrowed(board),
+            (None, Some(config)) => {
+                std::borrow::Cow::Owned(Board::generate(config.hkeys.len(), config.dkeys.len()))
+            }
+            (None, None) => std::borrow::Cow::Owned(Board::default()),
+        }
+    }
+    pub fn has_custom_board(&self) -> bool {
+        self.board.is_some()
+    }
+
+    pub fn poll_stats(&self) -> Option<&PollStats> {
+        self.poll_stats.as_ref()
+    }
+    pub fn set_poll_stats(&mut self, stats: PollStats) {
+        self.poll_stats = Some(stats);
+    }
+
+    pub fn config(&self) -> Option<&Config> {
+        self.config.as_ref()
+    }
+
+    pub fn serialize_config(&mut self) -> Result<String, Error> {
+        if let Some(config) = &self.config {
+            let mut commands = String::new();
+            let serial = |value: Millimeter| value.to_serial().ok_or(Error::OutOfRange(value));
+
+            for (i, key) in config.hkeys.iter().enumerate() {
+                let idx = i + 1;
+                if let Some(rt) = &key.rt {
+                    write_serial!(
+                        commands;
+                        "hkey", idx, "rt",   1;
+                        "hkey", idx, "crt",  rt.continuos;
+                        "hkey", idx, "rtus", serial(rt.up_sensitivity)?;
+                        "hkey", idx, "rtds", serial(rt.down_sensitivity)?;
+                        "hkey", idx, "lh",   serial(key.hysterisis.lower)?;
+                        "hkey", idx, "rtuh", serial(key.hysterisis.upper)?;
+                        "hkey", idx, "char", key.keycode.to_serial();
+                        "hkey", idx, "hid",  key.hid
+                    );
+                } else {
+                    write_serial!(
+                        commands;
+                        "hkey", idx, "rt", 0
+                    );
+                }
+            }
+
+            log::debug!("{}", commands);
+            return Ok(commands);
+        }
+        Err(Error::Parse)
+    }
+
+    fn parse_config(raw_config: String) -> Result<Config, Error> {
+        // the get ouput looks something like this
+        // GET key=value
+        // GET END
+
+        // key -> prefix ?("." suffix)
+        // prefix -> (("h" | "d") "key" number) | string
+        // suffix & value -> string
+
+        // let mut port = self.port.lock().map_err(|_| Error::Read)?;
+
+        // port.open(serialport::Parity::Even)?;
+        // port.write("get\n")?;
+
+        let mut config = Config::default();
+
+        for line in raw_config.lines() {
+            let Some(line) = line.strip_prefix("GET ") else {
+                continue;
+            };
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+
+            if let Some((lhs, rhs)) = key.split_once('.') {
+                map_key_to_config(&mut config, lhs, rhs, value)?;
+            } else {
+                match key {
+                    "hkeys" => {
+                        let key_count = parse::<u16>(value)?;
+                        for _ in 0..key_count {
+                            config.hkeys.push(HKey::default());
+                        }
+                    }
+                    "dkeys" => {
+                        //TODO
+                    }
+                    _ => {
+                        config.firmware.insert(key.to_string(), value.to_string());
+                    }
+                };
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Send `get` and parse everything up to `GET END`
+    fn read_config(port: &mut Port) -> Result<Config, Error> {
+        // lines left over from an earlier answer would end up in this config
+        port.clear_input()?;
+        port.write("get\n")?;
+        let mut raw_config = String::new();
+        let deadline = Instant::now() + CONFIG_TIMEOUT;
+        'read: loop {
+            if Instant::now() >= deadline {
+                return Err(Error::Read);
+            }
+            for line in port.read_lines()? {
+                if line.contains("GET END") {
+                    break 'read;
+                }
+
+                log::debug!("{line}");
+                raw_config += &line;
+                raw_config += "\n";
+            }
+        }
+        Self::parse_config(raw_config)
+    }
+
+    /// Read one full `out` frame
+    ///
+    /// Waits until every key reported a value, or if the key count is not known yet
+    /// until the first batch of lines arrived
+    fn read_sensors(port: &mut Port, key_count: usize) -> Result<Vec<SensorData>, Error> {
+        port.write("out\n")?;
+        let deadline = Instant::now() + FRAME_TIMEOUT;
+        let mut result = Vec::with_capacity(key_count);
+        loop {
+            for line in port.read_lines()? {
+                let Some(line) = line.strip_prefix("OUT ") else {
+                    continue;
+                };
+                let Some((key, value)) = line.split_once('=') else {
+                    continue;
+                };
+                let Some((raw, mapped)) = value.split_once(' ') else {
+                    continue;
+                };
+
+                // keys are numbered from 1
+                let key = key
+                    .strip_prefix("hkey")
+                    .and_then(|index| index.parse::<usize>().ok()?.checked_sub(1));
+                let raw = raw.parse::<usize>();
+                let mapped = mapped.parse::<u16>();
+                if let (Some(key), Ok(raw), Ok(mapped)) = (key, raw, mapped) {
+                    result.push(SensorData {
+                        raw,
+                        mapped: Millimeter::from_serial(mapped),
+                        key,
+                    });
+                }
+            }
+
+            let complete = if key_count == 0 {
+                !result.is_empty()
+            } else {
+                result.len() >= key_count
+            };
+            if complete || Instant::now() >= deadline {
+                return Ok(result);
+            }
+        }
+    }
+
+    /// Next event of the event loop, fails when there is none waiting
+    ///
+    /// Answers to checks queued before the last write are dropped,
+    /// the config they read is already outdated
+    pub fn recv_data(&mut self) -> Result<Event, Error> {
+        let Some(data_receiver) = &mut self.data_receiver else {
+            return Err(Error::Read);
+        };
+        loop {
+            let Ok(data) = data_receiver.try_recv() else {
+                return Err(Error::Read);
+            };
+            if !matches!(data, Event::CheckedConfig(_) | Event::CheckFailed(_)) {
+                return Ok(data);
+            }
+            self.pending_checks = self.pending_checks.saturating_sub(1);
+            if self.stale_checks == 0 {
+                return Ok(data);
+            }
+            self.stale_checks -= 1;
+        }
+    }
+
+    /// Queue a command for the event loop
+    pub fn send_event(&mut self, event: SendEvent) -> Result<(), Error> {
+        let Some(event_sender) = &mut self.event_sender else {
+            return Err(Error::Send);
+        };
+        let check = event == SendEvent::CheckConfig;
+        let write = matches!(event, SendEvent::SendCommands(_) | SendEvent::Reset);
+        event_sender.send(event).map_err(|_| Error::Send)?;
+        if check {
+            self.pending_checks += 1;
+        } else if write {
+            self.stale_checks = self.pending_checks;
+        }
+        Ok(())
+    }
+
+    /// Open the port on a background thread, does nothing if it is running already
+    ///
+    /// [`Event::Init`] is sent once the port is open
+    pub fn spawn_event_loop(&mut self) -> Result<(), Error> {
+        if self.data_receiver.is_some() || self.event_sender.is_some() {
+            return Ok(());
+        }
+        let (data_sender, data_receiver) = std::sync::mpsc::channel::<Event>();
+        let (event_sender, event_receiver) = std::sync::mpsc::channel::<SendEvent>();
+        self.data_receiver = Some(data_receiver);
+        self.event_sender = Some(event_sender);
+
+        let port = self.port.clone();
+        let serial_settings = self.serial_settings.clone();
+
+        std::thread::spawn(move || {
+            if let Err(e) = Self::event_loop(&port, &serial_settings, &data_sender, event_receiver)
+            {
+                log::error!("device event loop stopped: {e}");
+                let _ = data_sender.send(Event::Error(e));
+            }
+        });
+        Ok(())
+    }
+
+    /// If the event loop is running
+    pub fn is_connected(&self) -> bool {
+        self.event_sender.is_some()
+    }
+
+    /// Stop the event loop, it gets spawned again on the next [`Device::spawn_event_loop`]
+    pub fn restart_event_loop(&mut self) {
+        self.data_receiver = None;
+        self.event_sender = None;
+        self.pending_checks = 0;
+        self.stale_checks = 0;
+    }
+
+    fn event_loop(
+        port: &Mutex<Port>,
+        serial_settings: &SerialSettings,
+        data_sender: &Sender<Event>,
+        event_receiver: Receiver<SendEvent>,
+    ) -> Result<(), Error> {
+        let mut port = port.lock().map_err(|_| Error::Read)?;
+
+        let mut read_sensors = false;
+        let mut key_count = 0;
+        let mut poller = poll::Poller::new(poll::DEFAULT_RATE);
+        port.transport.configure(serial_settings)?;
+        port.transport.open()?;
+        data_sender.send(Event::Init).map_err(|_| Error::Send)?;
+
+        loop {
+            if read_sensors && poller.is_due() {
+                let started = Instant::now();
+                let sensor_data = Self::read_sensors(&mut port, key_count)?;
+                poller.record(started);
+
+                for data in sensor_data {
+                    let _ = data_sender
+                        .send(Event::Sensor(data))
+                        .map_err(|_| Error::Send);
+                }
+                if let Some(stats) = poller.report() {
+                    let _ = data_sender.send(Event::PollStats(stats));
+                }
+            }
+
+            let timeout = if read_sensors {
+                poller.time_until_next()
+            } else {
+                IDLE_TIMEOUT
+            };
+            let event = match event_receiver.recv_timeout(timeout) {
+                Ok(event) => event,
+                Err(RecvTimeoutError::Timeout) => continue,
+                // the device was dropped or the event loop restarted
+                Err(RecvTimeoutError::Disconnected) => return Ok(()),
+            };
+
+            match event {
+                SendEvent::SendCommands(cmds) => {
+                    port.write(cmds).map_err(|_| Error::Send)?;
+                }
+                SendEvent::Reset => {
+                    port.write("reset\n").map_err(|_| Error::Send)?;
+                }
+                SendEvent::ReadSensorsBegin => read_sensors = true,
+                SendEvent::ReadSensorsEnd => read_sensors = false,
+                SendEvent::SetPollingRate(rate) => poller.set_rate(rate),
+                SendEvent::Configure(settings) => port.transport.configure(&settings)?,
+                SendEvent::ReadConfig => {
+                    let config = Self::read_config(&mut port)?;
+                    key_count = config.hkeys.len();
+                    data_sender
+                        .send(Event::Config(config))
+                        .map_err(|_| Error::Send)?;
+                }
+                SendEvent::CheckConfig => {
+                    // a check that gets no answer is tried again later, the port still works
+                    let event = match Self::read_config(&mut port) {
+                        Ok(config) => {
+                            key_count = config.hkeys.len();
+                            Event::CheckedConfig(config)
+                        }
+                        Err(e @ (Error::Read | Error::Parse)) => Event::CheckFailed(e),
+                        Err(e) => return Err(e),
+                    };
+                    data_sender.send(event).map_err(|_| Error::Send)?;
+                }
+            }
+        }
+    }
+}
+
+/// Command for the event loop of a device
+#[derive(Debug, PartialEq, Eq)]
+pub enum SendEvent {
+    /// send commands without a return value
+    SendCommands(String),
+    ReadSensorsBegin,
+    ReadSensorsEnd,
+    /// target sensor sample rate in Hz
+    SetPollingRate(u32),
+    /// reopen the port with new serial settings
+    Configure(SerialSettings),
+    ReadConfig,
+    /// read the config to look for changes made outside of crustility
+    CheckConfig,
+    /// restore the firmware defaults
+    Reset,
+}
+
+/// What the event loop of a device reports
+#[derive(Debug)]
+pub enum Event {
+    /// the port was opened
+    Init,
+    Sensor(SensorData),
+    PollStats(PollStats),
+    Config(Config),
+    /// answer to [`SendEvent::CheckConfig`]
+    CheckedConfig(Config),
+    /// [`SendEvent::CheckConfig`] got no valid config, the event loop keeps running
+    CheckFailed(Error),
+    /// the event loop stopped because of an error
+    Error(Error),
+}
+
+/// Reading of one hall effect sensor
+#[derive(Debug, Clone, PartialEq)]
+pub struct SensorData {
+    /// adc value
+    pub raw: usize,
+    /// distance from the bottom
+    pub mapped: Millimeter,
+    /// index of the hall effect key
+    pub key: usize,
+}
+
+fn parse<T: std::str::FromStr>(value: &str) -> Result<T, Error> {
+    value.parse().map_err(|_| Error::Parse)
+}
+
+/// Set one `hkeyN.setting` of the get output, the key has to be counted by `hkeys` already
+fn map_key_to_config(
+    config: &mut Config,
+    key_prefix: &str,
+    key_suffix: &str,
+    value: &str,
+) -> Result<(), Error> {
+    let Some(index) = key_prefix.strip_prefix("hkey") else {
+        //TODO: digital keys
+        return Ok(());
+    };
+    let key = parse::<usize>(index)?
+        .checked_sub(1)
+        .and_then(|index| config.hkeys.get_mut(index))
+        .ok_or(Error::Parse)?;
+    let distance = || parse::<u16>(value).map(Millimeter::from_serial);
+    match key_suffix {
+        "rt" => key.rt = (parse::<u32>(value)? == 1).then(config::RapidTrigger::default),
+        "crt" => {
+            let continuos = parse::<u16>(value)? == 1;
+            if let Some(rt) = &mut key.rt {
+                rt.continuos = continuos;
+            }
+        }
+        "rtus" => {
+            let sensitivity = distance()?;
+            if let Some(rt) = &mut key.rt {
+                rt.up_sensitivity = sensitivity;
+            }
+        }
+        "rtds" => {
+            let sensitivity = distance()?;
+            if let Some(rt) = &mut key.rt {
+                rt.down_sensitivity = sensitivity;
+            }
+        }
+        "uh" => key.hysterisis.upper = distance()?,
+        "lh" => key.hysterisis.lower = distance()?,
+        "char" => key.keycode = KeyCode::from_serial(parse::<u8>(value)?),
+        "rest" => key.rest = parse::<usize>(value)?,
+        "down" => key.down = parse::<usize>(value)?,
+        "hid" => key.hid = parse::<usize>(value)? == 1,
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Identifies a connected device in [`Devices`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DeviceHandle {
+    pid: u16,
+    vid: u16,
+    /// tells apart devices of the same model, kept while the device stays connected
+    instance: u16,
+}
+
+impl DeviceHandle {
+    #[cfg(test)]
+    pub(crate) fn new(vid: u16, pid: u16, instance: u16) -> Self {
+        Self { pid, vid, instance }
+    }
+
+    pub fn vid(&self) -> u16 {
+        self.vid
+    }
+    pub fn pid(&self) -> u16 {
+        self.pid
+    }
+    pub fn instance(&self) -> u16 {
+        self.instance
+    }
+    /// Stable name for the device, used to key per device files
+    ///
+    /// Devices of the same model share it
+    pub fn identity(&self) -> String {
+        format!("{:04x}:{:04x}", self.vid, self.pid)
+    }
+}
+
+/// The serial number tells devices of the same model apart, the port when there is none
+fn storage_identity(handle: &DeviceHandle, serial_number: Option<&str>, port_name: &str) -> String {
+    match serial_number.filter(|sn| !sn.is_empty()) {
+        Some(serial_number) => format!("{}:{serial_number}", handle.identity()),
+        None => format!("{}:{port_name}", handle.identity()),
+    }
+}
+
+/// Entry stored for a device, or the one of its model from before entries were per device
+fn stored<'a, T>(
+    store: &'a BTreeMap<String, T>,
+    handle: &DeviceHandle,
+    identity: &str,
+) -> Option<&'a T> {
+    store
+        .get(identity)
+        .or_else(|| store.get(&handle.identity()))
+}
+
+const SERIAL_SETTINGS_FILE: &str = "serial.toml";
+const BOARDS_FILE: &str = "layouts.toml";
+
+/// Serial settings for each device, keyed by [`Device::storage_identity`]
+///
+/// Older files are keyed by [`DeviceHandle::identity`], those still apply to the model
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+struct SerialSettingsStore(BTreeMap<String, SerialSettings>);
+
+/// Custom layouts for each device, keyed like [`SerialSettingsStore`]
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+struct BoardStore(BTreeMap<String, Board>);
+
+/// Connected devices, found with [`Devices::scan`]
+#[derive(Debug, Default)]
+pub struct Devices {
+    device_map: IndexMap<DeviceHandle, Device>,
+}
+
+impl Devices {
+    pub fn iter(&self) -> DevicesIterator<'_> {
+        DevicesIterator {
+            handles: self.device_map.keys(),
+        }
+    }
+
+    pub fn get_mut(&mut self, handle: &DeviceHandle) -> Option<&mut Device> {
+        self.device_map.get_mut(handle)
+    }
+
+    pub fn get(&self, handle: &DeviceHandle) -> Option<&Device> {
+        self.device_map.get(handle)
+    }
+
+    /// Name the files keep a device under, its model's when it is not connected
+    fn storage_identity(&self, handle: &DeviceHandle) -> String {
+        self.device_map.get(handle).map_or_else(
+            || handle.identity(),
+            |device| device.storage_identity(handle),
+        )
+    }
+
+    /// Change and persist the serial settings of a device
+    pub fn set_serial_settings(
+        &mut self,
+        handle: &DeviceHandle,
+        settings: SerialSettings,
+    ) -> Result<(), Error> {
+        let mut store: SerialSettingsStore = storage::load(SERIAL_SETTINGS_FILE)?;
+        store
+            .0
+            .insert(self.storage_identity(handle), settings.clone());
+        storage::save(SERIAL_SETTINGS_FILE, &store)?;
+
+        if let Some(device) = self.device_map.get_mut(handle) {
+            device.set_serial_settings(settings)?;
+        }
+        Ok(())
+    }
+
+    /// Change and persist the layout of a device, `None` goes back to the generated one
+    ///
+    /// Going back also drops a layout stored for the whole model, it would apply again
+    /// on the next scan otherwise
+    pub fn set_board(&mut self, handle: &DeviceHandle, board: Option<Board>) -> Result<(), Error> {
+        let mut store: BoardStore = storage::load(BOARDS_FILE)?;
+        let identity = self.storage_identity(handle);
+        match &board {
+            Some(board) => {
+                store.0.insert(identity, board.clone());
+            }
+            None => {
+                store.0.remove(&identity);
+                store.0.remove(&handle.identity());
+            }
+        }
+        storage::save(BOARDS_FILE, &store)?;
+
+        if let Some(device) = self.device_map.get_mut(handle) {
+            device.board = board;
+        }
+        Ok(())
+    }
+
+    /// Look for devices on the usb serial ports
+    ///
+    /// Devices that are still connected keep their handle and state
+    pub fn scan(&mut self) {
+        let mut ports = serialport::available_ports().expect("No ports found!");
+        ports.sort_by(|a, b| a.port_name.cmp(&b.port_name));
+        let found = ports
+            .into_iter()
+            .filter_map(|p| match p.port_type {
+                serialport::SerialPortType::UsbPort(info) => Some(FoundPort {
+                    port_name: p.port_name,
+                    vid: info.vid,
+                    pid: info.pid,
+                    serial_number: info.serial_number.filter(|sn| !sn.is_empty()),
+                    manufacturer: info.manufacturer,
+                    product: info.product,
+                }),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let serial_settings: SerialSettingsStore = storage::load_or_default(SERIAL_SETTINGS_FILE);
+        let boards: BoardStore = storage::load_or_default(BOARDS_FILE);
+        self.update(found, &serial_settings, &boards);
+
+        #[cfg(debug_assertions)]
+        self.device_map.insert(
+            DeviceHandle {
+                pid: 0,
+                vid: 0,
+                instance: 0,
+            },
+            Device {
+                port: Arc::new(Mutex::new(Port::new(dummy_firmware(3)))),
+                port_name: "<memory>".to_string(),
+                serial_settings: SerialSettings::default(),
+                name: "<dummy>".to_string(),
+                manufacturer: None,
+                serial_number: None,
+                key_count: 3,
+                config: Some(Config {
+                    hkeys: vec![HKey::default(), HKey::default(), HKey::default()],
+                    ..Default::default()
+                }),
+                synced: None,
+                board: None,
+                poll_stats: None,
+                data_receiver: None,
+                event_sender: None,
+                pending_checks: 0,
+                stale_checks: 0,
+            },
+        );
+    }
+
+    /// Replace the devices with the ones found, reusing the state of those still connected
+    ///
+    /// A device is recognized by its serial number, or its port when it has none
+    fn update(
+        &mut self,
+        found: Vec<FoundPort>,
+        serial_settings: &SerialSettingsStore,
+        boards: &BoardStore,
+    ) {
+        let mut old_devices = std::mem::take(&mut self.device_map);
+        let reused = found
+            .iter()
+            .map(|port| {
+                let handle = old_devices
+                    .iter()
+                    .find(|(handle, device)| {
+                        handle.vid == port.vid
+                            && handle.pid == port.pid
+                            && match &port.serial_number {
+                                Some(serial_number) => {
+                                    device.serial_number.as_ref() == Some(serial_number)
+                                }
+                                None => {
+                                    device.serial_number.is_none()
+                                        && device.port_name == port.port_name
+                                }
+                            }
+                    })
+                    .map(|(handle, _)| *handle)?;
+                old_devices.shift_remove_entry(&handle)
+            })
+            .collect::<Vec<_>>();
+        let mut taken = reused
+            .iter()
+            .flatten()
+            .map(|(handle, _)| *handle)
+            .collect::<HashSet<_>>();
+
+        for (port, old) in found.into_iter().zip(reused) {
+            let handle = match &old {
+                Some((handle, _)) => *handle,
+                None => {
+                    let mut handle = DeviceHandle {
+                        pid: port.pid,
+                        vid: port.vid,
+                        instance: 0,
+                    };
+                    while taken.contains(&handle) {
+                        handle.instance += 1;
+                    }
+                    taken.insert(handle);
+                    handle
+                }
+            };
+            let identity =
+                storage_identity(&handle, port.serial_number.as_deref(), &port.port_name);
+            let settings = stored(&serial_settings.0, &handle, &identity)
+                .cloned()
+                .unwrap_or_default();
+            let board = stored(&boards.0, &handle, &identity).cloned();
+            let new_port = || {
+                Arc::new(Mutex::new(Port::new(SerialTransport::new(
+                    port.port_name.clone(),
+                    settings.clone(),
+                ))))
+            };
+
+            let device = match old {
+                Some((_, mut device)) => {
+                    if device.port_name != port.port_name {
+                        // the event loop still holds the old port
+                        device.restart_event_loop();
+                        device.port = new_port();
+                        device.port_name = port.port_name;
+                    }
+                    device.name = port.product.unwrap_or_default();
+                    device.manufacturer = port.manufacturer;
+                    device.serial_number = port.serial_number;
+                    device.serial_settings = settings;
+                    device.board = board;
+                    device
+                }
+                None => Device {
+                    port: new_port(),
+                    port_name: port.port_name,
+                    serial_settings: settings,
+                    name: port.product.unwrap_or_default(),
+                    manufacturer: port.manufacturer,
+                    serial_number: port.serial_number,
+                    config: None,
+                    synced: None,
+                    board,
+                    key_count: 0,
+                    poll_stats: None,
+                    data_receiver: None,
+                    event_sender: None,
+                    pending_checks: 0,
+                    stale_checks: 0,
+                },
+            };
+            self.device_map.insert(handle, device);
+        }
+    }
+}
+
+/// Usb serial port a device was found on
+#[derive(Debug, Clone)]
+struct FoundPort {
+    port_name: String,
+    vid: u16,
+    pid: u16,
+    serial_number: Option<String>,
+    manufacturer: Option<String>,
+    product: Option<String>,
+}
+
+/// In memory device answering like a minipad with `key_count` hall effect keys
+#[cfg(any(test, debug_assertions))]
+fn dummy_firmware(key_count: usize) -> MemoryTransport {
+    MemoryTransport::with_responder(move |command| {
+        let mut out = String::new();
+        match command {
+            "get" => {
+                out += &format!("GET hkeys={key_count}\nGET dkeys=0\n");
+                for (i, char) in b"zxc".iter().cycle().take(key_count).enumerate() {
+                    let idx = i + 1;
+                    out += &format!(
+                        "GET hkey{idx}.rt=1\nGET hkey{idx}.crt=0\nGET hkey{idx}.rtus=10\n\
+                         GET hkey{idx}.rtds=10\nGET hkey{idx}.lh=290\nGET hkey{idx}.uh=310\n\
+                         GET hkey{idx}.char={char}\nGET hkey{idx}.hid=1\n"
+                    );
+                }
+                out += "GET END\n";
+            }
+            "out" => {
+                for i in 0..key_count {
+                    out += &format!("OUT hkey{}={} {}\n", i + 1, 500 * i, 200 * i);
+                }
+            }
+            _ => (),
+        }
+        out
+    })
+}
+
+/// Handles of the connected devices
+pub struct DevicesIterator<'a> {
+    handles: indexmap::map::Keys<'a, DeviceHandle, Device>,
+}
+
+impl<'a> Iterator for DevicesIterator<'a> {
+    type Item = &'a DeviceHandle;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.handles.next()
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Serial(#[from] serialport::Error),
+    #[error("serial port io")]
+    Io(#[from] std::io::Error),
+
+    #[error("could not read from the serial port")]
+    Read,
+
+    #[error("no permission to open {0}")]
+    PermissionDenied(String),
+
+    #[error("could not send the value")]
+    Send,
+
+    #[error("error parsing config")]
+    Parse,
+
+    #[error("{0} can't be sent to the device")]
+    OutOfRange(Millimeter),
+
+    #[error(transparent)]
+    Storage(#[from] storage::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn memory_device(transport: MemoryTransport) -> Device {
+        Device {
+            port: Arc::new(Mutex::new(Port::new(transport))),
+            port_name: "<memory>".to_string(),
+            serial_settings: SerialSettings::default(),
+            name: "<test>".to_string(),
+            manufacturer: None,
+            serial_number: None,
+            key_count: 0,
+            config: None,
+            synced: None,
+            board: None,
+            poll_stats: None,
+            data_receiver: None,
+            event_sender: None,
+            pending_checks: 0,
+            stale_checks: 0,
+        }
+    }
+
+    /// Next event of the event loop, failing the test if none arrives in time
+    fn recv(device: &mut Device) -> Event {
+        let deadline = Instant::now() + CONFIG_TIMEOUT * 2;
+        loop {
+            if let Ok(event) = device.recv_data() {
+                return event;
+            }
+            assert!(Instant::now() < deadline, "the event loop sent nothing");
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    fn connect(transport: MemoryTransport) -> Device {
+        let mut device = memory_device(transport);
+        device.spawn_event_loop().unwrap();
+        assert!(matches!(recv(&mut device), Event::Init));
+        device
+    }
+
+    #[test]
+    fn parse_get_output() {
+        let config = Device::parse_config(
+            "GET hkeys=2\nGET dkeys=0\nGET version=1.0\n\
+             GET hkey1.rt=1\nGET hkey1.crt=1\nGET hkey1.rtus=15\nGET hkey1.rtds=20\n\
+             GET hkey1.lh=290\nGET hkey1.uh=310\nGET hkey1.char=122\nGET hkey1.hid=1\n\
+             GET hkey2.rt=0\nGET hkey2.hid=0\nnoise\n"
+                .to_string(),
+        )
+        .unwrap();
+        assert_eq!(config.hkeys.len(), 2);
+        assert_eq!(
+            config.firmware.get("version").map(String::as_str),
+            Some("1.0")
+        );
+        let rt = config.hkeys[0].rt.as_ref().unwrap();
+        assert!(rt.continuos);
+        assert_eq!(rt.up_sensitivity, Millimeter::from_serial(15));
+        assert_eq!(rt.down_sensitivity, Millimeter::from_serial(20));
+        assert_eq!(
+            config.hkeys[0].hysterisis.lower,
+            Millimeter::from_serial(290)
+        );
+        assert_eq!(
+            config.hkeys[0].hysterisis.upper,
+            Millimeter::from_serial(310)
+        );
+        assert!(config.hkeys[0].hid);
+        assert!(config.hkeys[1].rt.is_none());
+        assert!(!config.hkeys[1].hid);
+    }
+
+    #[test]
+    fn read_config_over_the_event_loop() {
+        let transport = dummy_firmware(3);
+        let mut device = connect(transport.clone());
+        device.send_event(SendEvent::ReadConfig).unwrap();
+        let Event::Config(config) = recv(&mut device) else {
+            panic!("expected a config");
+        };
+        assert_eq!(config.hkeys.len(), 3);
+        assert!(config.hkeys.iter().all(|key| key.rt.is_some() && key.hid));
+        assert_eq!(transport.take_written(), b"get\n");
+    }
+
+    #[test]
+    fn sensor_frames() {
+        let transport = dummy_firmware(3);
+        let mut device = connect(transport);
+        device.send_event(SendEvent::ReadConfig).unwrap();
+        assert!(matches!(recv(&mut device), Event::Config(_)));
+        device.send_event(SendEvent::ReadSensorsBegin).unwrap();
+
+        let mut seen = [false; 3];
+        while !seen.iter().all(|seen| *seen) {
+            if let Event::Sensor(data) = recv(&mut device) {
+                assert_eq!(data.raw, 500 * data.key);
+                assert_eq!(data.mapped, Millimeter::from_serial(200 * data.key as u16));
+                seen[data.key] = true;
+            }
+        }
+    }
+
+    #[test]
+    fn bad_sensor_lines_are_skipped() {
+        let transport = MemoryTransport::with_responder(|command| match command {
+            "out" => "OUT hkey0=1 1\nOUT dkey1=1 1\nOUT hk=1 1\nOUT hkeyx=1 1\n\
+                      OUT hkey1=500 200\nOUT hkey2=low 1\nOUT hkey2=1000 400\n"
+                .to_string(),
+            _ => String::new(),
+        });
+        let mut port = Port::new(transport);
+        port.transport.open().unwrap();
+        let data = Device::read_sensors(&mut port, 2).unwrap();
+        assert_eq!(
+            data.iter().map(|d| (d.key, d.raw)).collect::<Vec<_>>(),
+            [(0, 500), (1, 1000)]
+        );
+    }
+
+    #[test]
+    fn commands_are_written_as_is() {
+        let transport = dummy_firmware(1);
+        let mut device = connect(transport.clone());
+        device
+            .send_event(SendEvent::SendCommands("hkey1.rt 0\n".to_string()))
+            .unwrap();
+        // the event loop handles events in order, the config read waits for the command
+        device.send_event(SendEvent::ReadConfig).unwrap();
+        assert!(matches!(recv(&mut device), Event::Config(_)));
+        assert_eq!(transport.take_written(), b"hkey1.rt 0\nget\n");
+    }
+
+    #[test]
+    fn config_without_end_times_out() {
+        let transport = MemoryTransport::with_responder(|command| match command {
+            "get" => "GET hkeys=1\n".to_string(),
+            _ => String::new(),
+        });
+        let mut device = connect(transport);
+        device.send_event(SendEvent::ReadConfig).unwrap();
+        assert!(matches!(recv(&mut device), Event::Error(Error::Read)));
+    }
+
+    #[test]
+    fn storage_identity() {
+        let handle = DeviceHandle {
+            pid: 0x1234,
+            vid: 0xabcd,
+            instance: 0,
+        };
+        let mut device = memory_device(MemoryTransport::default());
+        device.port_name = "/dev/ttyACM0".to_string();
+        assert_eq!(device.storage_identity(&handle), "abcd:1234:/dev/ttyACM0");
+        device.serial_number = Some(String::new());
+        assert_eq!(device.storage_identity(&handle), "abcd:1234:/dev/ttyACM0");
+
+        device.serial_number = Some("A1".to_string());
+        let mut other = memory_device(MemoryTransport::default());
+        other.serial_number = Some("B2".to_string());
+        assert_eq!(device.storage_identity(&handle), "abcd:1234:A1");
+        assert_ne!(
+            device.storage_identity(&handle),
+            other.storage_identity(&handle)
+        );
+    }
+
+    #[test]
+    fn bad_get_output() {
+        for raw in [
+            "GET hkey1.rt=1\n",
+            "GET hkeys=1\nGET hkey0.rt=1\n",
+            "GET hkeys=1\nGET hkey2.rt=1\n",
+            "GET hkeys=1\nGET hkey.rt=1\n",
+            "GET hkeys=1\nGET hkey1.lh=low\n",
+            "GET hkeys=many\n",
+        ] {
+            assert!(
+                matches!(Device::parse_config(raw.to_string()), Err(Error::Parse)),
+                "{raw}"
+            );
+        }
+    }
+
+    #[test]
+    fn check_after_a_timed_out_answer() {
+        let mut gets = 0;
+        let transport = MemoryTransport::with_responder(move |command| {
+            if command != "get" {
+                return String::new();
+            }
+            gets += 1;
+            match gets {
+                // the answer stops halfway
+                1 => "GET hkeys=2\nGET hkey1.rt=1\n".to_string(),
+                _ => "GET hkeys=1\nGET hkey1.rt=0\nGET END\n".to_string(),
+            }
+        });
+        let mut device = connect(transport.clone());
+        device.send_event(SendEvent::CheckConfig).unwrap();
+        assert!(matches!(recv(&mut device), Event::CheckFailed(Error::Read)));
+
+        // the rest of the first answer arrives late
+        transport.push_incoming("GET hkey2.rt=1\nGET END\n");
+        device.send_event(SendEvent::CheckConfig).unwrap();
+        let Event::CheckedConfig(config) = recv(&mut device) else {
+            panic!("expected a config");
+        };
+        assert_eq!(config.hkeys.len(), 1);
+        assert!(config.hkeys[0].rt.is_none());
+    }
+
+    fn found(port_name: &str, serial_number: Option<&str>) -> FoundPort {
+        FoundPort {
+            port_name: port_name.to_string(),
+            vid: 0xabcd,
+            pid: 0x1234,
+            serial_number: serial_number.map(str::to_string),
+            manufacturer: None,
+            product: Some("minipad".to_string()),
+        }
+    }
+
+    fn update(devices: &mut Devices, found: Vec<FoundPort>) {
+        devices.update(
+            found,
+            &SerialSettingsStore::default(),
+            &BoardStore::default(),
+        );
+    }
+
+    /// Serial number and port of every device, by instance
+    fn devices_by_instance(devices: &Devices) -> Vec<(u16, Option<&str>, &str)> {
+        let mut list = devices
+            .iter()
+            .map(|handle| {
+                let device = devices.get(handle).unwrap();
+                (handle.instance, device.serial_number(), device.port_name())
+            })
+            .collect::<Vec<_>>();
+        list.sort();
+        list
+    }
+
+    #[test]
+    fn serial_settings_per_device() {
+        let settings = |baud_rate| SerialSettings {
+            baud_rate,
+            ..Default::default()
+        };
+        // the model's entry from before settings were kept per device
+        let store = SerialSettingsStore(BTreeMap::from([
+            ("abcd:1234".to_string(), settings(9600)),
+            ("abcd:1234:A1".to_string(), settings(57600)),
+            ("abcd:1234:/dev/ttyACM2".to_string(), settings(19200)),
+        ]));
+        let mut devices = Devices::default();
+        devices.update(
+            vec![
+                found("/dev/ttyACM0", Some("A1")),
+                found("/dev/ttyACM1", Some("B2")),
+                found("/dev/ttyACM2", None),
+            ],
+            &store,
+            &BoardStore::default(),
+        );
+
+        let mut baud_rates = devices
+            .iter()
+            .map(|handle| {
+                let device = devices.get(handle).unwrap();
+                (
+                    device.port_name.as_str(),
+                    device.serial_settings().baud_rate,
+                )
+            })
+            .collect::<Vec<_>>();
+        baud_rates.sort();
+        assert_eq!(
+            baud_rates,
+            [
+                ("/dev/ttyACM0", 57600),
+                ("/dev/ttyACM1", 9600),
+                ("/dev/ttyACM2", 19200)
+            ]
+        );
+    }
+
+    #[test]
+    fn scan_keeps_devices_by_serial_number() {
+        let mut devices = Devices::default();
+        update(
+            &mut devices,
+            vec![found("ttyACM0", Some("A")), found("ttyACM1", Some("B"))],
+        );
+        assert_eq!(
+            devices_by_instance(&devices),
+            [(0, Some("A"), "ttyACM0"), (1, Some("B"), "ttyACM1")]
+        );
+        let b = *devices.iter().nth(1).unwrap();
+        let device = devices.get_mut(&b).unwrap();
+        device.set_config(Config::default());
+        let (sender, _receiver) = std::sync::mpsc::channel();
+        device.event_sender = Some(sender);
+
+        // unplugging the first pad moves the second one to its port
+        update(&mut devices, vec![found("ttyACM0", Some("B"))]);
+        assert_eq!(devices_by_instance(&devices), [(1, Some("B"), "ttyACM0")]);
+        let device = devices.get(&b).unwrap();
+        assert!(device.config().is_some());
+        assert!(
+            !device.is_connected(),
+            "the loop of the old port keeps running"
+        );
+
+        update(
+            &mut devices,
+            vec![found("ttyACM0", Some("B")), found("ttyACM1", Some("A"))],
+        );
+        assert_eq!(
+            devices_by_instance(&devices),
+            [(0, Some("A"), "ttyACM1"), (1, Some("B"), "ttyACM0")]
+        );
+        assert!(devices.get(&b).unwrap().config().is_some());
+    }
+
+    #[test]
+    fn scan_keeps_devices_without_serial_number_by_port() {
+        let mut devices = Devices::default();
+        update(
+            &mut devices,
+            vec![found("ttyACM0", None), found("ttyACM1", None)],
+        );
+        let second = *devices.iter().nth(1).unwrap();
+        devices
+            .get_mut(&second)
+            .unwrap()
+            .set_config(Config::default());
+
+        update(&mut devices, vec![found("ttyACM1", None)]);
+        assert_eq!(devices_by_instance(&devices), [(1, None, "ttyACM1")]);
+        assert!(devices.get(&second).unwrap().config().is_some());
+
+        // another pad on a known port is a new device
+        update(&mut devices, vec![found("ttyACM0", None)]);
+        assert_eq!(devices_by_instance(&devices), [(0, None, "ttyACM0")]);
+        assert!(devices.get(&second).is_none());
+    }
+
+    #[test]
+    fn checks_before_a_write_are_dropped() {
+        let mut device = connect(dummy_firmware(1));
+        device.send_event(SendEvent::CheckConfig).unwrap();
+        device
+            .send_event(SendEvent::SendCommands("hkey1.rt 0\n".to_string()))
+            .unwrap();
+        device.send_event(SendEvent::CheckConfig).unwrap();
+        device.send_event(SendEvent::ReadConfig).unwrap();
+
+        let mut checks = 0;
+        loop {
+            match recv(&mut device) {
+                Event::CheckedConfig(_) => checks += 1,
+                Event::Config(_) => break,
+                event => panic!("unexpected {event:?}"),
+            }
+        }
+        assert_eq!(checks, 1);
+    }
+
+    #[test]
+    fn check_without_end_keeps_the_event_loop() {
+        let transport = MemoryTransport::with_responder(|command| match command {
+            "get" => "GET hkeys=1\n".to_string(),
+            _ => String::new(),
+        });
+        let mut device = connect(transport.clone());
+        device.send_event(SendEvent::CheckConfig).unwrap();
+        assert!(matches!(recv(&mut device), Event::CheckFailed(Error::Read)));
+
+        transport.take_written();
+        device
+            .send_event(SendEvent::SendCommands("hkey1.rt 0\n".to_string()))
+            .unwrap();
+        device.send_event(SendEvent::CheckConfig).unwrap();
+        assert!(matches!(recv(&mut device), Event::CheckFailed(Error::Read)));
+        assert_eq!(transport.take_written(), b"hkey1.rt 0\nget\n");
+    }
+}
@@ -0,0 +1,319 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_RATE: u32 = 100;
+pub const MIN_RATE: u32 = 1;
+pub const MAX_RATE: u32 = 1000;
+
+/// how many frames the latency and rate statistics are averaged over
+const WINDOW: usize = 50;
+/// consecutive late frames before the poll period is backed off
+const SLOW_FRAMES_LIMIT: u32 = 5;
+/// headroom given on top of the measured latency when throttling
+const THROTTLE_HEADROOM: f32 = 1.25;
+const REPORT_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Sensor polling statistics, reported periodically by the event loop
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct PollStats {
+    /// requested sample rate in Hz
+    pub target_rate: u32,
+    /// sample rate actually achieved in Hz
+    pub effective_rate: f32,
+    /// mean time between sending `out` and receiving the full frame
+    pub latency: Duration,
+    /// standard deviation of the latency
+    pub jitter: Duration,
+    /// the firmware could not keep up so the rate was lowered
+    pub throttled: bool,
+}
+
+/// Schedules `out` requests at a target rate
+///
+/// Backs off automatically when frames take longer than the poll period
+/// and recovers towards the target rate once the firmware catches up
+#[derive(Debug)]
+pub struct Poller {
+    target_rate: u32,
+    target_period: Duration,
+    period: Duration,
+    last_poll: Option<Instant>,
+    latencies: VecDeque<Duration>,
+    frames: VecDeque<Instant>,
+    slow_frames: u32,
+    last_report: Instant,
+}
+
+impl Poller {
+    pub fn new(rate: u32) -> Self {
+        let rate = rate.clamp(MIN_RATE, MAX_RATE);
+        let period = Self::period_of(rate);
+        Self {
+            target_rate: rate,
+            target_period: period,
+            period,
+            last_poll: None,
+            latencies: VecDeque::with_capacity(WINDOW),
+            frames: VecDeque::with_capacity(WINDOW),
+            slow_frames: 0,
+            last_report: Instant::now(),
+        }
+    }
+
+    pub fn set_rate(&mut self, rate: u32) {
+        *self = Self::new(rate);
+    }
+
+    fn period_of(rate: u32) -> Duration {
+        Duration::from_secs(1) / rate
+    }
+
+    pub fn is_due(&self) -> bool {
+        self.time_until_next().is_zero()
+    }
+
+    /// time left until the next frame should be requested
+    pub fn time_until_next(&self) -> Duration {
+        self.time_until_next_at(Instant::now())
+    }
+
+    fn time_until_next_at(&self, now: Instant) -> Duration {
+        match self.last_poll {
+            Some(last) => self
+                .period
+                .saturating_sub(now.saturating_duration_since(last)),
+            None => Duration::ZERO,
+        }
+    }
+
+    /// record a completed frame that was requested at `started`
+    pub fn record(&mut self, started: Instant) {
+        self.record_at(started, Instant::now());
+    }
+
+    fn record_at(&mut self, started: Instant, now: Instant) {
+        let latency = now.saturating_duration_since(started);
+        self.last_poll = Some(started);
+
+        if self.latencies.len() == WINDOW {
+            self.latencies.pop_front();
+        }
+        self.latencies.push_back(latency);
+        if self.frames.len() == WINDOW {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(started);
+
+        self.throttle(latency);
+    }
+
+    fn throttle(&mut self, latency: Duration) {
+        if latency > self.period {
+            self.slow_frames += 1;
+        } else {
+            self.slow_frames = 0;
+        }
+
+        let needed = self.mean_latency().mul_f32(THROTTLE_HEADROOM);
+        if self.slow_frames >= SLOW_FRAMES_LIMIT {
+            self.slow_frames = 0;
+            if needed > self.period {
+                log::warn!(
+                    "device can't keep up with {} Hz, throttling to {:.0} Hz",
+                    self.target_rate,
+                    1. / needed.as_secs_f32()
+                );
+                self.period = needed;
+            }
+        } else if self.period > self.target_period && needed < self.period {
+            // ease back towards the target rate
+            self.period = self.period.mul_f32(0.9).max(self.target_period);
+        }
+    }
+
+    fn mean_latency(&self) -> Duration {
+        if self.latencies.is_empty() {
+            return Duration::ZERO;
+        }
+        self.latencies.iter().sum::<Duration>() / self.latencies.len() as u32
+    }
+
+    fn jitter(&self) -> Duration {
+        if self.latencies.len() < 2 {
+            return Duration::ZERO;
+        }
+        let mean = self.mean_latency().as_secs_f32();
+        let variance = self
+            .latencies
+            .iter()
+            .map(|l| (l.as_secs_f32() - mean).powi(2))
+            .sum::<f32>()
+            / self.latencies.len() as f32;
+        Duration::from_secs_f32(variance.sqrt())
+    }
+
+    fn effective_rate(&self) -> f32 {
+        let (Some(first), Some(last)) = (self.frames.front(), self.frames.back()) else {
+            return 0.;
+        };
+        let elapsed = last.duration_since(*first).as_secs_f32();
+        if elapsed <= 0. {
+            return 0.;
+        }
+        (self.frames.len() - 1) as f32 / elapsed
+    }
+
+    /// statistics for the last frames, at most once per report interval
+    pub fn report(&mut self) -> Option<PollStats> {
+        self.report_at(Instant::now())
+    }
+
+    fn report_at(&mut self, now: Instant) -> Option<PollStats> {
+        if now.saturating_duration_since(self.last_report) < REPORT_INTERVAL {
+            return None;
+        }
+        self.last_report = now;
+
+        Some(PollStats {
+            target_rate: self.target_rate,
+            effective_rate: self.effective_rate(),
+            latency: self.mean_latency(),
+            jitter: self.jitter(),
+            throttled: self.period > self.target_period,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Time that only moves when a test says so
+    struct Clock(Instant);
+
+    impl Clock {
+        /// starts after the poller was created, which reads the real clock
+        fn new() -> Self {
+            Self(Instant::now())
+        }
+
+        fn advance(&mut self, micros: u64) {
+            self.0 += Duration::from_micros(micros);
+        }
+
+        /// let a frame take `latency` microseconds and record it
+        fn frame(&mut self, poller: &mut Poller, latency: u64) {
+            let started = self.0;
+            self.advance(latency);
+            poller.record_at(started, self.0);
+        }
+    }
+
+    fn micros(duration: Duration) -> u128 {
+        duration.as_micros()
+    }
+
+    #[test]
+    fn schedules_at_the_rate() {
+        let mut poller = Poller::new(100);
+        let mut clock = Clock::new();
+        assert!(poller.time_until_next_at(clock.0).is_zero());
+
+        clock.frame(&mut poller, 4_000);
+        assert_eq!(micros(poller.time_until_next_at(clock.0)), 6_000);
+        clock.advance(6_000);
+        assert!(poller.time_until_next_at(clock.0).is_zero());
+    }
+
+    #[test]
+    fn throttles_after_repeated_late_frames() {
+        let mut poller = Poller::new(1000);
+        let mut clock = Clock::new();
+
+        // a few late frames are not enough
+        for _ in 0..SLOW_FRAMES_LIMIT - 1 {
+            clock.frame(&mut poller, 4_000);
+        }
+        clock.frame(&mut poller, 500);
+        assert_eq!(poller.period, poller.target_period);
+
+        for _ in 0..SLOW_FRAMES_LIMIT {
+            clock.frame(&mut poller, 4_000);
+        }
+        // the mean latency with headroom
+        let needed = poller.mean_latency().mul_f32(THROTTLE_HEADROOM);
+        assert!(poller.period > poller.target_period);
+        assert_eq!(micros(poller.period), micros(needed));
+
+        clock.advance(micros(REPORT_INTERVAL) as u64);
+        let stats = poller.report_at(clock.0).unwrap();
+        assert!(stats.throttled);
+        assert_eq!(stats.target_rate, 1000);
+    }
+
+    #[test]
+    fn recovers_once_frames_are_fast_again() {
+        let mut poller = Poller::new(1000);
+        let mut clock = Clock::new();
+        for _ in 0..SLOW_FRAMES_LIMIT {
+            clock.frame(&mut poller, 4_000);
+        }
+        let throttled = poller.period;
+        assert!(throttled > poller.target_period);
+
+        clock.frame(&mut poller, 500);
+        assert!(poller.period < throttled);
+        for _ in 0..WINDOW {
+            clock.frame(&mut poller, 500);
+        }
+        assert_eq!(poller.period, poller.target_period);
+
+        clock.advance(micros(REPORT_INTERVAL) as u64);
+        assert!(!poller.report_at(clock.0).unwrap().throttled);
+    }
+
+    #[test]
+    fn latency_jitter_and_rate() {
+        let mut poller = Poller::new(100);
+        let mut clock = Clock::new();
+        for i in 0..10 {
+            // frames start every 10ms and take 1ms or 3ms
+            let latency = if i % 2 == 0 { 1_000 } else { 3_000 };
+            clock.frame(&mut poller, latency);
+            clock.advance(10_000 - latency);
+        }
+
+        clock.advance(micros(REPORT_INTERVAL) as u64);
+        let stats = poller.report_at(clock.0).unwrap();
+        assert_eq!(micros(stats.latency), 2_000);
+        assert!(
+            micros(stats.jitter).abs_diff(1_000) <= 1,
+            "{:?}",
+            stats.jitter
+        );
+        assert!(
+            (stats.effective_rate - 100.).abs() < 0.01,
+            "{}",
+            stats.effective_rate
+        );
+        assert!(!stats.throttled);
+    }
+
+    #[test]
+    fn reports_once_per_interval() {
+        let mut poller = Poller::new(100);
+        let mut clock = Clock::new();
+        clock.advance(micros(REPORT_INTERVAL) as u64);
+        assert!(poller.report_at(clock.0).is_some());
+        clock.advance(micros(REPORT_INTERVAL) as u64 - 1);
+        assert!(poller.report_at(clock.0).is_none());
+        clock.advance(1);
+        assert!(poller.report_at(clock.0).is_some());
+    }
+
+    #[test]
+    fn rate_is_clamped() {
+        assert_eq!(Poller::new(0).target_rate, MIN_RATE);
+        assert_eq!(Poller::new(5000).target_rate, MAX_RATE);
+    }
+}
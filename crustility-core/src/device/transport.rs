@@ -0,0 +1,328 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use super::Error;
+
+/// Byte stream a [`super::Device`] talks the minipad protocol over
+pub trait Transport: Send + std::fmt::Debug {
+    /// Opens the transport if its not already open
+    fn open(&mut self) -> Result<(), Error>;
+
+    fn close(&mut self);
+
+    fn write(&mut self, data: &[u8]) -> Result<(), Error>;
+
+    /// Read whatever is available into `buf`
+    ///
+    /// Returns `Ok(0)` if nothing arrived before the read timed out
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error>;
+
+    /// Drop whatever arrived but was not read yet
+    fn clear_input(&mut self) -> Result<(), Error>;
+
+    /// Apply new serial settings, reopening the transport if needed
+    ///
+    /// Transports that don't have serial settings ignore this
+    fn configure(&mut self, _settings: &SerialSettings) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// How the serial port gets opened
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SerialSettings {
+    pub baud_rate: u32,
+    pub flow_control: FlowControl,
+    pub parity: Parity,
+    pub data_bits: DataBits,
+    pub stop_bits: StopBits,
+    /// read timeout in milliseconds
+    pub timeout: u64,
+}
+
+impl Default for SerialSettings {
+    fn default() -> Self {
+        Self {
+            baud_rate: 115_200,
+            flow_control: FlowControl::Hardware,
+            parity: Parity::Even,
+            data_bits: DataBits::Eight,
+            stop_bits: StopBits::One,
+            timeout: 200,
+        }
+    }
+}
+
+pub const BAUD_RATES: [u32; 8] = [
+    9600, 19_200, 38_400, 57_600, 115_200, 230_400, 460_800, 921_600,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FlowControl {
+    None,
+    Software,
+    Hardware,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Parity {
+    None,
+    Odd,
+    Even,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DataBits {
+    Five,
+    Six,
+    Seven,
+    Eight,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StopBits {
+    One,
+    Two,
+}
+
+impl From<FlowControl> for serialport::FlowControl {
+    fn from(value: FlowControl) -> Self {
+        match value {
+            FlowControl::None => Self::None,
+            FlowControl::Software => Self::Software,
+            FlowControl::Hardware => Self::Hardware,
+        }
+    }
+}
+
+impl From<Parity> for serialport::Parity {
+    fn from(value: Parity) -> Self {
+        match value {
+            Parity::None => Self::None,
+            Parity::Odd => Self::Odd,
+            Parity::Even => Self::Even,
+        }
+    }
+}
+
+impl From<DataBits> for serialport::DataBits {
+    fn from(value: DataBits) -> Self {
+        match value {
+            DataBits::Five => Self::Five,
+            DataBits::Six => Self::Six,
+            DataBits::Seven => Self::Seven,
+            DataBits::Eight => Self::Eight,
+        }
+    }
+}
+
+impl From<StopBits> for serialport::StopBits {
+    fn from(value: StopBits) -> Self {
+        match value {
+            StopBits::One => Self::One,
+            StopBits::Two => Self::Two,
+        }
+    }
+}
+
+/// Serial port transport
+#[derive(Debug)]
+pub struct SerialTransport {
+    port: Option<Box<dyn serialport::SerialPort>>,
+    port_name: String,
+    settings: SerialSettings,
+}
+
+impl SerialTransport {
+    pub fn new(port_name: String, settings: SerialSettings) -> Self {
+        Self {
+            port: None,
+            port_name,
+            settings,
+        }
+    }
+}
+
+impl Transport for SerialTransport {
+    fn open(&mut self) -> Result<(), Error> {
+        if self.port.is_none() {
+            let port = serialport::new(self.port_name.clone(), self.settings.baud_rate)
+                .timeout(Duration::from_millis(self.settings.timeout))
+                .flow_control(self.settings.flow_control.into())
+                .parity(self.settings.parity.into())
+                .data_bits(self.settings.data_bits.into())
+                .stop_bits(self.settings.stop_bits.into())
+                .open()
+                .map_err(|e| match e.kind() {
+                    serialport::ErrorKind::Io(std::io::ErrorKind::PermissionDenied) => {
+                        Error::PermissionDenied(self.port_name.clone())
+                    }
+                    _ => e.into(),
+                })?;
+
+            self.port = Some(port);
+        }
+
+        Ok(())
+    }
+
+    fn close(&mut self) {
+        self.port = None;
+    }
+
+    fn write(&mut self, data: &[u8]) -> Result<(), Error> {
+        if let Some(port) = &mut self.port {
+            port.write_all(data)?;
+        }
+        Ok(())
+    }
+
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        let Some(port) = &mut self.port else {
+            return Err(Error::Read);
+        };
+        match port.read(buf) {
+            Ok(t) => Ok(t),
+            Err(ref e) if e.kind() == std::io::ErrorKind::TimedOut => Ok(0),
+            Err(_) => Err(Error::Read),
+        }
+    }
+
+    fn clear_input(&mut self) -> Result<(), Error> {
+        if let Some(port) = &self.port {
+            port.clear(serialport::ClearBuffer::Input)?;
+        }
+        Ok(())
+    }
+
+    fn configure(&mut self, settings: &SerialSettings) -> Result<(), Error> {
+        if &self.settings == settings {
+            return Ok(());
+        }
+        self.settings = settings.clone();
+        if self.port.is_some() {
+            self.close();
+            self.open()?;
+        }
+        Ok(())
+    }
+}
+
+type Responder = Box<dyn FnMut(&str) -> String + Send>;
+
+/// In memory transport
+///
+/// Every line written is passed to the responder and its answer is queued up to be read,
+/// the buffers are shared between clones so the other end can be inspected
+#[derive(Clone, Default)]
+pub struct MemoryTransport {
+    buffers: Arc<Mutex<MemoryBuffers>>,
+}
+
+#[derive(Default)]
+struct MemoryBuffers {
+    open: bool,
+    /// everything written to the transport
+    written: Vec<u8>,
+    /// unfinished line that was not passed to the responder yet
+    line: String,
+    incoming: VecDeque<u8>,
+    responder: Option<Responder>,
+}
+
+impl std::fmt::Debug for MemoryTransport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MemoryTransport").finish_non_exhaustive()
+    }
+}
+
+impl MemoryTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_responder(responder: impl FnMut(&str) -> String + Send + 'static) -> Self {
+        let transport = Self::default();
+        transport.lock().responder = Some(Box::new(responder));
+        transport
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, MemoryBuffers> {
+        // a panic while holding the lock leaves the buffers in a usable state
+        self.buffers.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// queue data to be read from the transport
+    pub fn push_incoming(&self, data: impl AsRef<[u8]>) {
+        self.lock().incoming.extend(data.as_ref());
+    }
+
+    /// take everything written to the transport so far
+    pub fn take_written(&self) -> Vec<u8> {
+        std::mem::take(&mut self.lock().written)
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.lock().open
+    }
+}
+
+impl Transport for MemoryTransport {
+    fn open(&mut self) -> Result<(), Error> {
+        self.lock().open = true;
+        Ok(())
+    }
+
+    fn close(&mut self) {
+        self.lock().open = false;
+    }
+
+    fn write(&mut self, data: &[u8]) -> Result<(), Error> {
+        let mut buffers = self.lock();
+        if !buffers.open {
+            return Ok(());
+        }
+        buffers.written.extend_from_slice(data);
+        buffers.line += &String::from_utf8_lossy(data);
+
+        while let Some(end) = buffers.line.find('\n') {
+            let line: String = buffers.line.drain(..=end).collect();
+            let MemoryBuffers {
+                responder,
+                incoming,
+                ..
+            } = &mut *buffers;
+            if let Some(responder) = responder {
+                incoming.extend(responder(line.trim_end()).as_bytes());
+            }
+        }
+        Ok(())
+    }
+
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        let mut buffers = self.lock();
+        if !buffers.open {
+            return Err(Error::Read);
+        }
+        if buffers.incoming.is_empty() {
+            // behave like a serial port read timing out instead of spinning
+            drop(buffers);
+            std::thread::sleep(Duration::from_millis(1));
+            return Ok(0);
+        }
+        let len = buf.len().min(buffers.incoming.len());
+        for (dst, src) in buf.iter_mut().zip(buffers.incoming.drain(..len)) {
+            *dst = src;
+        }
+        Ok(len)
+    }
+
+    fn clear_input(&mut self) -> Result<(), Error> {
+        self.lock().incoming.clear();
+        Ok(())
+    }
+}
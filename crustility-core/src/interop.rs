@@ -0,0 +1,492 @@
+use std::collections::BTreeMap;
+
+use serde_json::{json, Map, Value};
+use thiserror::Error;
+
+use crate::config::{Config, HKey, Hysterisis, Millimeter, RapidTrigger};
+use crate::keycode::KeyCode;
+
+/// Format the key settings are imported from and exported to
+///
+/// Both use the setting names and units of the firmware, so they carry everything
+/// a minipad stores and nothing crustility adds on top
+///
+/// Files saved by the official minipad utility are not read: their format is not
+/// documented, and a guessed importer would quietly get settings wrong. Settings on a
+/// minipad configured with it are read from the device itself, and scripts around
+/// it already speak the serial commands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Format {
+    /// the commands the firmware takes over serial, one `hkey1.lh 290` per line
+    ///
+    /// Imports also take the output of `get`, `GET hkey1.lh=290`, as another tool logs it
+    Commands,
+    /// crustility's own json form of the commands, for scripts and other tools
+    ///
+    /// `{"hkeys": [{"rt": 1, "lh": 290, ...}]}` with the firmware's names and units
+    Json,
+}
+
+impl Format {
+    pub const ALL: [Format; 2] = [Format::Commands, Format::Json];
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Commands => "txt",
+            Format::Json => "json",
+        }
+    }
+
+    /// Guess the format of a file from its content
+    pub fn detect(raw: &str) -> Self {
+        if raw.trim_start().starts_with('{') {
+            Format::Json
+        } else {
+            Format::Commands
+        }
+    }
+}
+
+/// Setting that was left out while translating
+#[derive(Debug, Clone, PartialEq)]
+pub struct Untranslated {
+    /// name of the setting in the other format, like `hkey2.rest`
+    pub field: String,
+    pub value: String,
+    pub reason: Reason,
+}
+
+/// Why a setting was left out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reason {
+    /// digital keys have no settings yet
+    DigitalKeys,
+    /// a distance out of the range the firmware takes
+    OutOfRange,
+    /// a line that is not a setting
+    NotASetting,
+    /// a firmware value like the version
+    NotAKeySetting,
+    /// a key past the count the file gives
+    PastKeyCount(usize),
+    UnknownSetting,
+    InvalidValue,
+    /// calibration belongs to the switch and stays on the device
+    Calibration,
+    /// a rapid trigger setting of a key with rapid trigger off
+    RapidTriggerOff,
+}
+
+/// Settings that could not be translated, empty when nothing was lost
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Report {
+    pub untranslated: Vec<Untranslated>,
+}
+
+impl Report {
+    pub fn is_empty(&self) -> bool {
+        self.untranslated.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.untranslated.len()
+    }
+
+    fn skip(&mut self, field: impl Into<String>, value: impl Into<String>, reason: Reason) {
+        self.untranslated.push(Untranslated {
+            field: field.into(),
+            value: value.into(),
+            reason,
+        });
+    }
+}
+
+/// Settings of one key as the firmware names them
+type RawKey = BTreeMap<String, String>;
+
+/// Most keys a file may have, far more than any minipad has
+///
+/// Keeps a typo like `hkey9999999.lh` from allocating millions of keys
+pub const MAX_KEYS: usize = u8::MAX as usize;
+
+/// Read settings written by another tool
+pub fn import(format: Format, raw: &str) -> Result<(Config, Report), Error> {
+    let mut report = Report::default();
+    let keys = match format {
+        Format::Commands => read_commands(raw, &mut report)?,
+        Format::Json => read_json(raw, &mut report)?,
+    };
+    if keys.is_empty() {
+        return Err(Error::NoKeys);
+    }
+
+    let mut config = Config::default();
+    for (i, key) in keys.into_iter().enumerate() {
+        config.hkeys.push(translate_key(i + 1, key, &mut report));
+    }
+    Ok((config, report))
+}
+
+/// Write the key settings of a config for another tool
+pub fn export(format: Format, config: &Config) -> (String, Report) {
+    let mut report = Report::default();
+    if !config.dkeys.is_empty() {
+        report.skip("dkeys", config.dkeys.len().to_string(), Reason::DigitalKeys);
+    }
+
+    let keys = config
+        .hkeys
+        .iter()
+        .enumerate()
+        .map(|(i, key)| export_key(i + 1, key, &mut report));
+    let raw = match format {
+        Format::Commands => keys
+            .enumerate()
+            .flat_map(|(i, key)| {
+                key.into_iter()
+                    .map(move |(field, value)| format!("hkey{}.{field} {value}\n", i + 1))
+            })
+            .collect(),
+        Format::Json => {
+            let keys = keys
+                .map(|key| {
+                    key.into_iter()
+                        .map(|(field, value)| (field.to_string(), json!(value)))
+                        .collect::<Map<_, _>>()
+                })
+                .collect::<Vec<_>>();
+            let mut raw =
+                serde_json::to_string_pretty(&json!({ "hkeys": keys })).unwrap_or_default();
+            raw.push('\n');
+            raw
+        }
+    };
+    (raw, report)
+}
+
+/// Settings of a key, the rapid trigger ones only while it is on
+///
+/// Distances the firmware can't take are left out and reported
+fn export_key(index: usize, key: &HKey, report: &mut Report) -> Vec<(&'static str, u16)> {
+    let mut settings = vec![("rt", key.rt.is_some() as u16)];
+    let mut distances = Vec::new();
+    if let Some(rt) = &key.rt {
+        settings.push(("crt", rt.continuos as u16));
+        distances.extend([("rtus", rt.up_sensitivity), ("rtds", rt.down_sensitivity)]);
+    }
+    distances.extend([("lh", key.hysterisis.lower), ("uh", key.hysterisis.upper)]);
+    for (field, distance) in distances {
+        match distance.to_serial() {
+            Some(value) => settings.push((field, value)),
+            None => report.skip(
+                format!("hkey{index}.{field}"),
+                distance.to_string(),
+                Reason::OutOfRange,
+            ),
+        }
+    }
+    settings.extend([
+        ("char", key.keycode.to_serial() as u16),
+        ("hid", key.hid as u16),
+    ]);
+    settings
+}
+
+fn read_commands(raw: &str, report: &mut Report) -> Result<Vec<RawKey>, Error> {
+    let mut keys = Vec::<RawKey>::new();
+    let mut key_count = None;
+
+    for (number, line) in raw.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with("//") {
+            continue;
+        }
+        let line = line.strip_prefix("GET ").unwrap_or(line);
+        let Some((name, value)) = line
+            .split_once('=')
+            .or_else(|| line.split_once(char::is_whitespace))
+        else {
+            // commands without a value
+            if !matches!(line, "save" | "get" | "echo" | "out" | "END") {
+                report.skip(line, "", Reason::NotASetting);
+            }
+            continue;
+        };
+        let (name, value) = (name.trim(), value.trim());
+
+        let Some((prefix, field)) = name.split_once('.') else {
+            match name {
+                "hkeys" => {
+                    key_count = Some(
+                        value
+                            .parse::<usize>()
+                            .ok()
+                            .filter(|count| *count <= MAX_KEYS)
+                            .ok_or_else(|| Error::Line(number + 1, line.to_string()))?,
+                    )
+                }
+                "dkeys" if value == "0" => (),
+                "dkeys" => report.skip(name, value, Reason::DigitalKeys),
+                // firmware values like the version
+                _ => report.skip(name, value, Reason::NotAKeySetting),
+            }
+            continue;
+        };
+        if let Some(index) = prefix.strip_prefix("hkey") {
+            let index = index
+                .parse::<usize>()
+                .ok()
+                .filter(|index| (1..=MAX_KEYS).contains(index))
+                .ok_or_else(|| Error::Line(number + 1, line.to_string()))?;
+            if keys.len() < index {
+                keys.resize_with(index, RawKey::new);
+            }
+            keys[index - 1].insert(field.to_string(), value.to_string());
+        } else if prefix.starts_with("dkey") {
+            report.skip(name, value, Reason::DigitalKeys);
+        } else {
+            report.skip(name, value, Reason::NotAKeySetting);
+        }
+    }
+
+    if let Some(count) = key_count {
+        if keys.len() > count {
+            for index in count..keys.len() {
+                report.skip(
+                    format!("hkey{}", index + 1),
+                    "",
+                    Reason::PastKeyCount(count),
+                );
+            }
+            keys.truncate(count);
+        }
+        keys.resize_with(count, RawKey::new);
+    }
+    Ok(keys)
+}
+
+fn read_json(raw: &str, report: &mut Report) -> Result<Vec<RawKey>, Error> {
+    let root: Map<String, Value> = serde_json::from_str(raw)?;
+    let mut keys = Vec::new();
+
+    for (name, value) in root {
+        match (name.as_str(), value) {
+            ("hkeys", Value::Array(hkeys)) => {
+                if hkeys.len() > MAX_KEYS {
+                    return Err(Error::Json(format!(
+                        "there are {} keys, at most {MAX_KEYS} are supported",
+                        hkeys.len()
+                    )));
+                }
+                for (i, key) in hkeys.into_iter().enumerate() {
+                    let Value::Object(key) = key else {
+                        return Err(Error::Json(format!("hkeys[{i}] is not an object")));
+                    };
+                    let key = key
+                        .into_iter()
+                        .map(|(field, value)| {
+                            let value = match value {
+                                // some tools write the character instead of its code
+                                Value::String(c) if field == "char" && c.chars().count() == 1 => c
+                                    .chars()
+                                    .next()
+                                    .and_then(KeyCode::from_char)
+                                    .map_or(c, |code| code.to_serial().to_string()),
+                                Value::String(value) => value,
+                                value => value.to_string(),
+                            };
+                            (field, value)
+                        })
+                        .collect();
+                    keys.push(key);
+                }
+            }
+            ("hkeys", _) => return Err(Error::Json("hkeys is not a list".to_string())),
+            ("dkeys", Value::Array(dkeys)) if dkeys.is_empty() => (),
+            ("dkeys", value) => report.skip("dkeys", value.to_string(), Reason::DigitalKeys),
+            (_, value) => report.skip(name, value.to_string(), Reason::NotAKeySetting),
+        }
+    }
+    Ok(keys)
+}
+
+/// Build a key from the firmware settings, reporting what doesn't fit
+///
+/// Missing settings keep the defaults of [`HKey`]
+fn translate_key(index: usize, mut raw: RawKey, report: &mut Report) -> HKey {
+    let mut key = HKey::default();
+    let name = |field: &str| format!("hkey{index}.{field}");
+
+    // rapid trigger decides if its other settings are used
+    let rt_enabled = match raw.remove("rt") {
+        Some(value) => match parse_flag(&value) {
+            Some(enabled) => enabled,
+            None => {
+                report.skip(name("rt"), value, Reason::InvalidValue);
+                false
+            }
+        },
+        None => false,
+    };
+    let mut rt = RapidTrigger::default();
+    let mut hysterisis = Hysterisis::default();
+
+    for (field, value) in raw {
+        let distance = || value.parse::<u16>().ok().map(Millimeter::from_serial);
+        let result = match field.as_str() {
+            "crt" => parse_flag(&value).map(|v| rt.continuos = v),
+            "rtus" => distance().map(|v| rt.up_sensitivity = v),
+            "rtds" => distance().map(|v| rt.down_sensitivity = v),
+            "lh" => distance().map(|v| hysterisis.lower = v),
+            // older firmware called it `rtuh`
+            "uh" | "rtuh" => distance().map(|v| hysterisis.upper = v),
+            "char" => value
+                .parse::<u8>()
+                .ok()
+                .map(|v| key.keycode = KeyCode::from_serial(v)),
+            "hid" => parse_flag(&value).map(|v| key.hid = v),
+            "rest" | "down" => {
+                report.skip(name(&field), value, Reason::Calibration);
+                continue;
+            }
+            _ => {
+                report.skip(name(&field), value, Reason::UnknownSetting);
+                continue;
+            }
+        };
+        if result.is_none() {
+            report.skip(name(&field), value, Reason::InvalidValue);
+        } else if !rt_enabled && matches!(field.as_str(), "crt" | "rtus" | "rtds") {
+            report.skip(name(&field), value, Reason::RapidTriggerOff);
+        }
+    }
+
+    key.rt = rt_enabled.then_some(rt);
+    key.hysterisis = hysterisis;
+    key
+}
+
+fn parse_flag(value: &str) -> Option<bool> {
+    match value {
+        "1" | "true" => Some(true),
+        "0" | "false" => Some(false),
+        _ => None,
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("line {0} is not a setting: {1}")]
+    Line(usize, String),
+    #[error("invalid json")]
+    Parse(#[from] serde_json::Error),
+    #[error("{0}")]
+    Json(String),
+    #[error("there are no key settings in the file")]
+    NoKeys,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compare;
+
+    fn config() -> Config {
+        let mut rt_key = HKey {
+            rt: Some(RapidTrigger {
+                continuos: true,
+                up_sensitivity: Millimeter::from_serial(15),
+                down_sensitivity: Millimeter::from_serial(25),
+            }),
+            hid: true,
+            keycode: KeyCode::from_char('x').unwrap(),
+            ..Default::default()
+        };
+        rt_key.hysterisis.lower = Millimeter::from_serial(120);
+        let mut plain_key = HKey::default();
+        plain_key.hysterisis.upper = Millimeter::from_serial(350);
+        Config {
+            hkeys: vec![rt_key, plain_key],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let config = config();
+        for format in Format::ALL {
+            let (raw, report) = export(format, &config);
+            assert!(report.is_empty(), "{format:?}: {report:?}");
+            assert_eq!(Format::detect(&raw), format);
+
+            let (imported, report) = import(format, &raw).unwrap();
+            assert!(report.is_empty(), "{format:?}: {report:?}");
+            assert_eq!(imported.hkeys.len(), 2);
+            assert_eq!(compare::diff(&config, &imported), [], "{format:?}");
+            assert!(imported.hkeys[0].hid);
+        }
+    }
+
+    #[test]
+    fn export_reports_what_does_not_fit() {
+        let mut config = config();
+        config.hkeys[1].hysterisis.lower = Millimeter::from_hundredths(-1);
+        let (raw, report) = export(Format::Commands, &config);
+        assert_eq!(report.len(), 1);
+        assert_eq!(report.untranslated[0].field, "hkey2.lh");
+        assert_eq!(report.untranslated[0].reason, Reason::OutOfRange);
+        assert!(!raw.contains("hkey2.lh"));
+    }
+
+    #[test]
+    fn get_output() {
+        let raw = "GET hkeys=1\nGET dkeys=0\nGET version=2024.1\nGET hkey1.rt=0\n\
+                   GET hkey1.lh=200\nGET hkey1.rest=1800\nGET END\n";
+        let (config, report) = import(Format::Commands, raw).unwrap();
+        assert_eq!(config.hkeys.len(), 1);
+        assert!(config.hkeys[0].rt.is_none());
+        assert_eq!(
+            config.hkeys[0].hysterisis.lower,
+            Millimeter::from_serial(200)
+        );
+        let fields = report
+            .untranslated
+            .iter()
+            .map(|u| (u.field.as_str(), u.reason))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            fields,
+            [
+                ("version", Reason::NotAKeySetting),
+                ("hkey1.rest", Reason::Calibration)
+            ]
+        );
+    }
+
+    #[test]
+    fn key_limits() {
+        let line = |raw: &str| match import(Format::Commands, raw) {
+            Err(Error::Line(line, _)) => Some(line),
+            _ => None,
+        };
+        assert_eq!(line("hkey1.lh 200\nhkey0.lh 200\n"), Some(2));
+        assert_eq!(line("hkey256.lh 200\n"), Some(1));
+        assert_eq!(line("hkey99999999999.lh 200\n"), Some(1));
+        assert_eq!(line("hkeys 256\n"), Some(1));
+        assert_eq!(line("hkeys -1\n"), Some(1));
+
+        let (config, _) = import(Format::Commands, "hkey255.lh 200\n").unwrap();
+        assert_eq!(config.hkeys.len(), MAX_KEYS);
+
+        let json = format!(r#"{{"hkeys": [{}]}}"#, vec!["{}"; MAX_KEYS + 1].join(","));
+        assert!(matches!(import(Format::Json, &json), Err(Error::Json(_))));
+    }
+
+    #[test]
+    fn file_key_count_wins() {
+        let (config, report) = import(Format::Commands, "hkeys 1\nhkey2.lh 200\n").unwrap();
+        assert_eq!(config.hkeys.len(), 1);
+        assert_eq!(report.untranslated[0].field, "hkey2");
+        assert_eq!(report.untranslated[0].reason, Reason::PastKeyCount(1));
+    }
+}
@@ -0,0 +1,245 @@
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
+
+/// Key sent by the firmware when a hall effect key is pressed
+///
+/// The firmware takes a single byte per key and follows the arduino keyboard library:
+/// printable ascii is typed as the us layout character, `0x80..=0x87` are the modifiers
+/// and everything from `0x88` is a raw HID usage offset by 136, which covers the
+/// function keys up to F24 and keys that don't depend on the host's layout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct KeyCode(u8);
+
+/// offset the firmware subtracts to get a raw HID usage
+const USAGE_OFFSET: u8 = 136;
+/// highest HID usage that still fits into a byte after the offset
+pub const MAX_USAGE: u8 = u8::MAX - USAGE_OFFSET;
+
+const MODIFIERS: [&str; 8] = [
+    "Left Ctrl",
+    "Left Shift",
+    "Left Alt",
+    "Left GUI",
+    "Right Ctrl",
+    "Right Shift",
+    "Right Alt",
+    "Right GUI",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Category {
+    None,
+    /// typed as the character on a us layout
+    Character,
+    Modifier,
+    /// raw HID usage, the host's layout decides what gets typed
+    Usage,
+}
+
+impl KeyCode {
+    /// key that does nothing
+    pub const NONE: Self = Self(0);
+
+    pub fn from_serial(value: u8) -> Self {
+        Self(value)
+    }
+
+    pub fn to_serial(self) -> u8 {
+        self.0
+    }
+
+    /// Printable ascii character
+    pub fn from_char(c: char) -> Option<Self> {
+        (c.is_ascii_graphic() || c == ' ').then_some(Self(c as u8))
+    }
+
+    /// Raw HID usage from the keyboard/keypad usage page
+    pub fn from_usage(usage: u8) -> Option<Self> {
+        // lazily, the sum overflows for usages that don't fit
+        (usage <= MAX_USAGE).then(|| Self(usage + USAGE_OFFSET))
+    }
+
+    pub fn category(self) -> Category {
+        match self.0 {
+            0 => Category::None,
+            0x80..=0x87 => Category::Modifier,
+            c if c >= USAGE_OFFSET => Category::Usage,
+            _ => Category::Character,
+        }
+    }
+
+    pub fn name(self) -> Cow<'static, str> {
+        match self.category() {
+            Category::None => "None".into(),
+            Category::Character => match self.0 {
+                b' ' => "Space".into(),
+                c if c.is_ascii_graphic() => format!("'{}'", c as char).into(),
+                c => format!("0x{c:02x}").into(),
+            },
+            Category::Modifier => MODIFIERS[(self.0 - 0x80) as usize].into(),
+            Category::Usage => {
+                let usage = self.0 - USAGE_OFFSET;
+                usage_name(usage).unwrap_or_else(|| format!("Usage 0x{usage:02x}").into())
+            }
+        }
+    }
+
+    /// Every key code the firmware accepts
+    pub fn all() -> impl Iterator<Item = KeyCode> {
+        std::iter::once(Self::NONE)
+            .chain((b' '..=b'~').map(Self))
+            .chain((0x80..=0x87).map(Self))
+            .chain((0x04..=MAX_USAGE).filter_map(Self::from_usage))
+    }
+}
+
+impl std::fmt::Display for KeyCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.name())
+    }
+}
+
+/// Name of a usage on the HID keyboard/keypad page
+fn usage_name(usage: u8) -> Option<Cow<'static, str>> {
+    let name: Cow<'static, str> = match usage {
+        0x04..=0x1d => ((b'A' + usage - 0x04) as char).to_string().into(),
+        0x1e..=0x26 => ((b'1' + usage - 0x1e) as char).to_string().into(),
+        0x27 => "0".into(),
+        0x3a..=0x45 => format!("F{}", usage - 0x3a + 1).into(),
+        0x59..=0x61 => format!("Keypad {}", usage - 0x59 + 1).into(),
+        0x68..=0x73 => format!("F{}", usage - 0x68 + 13).into(),
+        _ => match usage {
+            0x28 => "Enter",
+            0x29 => "Escape",
+            0x2a => "Backspace",
+            0x2b => "Tab",
+            0x2c => "Spacebar",
+            0x2d => "Minus",
+            0x2e => "Equal",
+            0x2f => "Left Bracket",
+            0x30 => "Right Bracket",
+            0x31 => "Backslash",
+            0x32 => "Non-US Hash",
+            0x33 => "Semicolon",
+            0x34 => "Quote",
+            0x35 => "Grave",
+            0x36 => "Comma",
+            0x37 => "Period",
+            0x38 => "Slash",
+            0x39 => "Caps Lock",
+            0x46 => "Print Screen",
+            0x47 => "Scroll Lock",
+            0x48 => "Pause",
+            0x49 => "Insert",
+            0x4a => "Home",
+            0x4b => "Page Up",
+            0x4c => "Delete",
+            0x4d => "End",
+            0x4e => "Page Down",
+            0x4f => "Right Arrow",
+            0x50 => "Left Arrow",
+            0x51 => "Down Arrow",
+            0x52 => "Up Arrow",
+            0x53 => "Num Lock",
+            0x54 => "Keypad /",
+            0x55 => "Keypad *",
+            0x56 => "Keypad -",
+            0x57 => "Keypad +",
+            0x58 => "Keypad Enter",
+            0x62 => "Keypad 0",
+            0x63 => "Keypad .",
+            0x64 => "Non-US Backslash",
+            0x65 => "Application",
+            0x66 => "Power",
+            0x67 => "Keypad =",
+            0x74 => "Execute",
+            0x75 => "Help",
+            0x76 => "Menu",
+            0x77 => "Select",
+            _ => return None,
+        }
+        .into(),
+    };
+    Some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn characters() {
+        let a = KeyCode::from_char('a').unwrap();
+        assert_eq!(a.to_serial(), b'a');
+        assert_eq!(a.category(), Category::Character);
+        assert_eq!(a.name(), "'a'");
+        assert_eq!(KeyCode::from_char(' ').unwrap().name(), "Space");
+        assert_eq!(KeyCode::from_char('~').unwrap().to_serial(), b'~');
+
+        assert_eq!(KeyCode::from_char('\n'), None);
+        assert_eq!(KeyCode::from_char('\u{7f}'), None);
+        assert_eq!(KeyCode::from_char('ä'), None);
+    }
+
+    #[test]
+    fn usages() {
+        let f13 = KeyCode::from_usage(0x68).unwrap();
+        assert_eq!(f13.to_serial(), 0x68 + USAGE_OFFSET);
+        assert_eq!(f13.category(), Category::Usage);
+        assert_eq!(f13.name(), "F13");
+        assert_eq!(KeyCode::from_usage(0x04).unwrap().name(), "A");
+        assert_eq!(KeyCode::from_usage(0x27).unwrap().name(), "0");
+        assert_eq!(KeyCode::from_usage(0x28).unwrap().name(), "Enter");
+        assert_eq!(KeyCode::from_usage(0x62).unwrap().name(), "Keypad 0");
+        // usages without a name still show their number
+        assert_eq!(KeyCode::from_usage(0x00).unwrap().name(), "Usage 0x00");
+
+        assert!(KeyCode::from_usage(MAX_USAGE).is_some());
+        assert_eq!(KeyCode::from_usage(MAX_USAGE + 1), None);
+    }
+
+    #[test]
+    fn modifiers_and_none() {
+        assert_eq!(KeyCode::NONE.category(), Category::None);
+        assert_eq!(KeyCode::NONE.name(), "None");
+        assert_eq!(KeyCode::default(), KeyCode::NONE);
+
+        let ctrl = KeyCode::from_serial(0x80);
+        assert_eq!(ctrl.category(), Category::Modifier);
+        assert_eq!(ctrl.name(), "Left Ctrl");
+        assert_eq!(KeyCode::from_serial(0x87).name(), "Right GUI");
+        assert_eq!(KeyCode::from_serial(0x88).category(), Category::Usage);
+    }
+
+    #[test]
+    fn unprintable_characters() {
+        // the firmware would take them, the picker does not offer them
+        let code = KeyCode::from_serial(0x7f);
+        assert_eq!(code.category(), Category::Character);
+        assert_eq!(code.name(), "0x7f");
+        assert!(!KeyCode::all().any(|k| k == code));
+    }
+
+    #[test]
+    fn serial_round_trip() {
+        for value in 0..=u8::MAX {
+            assert_eq!(KeyCode::from_serial(value).to_serial(), value);
+        }
+        for code in KeyCode::all() {
+            assert_eq!(KeyCode::from_serial(code.to_serial()), code);
+            assert_eq!(code.to_string(), code.name());
+        }
+    }
+
+    #[test]
+    fn all_is_unique() {
+        let mut codes = KeyCode::all().map(KeyCode::to_serial).collect::<Vec<_>>();
+        let count = codes.len();
+        codes.sort_unstable();
+        codes.dedup();
+        assert_eq!(codes.len(), count);
+        assert!(KeyCode::all().all(|code| !code.name().is_empty()));
+    }
+}
@@ -0,0 +1,53 @@
+//! Talk to [minipad](https://github.com/minipadKB/minipad-firmware) keypads over serial
+//!
+//! [`device::Devices`] finds the connected keypads. Each [`device::Device`] runs its
+//! serial connection on a background thread, commands are sent to it as
+//! [`device::SendEvent`]s and everything it reads comes back as [`device::Event`]s.
+//! What is read and written is a [`config::Config`], which can also be stored as a
+//! named [`profile`].
+//!
+//! ```no_run
+//! use crustility_core::device::{Devices, Event, SendEvent};
+//!
+//! let mut devices = Devices::default();
+//! devices.scan();
+//! let handle = *devices.iter().next().expect("no minipad connected");
+//! let device = devices.get_mut(&handle).unwrap();
+//! device.spawn_event_loop()?;
+//! device.send_event(SendEvent::ReadConfig)?;
+//! loop {
+//!     if let Ok(Event::Config(config)) = device.recv_data() {
+//!         println!("{} keys", config.hkeys.len());
+//!         break;
+//!     }
+//! }
+//! # Ok::<(), crustility_core::device::Error>(())
+//! ```
+
+/// Configs saved before the device gets changed
+pub mod backup;
+/// Physical arrangement of the keys
+pub mod board;
+/// Differences between two configs
+pub mod compare;
+/// Key settings and distances
+pub mod config;
+/// Finding devices and talking to them over serial
+pub mod device;
+/// Key settings in the firmware's own terms, for other tools and scripts
+pub mod interop;
+/// Key codes the firmware can send
+pub mod keycode;
+/// Starting settings for common play styles
+pub mod preset;
+/// Configs stored by name
+pub mod profile;
+/// Configs as short strings that can be pasted in chat
+pub mod share;
+/// Files in the crustility config directory
+pub mod storage;
+/// udev rules for access to the serial port
+#[cfg(target_os = "linux")]
+pub mod udev;
+/// Checks a config against the limits of the firmware
+pub mod validate;
@@ -0,0 +1,127 @@
+use crate::config::{Capabilities, HKey, Hysterisis, Millimeter, RapidTrigger};
+use crate::validate::{self, Field};
+
+/// Starting point for the settings of a key, tuned for a way of playing or typing
+#[derive(Debug, Clone, PartialEq)]
+pub struct Preset {
+    pub kind: Kind,
+    pub rapid_trigger: Option<RapidTriggerTemplate>,
+    /// how far a key is pressed before it actuates, unless changed in [`Params`]
+    pub actuation: Millimeter,
+    /// distance between actuation and release, larger gaps stop chatter
+    pub release_gap: Millimeter,
+}
+
+/// Which of the built in presets it is, the ui names and describes it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Kind {
+    /// continuous rapid trigger with a shallow actuation for fast streams
+    OsuAggressive,
+    /// rapid trigger that resets above the actuation point, forgiving when resting
+    /// fingers on the keys
+    OsuBalanced,
+    /// rapid trigger with a medium actuation, for movement keys
+    Gaming,
+    /// no rapid trigger and a deep actuation, like a regular switch
+    Typing,
+    /// no rapid trigger and a wide release gap, for noisy sensors or keys that
+    /// double press
+    AntiChatter,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RapidTriggerTemplate {
+    pub continuous: bool,
+    /// default for both directions, unless changed in [`Params`]
+    pub sensitivity: Millimeter,
+}
+
+/// Values of a preset that can be adjusted before applying it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Params {
+    /// distance from the top
+    pub actuation: Millimeter,
+    /// rapid trigger sensitivity, unused by presets without rapid trigger
+    pub sensitivity: Millimeter,
+}
+
+/// Built in presets
+pub fn catalog() -> Vec<Preset> {
+    let mm = Millimeter::from_hundredths;
+    vec![
+        Preset {
+            kind: Kind::OsuAggressive,
+            rapid_trigger: Some(RapidTriggerTemplate {
+                continuous: true,
+                sensitivity: mm(10),
+            }),
+            actuation: mm(40),
+            release_gap: mm(10),
+        },
+        Preset {
+            kind: Kind::OsuBalanced,
+            rapid_trigger: Some(RapidTriggerTemplate {
+                continuous: false,
+                sensitivity: mm(25),
+            }),
+            actuation: mm(100),
+            release_gap: mm(20),
+        },
+        Preset {
+            kind: Kind::Gaming,
+            rapid_trigger: Some(RapidTriggerTemplate {
+                continuous: false,
+                sensitivity: mm(30),
+            }),
+            actuation: mm(120),
+            release_gap: mm(20),
+        },
+        Preset {
+            kind: Kind::Typing,
+            rapid_trigger: None,
+            actuation: mm(200),
+            release_gap: mm(30),
+        },
+        Preset {
+            kind: Kind::AntiChatter,
+            rapid_trigger: None,
+            actuation: mm(150),
+            release_gap: mm(60),
+        },
+    ]
+}
+
+impl Preset {
+    pub fn params(&self) -> Params {
+        Params {
+            actuation: self.actuation,
+            sensitivity: self
+                .rapid_trigger
+                .map_or(Millimeter::ZERO, |rt| rt.sensitivity),
+        }
+    }
+
+    /// Set the rapid trigger and hysteresis of a key, the key code and hid stay
+    ///
+    /// Values are clamped to what the firmware accepts
+    pub fn apply(&self, params: &Params, capabilities: &Capabilities, key: &mut HKey) {
+        let clamp = |field: Field, value: Millimeter| {
+            let (min, max) = validate::range(capabilities, field);
+            value.clamp(min, max)
+        };
+
+        let lower = clamp(
+            Field::HysteresisLower,
+            capabilities.travel_distance - params.actuation,
+        );
+        let gap = self.release_gap.max(capabilities.hysteresis_tolerance);
+        let upper = clamp(Field::HysteresisUpper, lower + gap);
+        key.hysterisis = Hysterisis { lower, upper };
+
+        key.rt = self.rapid_trigger.map(|rt| RapidTrigger {
+            continuos: rt.continuous,
+            up_sensitivity: clamp(Field::UpSensitivity, params.sensitivity),
+            down_sensitivity: clamp(Field::DownSensitivity, params.sensitivity),
+        });
+    }
+}
@@ -0,0 +1,77 @@
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+use crate::config::Config;
+use crate::storage;
+
+/// Directory the named profiles are stored in, one toml file each
+pub fn profiles_dir() -> Result<PathBuf, Error> {
+    Ok(storage::config_dir()?.join("profiles"))
+}
+
+fn path(name: &str) -> Result<PathBuf, Error> {
+    let valid =
+        !name.trim().is_empty() && !name.starts_with('.') && !name.contains(['/', '\\', '\0']);
+    if !valid {
+        return Err(Error::InvalidName(name.to_string()));
+    }
+    Ok(profiles_dir()?.join(format!("{name}.toml")))
+}
+
+/// Names of the stored profiles, sorted
+pub fn list() -> Result<Vec<String>, Error> {
+    let entries = match std::fs::read_dir(profiles_dir()?) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    let mut names = entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            (path.extension()? == "toml").then_some(())?;
+            Some(path.file_stem()?.to_string_lossy().into_owned())
+        })
+        .collect::<Vec<_>>();
+    names.sort();
+    Ok(names)
+}
+
+/// Read a stored profile
+pub fn load(name: &str) -> Result<Config, Error> {
+    let raw = std::fs::read_to_string(path(name)?).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => Error::NotFound(name.to_string()),
+        _ => e.into(),
+    })?;
+    Ok(toml::from_str(&raw)?)
+}
+
+/// Store a config, replacing the profile with the same name
+pub fn save(name: &str, config: &Config) -> Result<(), Error> {
+    let path = path(name)?;
+    std::fs::create_dir_all(profiles_dir()?)?;
+    std::fs::write(path, toml::to_string_pretty(config)?)?;
+    Ok(())
+}
+
+/// Remove a stored profile
+pub fn delete(name: &str) -> Result<(), Error> {
+    std::fs::remove_file(path(name)?)?;
+    Ok(())
+}
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Storage(#[from] storage::Error),
+    #[error("profile file io")]
+    Io(#[from] std::io::Error),
+    #[error("\"{0}\" can't be used as a profile name")]
+    InvalidName(String),
+    #[error("there is no profile named \"{0}\"")]
+    NotFound(String),
+    #[error(transparent)]
+    Deserialize(#[from] toml::de::Error),
+    #[error(transparent)]
+    Serialize(#[from] toml::ser::Error),
+}
@@ -0,0 +1,239 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use thiserror::Error;
+
+use crate::config::{Config, HKey, Hysterisis, Millimeter, RapidTrigger};
+use crate::keycode::KeyCode;
+
+/// Start of every share string, so it can be picked out of a chat message
+pub const PREFIX: &str = "minipad:";
+/// Version of the binary form, bumped when the layout changes
+const VERSION: u8 = 1;
+/// flags, four distances and the key code
+const KEY_LEN: usize = 1 + 4 * 2 + 1;
+const CHECKSUM: crc::Crc<u32> = crc::Crc::<u32>::new(&crc::CRC_32_ISO_HDLC);
+
+const FLAG_RT: u8 = 1;
+const FLAG_CONTINUOUS: u8 = 1 << 1;
+const FLAG_HID: u8 = 1 << 2;
+
+/// Encode the key settings of a config as a short string that can be pasted in chat
+///
+/// The binary form is the version, the key count, the settings of each key and a
+/// crc32 of everything before it, encoded as url safe base64
+pub fn encode(config: &Config) -> Result<String, Error> {
+    let keys = &config.hkeys;
+    let key_count = u8::try_from(keys.len()).map_err(|_| Error::TooManyKeys(keys.len()))?;
+    let mut data = Vec::with_capacity(2 + keys.len() * KEY_LEN + 4);
+    data.push(VERSION);
+    data.push(key_count);
+    for key in keys {
+        let rt = key.rt.clone().unwrap_or_default();
+        let mut flags = 0;
+        if key.rt.is_some() {
+            flags |= FLAG_RT;
+        }
+        if rt.continuos {
+            flags |= FLAG_CONTINUOUS;
+        }
+        if key.hid {
+            flags |= FLAG_HID;
+        }
+        data.push(flags);
+        for distance in [
+            rt.up_sensitivity,
+            rt.down_sensitivity,
+            key.hysterisis.lower,
+            key.hysterisis.upper,
+        ] {
+            let value = distance.to_serial().ok_or(Error::OutOfRange(distance))?;
+            data.extend(value.to_le_bytes());
+        }
+        data.push(key.keycode.to_serial());
+    }
+    data.extend(CHECKSUM.checksum(&data).to_le_bytes());
+    Ok(format!("{PREFIX}{}", URL_SAFE_NO_PAD.encode(data)))
+}
+
+/// Decode a share string, surrounding text and whitespace are ignored
+pub fn decode(text: &str) -> Result<Config, Error> {
+    let start = text.find(PREFIX).ok_or(Error::NoPrefix)?;
+    let encoded = text[start + PREFIX.len()..]
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+        .next()
+        .unwrap_or_default();
+    let data = URL_SAFE_NO_PAD
+        .decode(encoded)
+        .map_err(|_| Error::Encoding)?;
+
+    let (data, checksum) = data.split_at(data.len().checked_sub(4).ok_or(Error::Truncated)?);
+    let checksum = u32::from_le_bytes(checksum.try_into().map_err(|_| Error::Truncated)?);
+    if CHECKSUM.checksum(data) != checksum {
+        return Err(Error::Checksum);
+    }
+
+    let [version, key_count, keys @ ..] = data else {
+        return Err(Error::Truncated);
+    };
+    if *version != VERSION {
+        return Err(Error::Version(*version));
+    }
+    if keys.len() != *key_count as usize * KEY_LEN {
+        return Err(Error::Truncated);
+    }
+
+    let mut config = Config::default();
+    for key in keys.chunks_exact(KEY_LEN) {
+        let flags = key[0];
+        let distance = |i: usize| {
+            Millimeter::from_serial(u16::from_le_bytes([key[1 + i * 2], key[2 + i * 2]]))
+        };
+        config.hkeys.push(HKey {
+            rt: (flags & FLAG_RT != 0).then(|| RapidTrigger {
+                continuos: flags & FLAG_CONTINUOUS != 0,
+                up_sensitivity: distance(0),
+                down_sensitivity: distance(1),
+            }),
+            hysterisis: Hysterisis {
+                lower: distance(2),
+                upper: distance(3),
+            },
+            hid: flags & FLAG_HID != 0,
+            keycode: KeyCode::from_serial(key[KEY_LEN - 1]),
+            ..Default::default()
+        });
+    }
+    Ok(config)
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    #[error("there is no \"{PREFIX}\" share string in the text")]
+    NoPrefix,
+    #[error("the share string is not valid base64")]
+    Encoding,
+    #[error("the share string is cut off")]
+    Truncated,
+    #[error("the share string is damaged, its checksum doesn't match")]
+    Checksum,
+    #[error("the share string has version {0}, this crustility only reads version 1")]
+    Version(u8),
+    #[error("{0} can't be put in a share string")]
+    OutOfRange(Millimeter),
+    #[error("a share string holds at most 255 keys, the config has {0}")]
+    TooManyKeys(usize),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compare;
+
+    fn config() -> Config {
+        let mut key = HKey {
+            rt: Some(RapidTrigger {
+                continuos: true,
+                up_sensitivity: Millimeter::from_serial(15),
+                down_sensitivity: Millimeter::from_serial(u16::MAX),
+            }),
+            hid: true,
+            keycode: KeyCode::from_char('z').unwrap(),
+            ..Default::default()
+        };
+        key.hysterisis.lower = Millimeter::from_serial(0);
+        Config {
+            hkeys: vec![key, HKey::default()],
+            ..Default::default()
+        }
+    }
+
+    /// Encoded form of `data` with a valid checksum
+    fn with_checksum(mut data: Vec<u8>) -> String {
+        data.extend(CHECKSUM.checksum(&data).to_le_bytes());
+        format!("{PREFIX}{}", URL_SAFE_NO_PAD.encode(data))
+    }
+
+    #[test]
+    fn round_trip() {
+        let config = config();
+        let text = encode(&config).unwrap();
+        assert!(text.starts_with(PREFIX));
+
+        // found in the middle of a chat message
+        let decoded = decode(&format!("try this: {text}. works great")).unwrap();
+        assert_eq!(decoded.hkeys.len(), 2);
+        assert_eq!(compare::diff(&config, &decoded), []);
+        assert!(decoded.hkeys[0].hid);
+
+        let empty = decode(&encode(&Config::default()).unwrap()).unwrap();
+        assert!(empty.hkeys.is_empty());
+    }
+
+    #[test]
+    fn key_limit() {
+        let mut config = Config {
+            hkeys: vec![HKey::default(); 255],
+            ..Default::default()
+        };
+        assert_eq!(decode(&encode(&config).unwrap()).unwrap().hkeys.len(), 255);
+        config.hkeys.push(HKey::default());
+        assert_eq!(encode(&config).err(), Some(Error::TooManyKeys(256)));
+    }
+
+    #[test]
+    fn out_of_range() {
+        let mut config = config();
+        config.hkeys[1].hysterisis.upper = Millimeter::from_hundredths(-5);
+        assert_eq!(
+            encode(&config),
+            Err(Error::OutOfRange(Millimeter::from_hundredths(-5)))
+        );
+    }
+
+    #[test]
+    fn damaged() {
+        let text = encode(&config()).unwrap();
+        assert_eq!(decode("no share string").err(), Some(Error::NoPrefix));
+        assert_eq!(decode(&format!("{PREFIX}a")).err(), Some(Error::Encoding));
+
+        // one character changed
+        let mut damaged = text.clone().into_bytes();
+        let i = PREFIX.len() + 4;
+        damaged[i] = if damaged[i] == b'A' { b'B' } else { b'A' };
+        let damaged = String::from_utf8(damaged).unwrap();
+        assert_eq!(decode(&damaged).err(), Some(Error::Checksum));
+
+        // cut off, the checksum no longer matches what is left
+        let data = URL_SAFE_NO_PAD.decode(&text[PREFIX.len()..]).unwrap();
+        let cut = format!(
+            "{PREFIX}{}",
+            URL_SAFE_NO_PAD.encode(&data[..data.len() - 3])
+        );
+        assert_eq!(decode(&cut).err(), Some(Error::Checksum));
+        assert_eq!(
+            decode(&format!("{PREFIX}AAA")).err(),
+            Some(Error::Truncated)
+        );
+    }
+
+    #[test]
+    fn truncated_with_valid_checksum() {
+        assert_eq!(
+            decode(&with_checksum(vec![VERSION])).err(),
+            Some(Error::Truncated)
+        );
+        // says two keys, has one
+        let mut data = vec![VERSION, 2];
+        data.extend([0; KEY_LEN]);
+        assert_eq!(decode(&with_checksum(data)).err(), Some(Error::Truncated));
+    }
+
+    #[test]
+    fn version() {
+        let mut data = vec![VERSION + 1, 1];
+        data.extend([0; KEY_LEN]);
+        assert_eq!(
+            decode(&with_checksum(data)).err(),
+            Some(Error::Version(VERSION + 1))
+        );
+    }
+}
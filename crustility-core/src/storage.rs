@@ -0,0 +1,51 @@
+use std::path::PathBuf;
+
+use serde::{de::DeserializeOwned, Serialize};
+use thiserror::Error;
+
+/// Directory crustility keeps its files in
+pub fn config_dir() -> Result<PathBuf, Error> {
+    Ok(dirs::config_dir()
+        .ok_or(Error::NoConfigDir)?
+        .join("crustility"))
+}
+
+/// Load a toml file from the config directory
+///
+/// Returns the default value if the file does not exist yet
+pub fn load<T: DeserializeOwned + Default>(file_name: &str) -> Result<T, Error> {
+    let path = config_dir()?.join(file_name);
+    match std::fs::read_to_string(path) {
+        Ok(raw) => Ok(toml::from_str(&raw)?),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(T::default()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Load a toml file from the config directory, falling back to the default on errors
+pub fn load_or_default<T: DeserializeOwned + Default>(file_name: &str) -> T {
+    load(file_name).unwrap_or_else(|e| {
+        log::error!("could not load {file_name}: {e}");
+        T::default()
+    })
+}
+
+/// Save a value as toml to the config directory
+pub fn save<T: Serialize>(file_name: &str, value: &T) -> Result<(), Error> {
+    let dir = config_dir()?;
+    std::fs::create_dir_all(&dir)?;
+    std::fs::write(dir.join(file_name), toml::to_string_pretty(value)?)?;
+    Ok(())
+}
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("could not find the user config directory")]
+    NoConfigDir,
+    #[error("file io")]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Deserialize(#[from] toml::de::Error),
+    #[error(transparent)]
+    Serialize(#[from] toml::ser::Error),
+}
@@ -0,0 +1,137 @@
+use std::io::Write;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::mpsc::Receiver;
+
+use thiserror::Error;
+
+use crate::device::DeviceHandle;
+
+pub const RULES_DIR: &str = "/etc/udev/rules.d";
+
+/// Rule file for the device, one per vid:pid so installing a rule keeps the others
+pub fn rule_path(handle: &DeviceHandle) -> PathBuf {
+    PathBuf::from(RULES_DIR).join(format!(
+        "70-crustility-{:04x}-{:04x}.rules",
+        handle.vid(),
+        handle.pid()
+    ))
+}
+
+/// udev rule giving the logged in user and the serial group access to the device
+pub fn rule(handle: &DeviceHandle, group: &str) -> String {
+    format!(
+        "# generated by crustility\n\
+         SUBSYSTEM==\"tty\", ATTRS{{idVendor}}==\"{:04x}\", ATTRS{{idProduct}}==\"{:04x}\", \
+         MODE=\"0660\", GROUP=\"{group}\", TAG+=\"uaccess\"\n",
+        handle.vid(),
+        handle.pid(),
+    )
+}
+
+/// Group owning the serial port, `dialout` or `uucp` on most distributions
+pub fn port_group(port_name: &str) -> Option<String> {
+    let gid = std::fs::metadata(port_name).ok()?.gid();
+    let groups = std::fs::read_to_string("/etc/group").ok()?;
+    groups.lines().find_map(|line| {
+        let mut fields = line.split(':');
+        let name = fields.next()?;
+        let id = fields.nth(1)?.parse::<u32>().ok()?;
+        (id == gid).then(|| name.to_string())
+    })
+}
+
+/// Groups of the current user
+pub fn user_groups() -> Result<Vec<String>, Error> {
+    let output = Command::new("id").arg("-Gn").output()?;
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .split_whitespace()
+        .map(str::to_string)
+        .collect())
+}
+
+pub fn rule_installed(handle: &DeviceHandle) -> bool {
+    rule_path(handle).exists()
+}
+
+/// Install the rule with pkexec and reload udev
+///
+/// Runs in the background since pkexec waits for the user to authenticate
+pub fn install_rule(handle: &DeviceHandle, rule: String) -> Receiver<Result<(), Error>> {
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let path = rule_path(handle);
+    std::thread::spawn(move || {
+        let _ = sender.send(install_rule_blocking(&path, &rule));
+    });
+    receiver
+}
+
+fn install_rule_blocking(path: &Path, rule: &str) -> Result<(), Error> {
+    // the rule goes over stdin, a file other users can write to could be swapped before
+    // root copies it
+    let mut child = Command::new("pkexec")
+        .arg("sh")
+        .arg("-c")
+        .arg(
+            "umask 022 \
+             && tee \"$0\" > /dev/null \
+             && udevadm control --reload-rules \
+             && udevadm trigger --action=add --subsystem-match=tty",
+        )
+        .arg(path)
+        .stdin(Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // a failed write shows up in the exit status, pkexec exits without reading when
+        // the authentication is dismissed
+        let _ = stdin.write_all(rule.as_bytes());
+    }
+    let status = child.wait()?;
+
+    match status.code() {
+        Some(0) => Ok(()),
+        // the authentication dialog was dismissed or failed
+        Some(126) | Some(127) => Err(Error::NotAuthorized),
+        _ => Err(Error::Install(status.to_string())),
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("io")]
+    Io(#[from] std::io::Error),
+    #[error("authentication was cancelled")]
+    NotAuthorized,
+    #[error("installing the udev rule failed: {0}")]
+    Install(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rule_per_device() {
+        let minipad = DeviceHandle::new(0x0727, 0x0727, 0);
+        let rule = rule(&minipad, "uucp");
+        assert_eq!(
+            rule,
+            "# generated by crustility\n\
+             SUBSYSTEM==\"tty\", ATTRS{idVendor}==\"0727\", ATTRS{idProduct}==\"0727\", \
+             MODE=\"0660\", GROUP=\"uucp\", TAG+=\"uaccess\"\n"
+        );
+
+        let other = DeviceHandle::new(0x2e8a, 0x000a, 0);
+        assert_eq!(
+            rule_path(&minipad),
+            Path::new("/etc/udev/rules.d/70-crustility-0727-0727.rules")
+        );
+        assert_ne!(rule_path(&minipad), rule_path(&other));
+        // the instance only tells apart devices of the same kind
+        assert_eq!(
+            rule_path(&other),
+            rule_path(&DeviceHandle::new(0x2e8a, 0x000a, 1))
+        );
+    }
+}
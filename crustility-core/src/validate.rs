@@ -0,0 +1,225 @@
+use crate::config::{Capabilities, Config, HKey, Millimeter};
+
+/// Setting of a hall effect key that can be invalid
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Field {
+    UpSensitivity,
+    DownSensitivity,
+    HysteresisLower,
+    HysteresisUpper,
+}
+
+/// Setting that the firmware would not accept or that would not work
+#[derive(Debug, Clone, PartialEq)]
+pub struct Issue {
+    /// index of the hall effect key
+    pub key: usize,
+    pub field: Field,
+    pub problem: Problem,
+}
+
+/// What is wrong with a setting
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Problem {
+    /// outside of [`range`]
+    OutOfRange { min: Millimeter, max: Millimeter },
+    /// the upper hysteresis is less than `tolerance` above the lower one
+    TooClose { tolerance: Millimeter },
+}
+
+/// Everything wrong with a config
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Report {
+    issues: Vec<Issue>,
+}
+
+impl Report {
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.issues.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    pub fn key_is_valid(&self, key: usize) -> bool {
+        !self.issues.iter().any(|issue| issue.key == key)
+    }
+
+    pub fn field(&self, key: usize, field: Field) -> Option<&Issue> {
+        self.issues
+            .iter()
+            .find(|issue| issue.key == key && issue.field == field)
+    }
+
+    fn check(&mut self, key: usize, field: Field, valid: bool, problem: Problem) {
+        if !valid && self.field(key, field).is_none() {
+            self.issues.push(Issue {
+                key,
+                field,
+                problem,
+            });
+        }
+    }
+}
+
+/// Range a field can take on its own, cross field rules are checked by [`validate`]
+pub fn range(capabilities: &Capabilities, field: Field) -> (Millimeter, Millimeter) {
+    let travel = capabilities.travel_distance;
+    match field {
+        Field::UpSensitivity | Field::DownSensitivity => (capabilities.min_sensitivity, travel),
+        Field::HysteresisLower => (
+            capabilities.hysteresis_tolerance,
+            travel - capabilities.hysteresis_tolerance,
+        ),
+        Field::HysteresisUpper => (
+            capabilities.hysteresis_tolerance + capabilities.hysteresis_tolerance,
+            travel,
+        ),
+    }
+}
+
+/// Check every hall effect key of a config
+pub fn validate(config: &Config) -> Report {
+    let mut report = Report::default();
+    for (i, key) in config.hkeys.iter().enumerate() {
+        validate_key(&mut report, &config.capabilities, i, key);
+    }
+    report
+}
+
+fn validate_key(report: &mut Report, capabilities: &Capabilities, i: usize, key: &HKey) {
+    let in_range = |report: &mut Report, field: Field, value: Millimeter| {
+        let (min, max) = range(capabilities, field);
+        report.check(
+            i,
+            field,
+            min <= value && value <= max,
+            Problem::OutOfRange { min, max },
+        );
+    };
+
+    if let Some(rt) = &key.rt {
+        in_range(report, Field::UpSensitivity, rt.up_sensitivity);
+        in_range(report, Field::DownSensitivity, rt.down_sensitivity);
+    }
+    in_range(report, Field::HysteresisLower, key.hysterisis.lower);
+    in_range(report, Field::HysteresisUpper, key.hysterisis.upper);
+
+    let tolerance = capabilities.hysteresis_tolerance;
+    report.check(
+        i,
+        Field::HysteresisUpper,
+        key.hysterisis.upper - key.hysterisis.lower >= tolerance,
+        Problem::TooClose { tolerance },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RapidTrigger;
+
+    fn mm(hundredths: i32) -> Millimeter {
+        Millimeter::from_hundredths(hundredths)
+    }
+
+    fn config(key: HKey) -> Config {
+        Config {
+            hkeys: vec![key],
+            ..Default::default()
+        }
+    }
+
+    fn hysteresis(lower: i32, upper: i32) -> HKey {
+        let mut key = HKey::default();
+        key.hysterisis.lower = mm(lower);
+        key.hysterisis.upper = mm(upper);
+        key
+    }
+
+    fn sensitivity(up: i32, down: i32) -> HKey {
+        HKey {
+            rt: Some(RapidTrigger {
+                continuos: false,
+                up_sensitivity: mm(up),
+                down_sensitivity: mm(down),
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn default_key_is_valid() {
+        assert!(validate(&config(HKey::default())).is_valid());
+    }
+
+    #[test]
+    fn hysteresis_bounds() {
+        // the default capabilities have 4mm of travel and a tolerance of 0.1mm
+        let cases = [
+            (10, 20, true),
+            (9, 20, false),
+            (390, 400, true),
+            (391, 400, false),
+            (380, 401, false),
+            (10, 19, false),
+        ];
+        for (lower, upper, valid) in cases {
+            let report = validate(&config(hysteresis(lower, upper)));
+            assert_eq!(report.is_valid(), valid, "lower {lower}, upper {upper}");
+        }
+    }
+
+    #[test]
+    fn hysteresis_tolerance() {
+        let cases = [
+            (100, 110, true),
+            (100, 109, false),
+            (100, 100, false),
+            (110, 100, false),
+        ];
+        for (lower, upper, valid) in cases {
+            let report = validate(&config(hysteresis(lower, upper)));
+            let problem = report.field(0, Field::HysteresisUpper).map(|i| i.problem);
+            let expected = (!valid).then_some(Problem::TooClose { tolerance: mm(10) });
+            assert_eq!(problem, expected, "lower {lower}, upper {upper}");
+            assert!(report.field(0, Field::HysteresisLower).is_none());
+        }
+    }
+
+    #[test]
+    fn sensitivity_bounds() {
+        let cases = [
+            (1, 400, true),
+            (0, 1, false),
+            (1, 0, false),
+            (401, 1, false),
+            (1, 401, false),
+        ];
+        for (up, down, valid) in cases {
+            let report = validate(&config(sensitivity(up, down)));
+            assert_eq!(report.is_valid(), valid, "up {up}, down {down}");
+        }
+    }
+
+    #[test]
+    fn one_issue_per_field() {
+        // out of range and too close to the lower hysteresis
+        let report = validate(&config(hysteresis(395, 0)));
+        assert_eq!(report.len(), 2);
+        assert_eq!(
+            report.field(0, Field::HysteresisLower).map(|i| i.problem),
+            Some(Problem::OutOfRange {
+                min: mm(10),
+                max: mm(390)
+            })
+        );
+        assert!(!report.key_is_valid(0));
+        assert!(report.key_is_valid(1));
+    }
+}
@@ -0,0 +1,88 @@
+use egui::{Key, KeyboardShortcut, Modifiers};
+
+use super::{i18n::tr, Crustility};
+
+/// write the edited config to the device
+pub const APPLY: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::Enter);
+/// save the edited config to the loaded profile
+pub const SAVE: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::S);
+/// read the config from the device again
+pub const RELOAD: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::R);
+
+const NUMBER_KEYS: [Key; 9] = [
+    Key::Num1,
+    Key::Num2,
+    Key::Num3,
+    Key::Num4,
+    Key::Num5,
+    Key::Num6,
+    Key::Num7,
+    Key::Num8,
+    Key::Num9,
+];
+
+/// Name read out by screen readers for buttons that only show an icon
+pub fn labeled(response: egui::Response, label: &str) -> egui::Response {
+    response.widget_info(|| egui::WidgetInfo::labeled(egui::WidgetType::Button, label));
+    response
+}
+
+/// Hover text of a button with a shortcut
+pub fn shortcut_hint(ctx: &egui::Context, text: &str, shortcut: &KeyboardShortcut) -> String {
+    format!("{text} ({})", ctx.format_shortcut(shortcut))
+}
+
+impl Crustility {
+    /// Shortcuts of the device view
+    ///
+    /// Runs after the panels so keys a focused widget used, like a key being
+    /// captured, don't trigger anything
+    pub fn keyboard_shortcuts(&mut self, ctx: &egui::Context) {
+        if self.device.is_none() {
+            return;
+        }
+        let (apply, save, reload) = ctx.input_mut(|i| {
+            (
+                i.consume_shortcut(&APPLY),
+                i.consume_shortcut(&SAVE),
+                i.consume_shortcut(&RELOAD),
+            )
+        });
+        if apply {
+            if self.device_report().is_valid() {
+                self.request_apply();
+            } else {
+                self.notifications.warning(tr!("apply-invalid"));
+            }
+        }
+        if save {
+            match self.settings.last_profile.clone() {
+                Some(name) => self.save_profile(&name),
+                None => self.notifications.warning(tr!("profile-save-first")),
+            }
+        }
+        if reload {
+            self.read_config();
+        }
+
+        // plain keys would end up in text fields
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+        let key_count = self
+            .device
+            .and_then(|handle| self.devices.get(&handle))
+            .and_then(|device| device.config())
+            .map_or(0, |config| config.hkeys.len());
+        ctx.input_mut(|i| {
+            for (n, key) in NUMBER_KEYS.into_iter().enumerate().take(key_count) {
+                if i.consume_key(Modifiers::NONE, key) {
+                    self.selected_key = Some(n);
+                }
+            }
+            if i.consume_key(Modifiers::NONE, Key::Escape) {
+                self.selected_key = None;
+            }
+        });
+    }
+}
@@ -0,0 +1,312 @@
+use std::time::Instant;
+
+use crate::{
+    focus::{self, Focus, FocusEvent, FocusWatcher},
+    rules::{MatchOn, Rule, Rules},
+};
+use crustility_core::profile;
+
+use super::{accessibility::labeled, i18n::tr, notify, Crustility};
+
+const MAX_LOG_LEN: usize = 100;
+
+/// Profile switch made because of a focus change
+#[derive(Debug, Clone)]
+struct Switch {
+    time: Instant,
+    app: String,
+    profile: String,
+    applied: bool,
+}
+
+/// State of the automatic profile switching
+#[derive(Default)]
+pub struct AutoSwitch {
+    rules: Rules,
+    watcher: Option<FocusWatcher>,
+    /// the detector stopped working, don't restart it every frame
+    failed: bool,
+    focus: Option<Focus>,
+    /// profile the rules picked for the focus
+    picked: Option<String>,
+    /// profile that was switched to last
+    active: Option<String>,
+    /// keep the current profile whatever is focused
+    manual_override: bool,
+    log: Vec<Switch>,
+    pub show: bool,
+}
+
+impl AutoSwitch {
+    pub fn load() -> Self {
+        Self {
+            rules: Rules::load(),
+            ..Default::default()
+        }
+    }
+}
+
+impl Crustility {
+    /// Switch profiles on focus changes
+    pub fn poll_autoswitch(&mut self, ctx: &egui::Context) {
+        let switch = &mut self.autoswitch;
+        if !switch.rules.enabled {
+            switch.watcher = None;
+            switch.failed = false;
+            return;
+        }
+        if switch.watcher.is_none() && !switch.failed {
+            match focus::detect() {
+                Some(detector) => {
+                    switch.watcher = Some(FocusWatcher::spawn(
+                        detector,
+                        switch.rules.clone(),
+                        ctx.clone(),
+                    ))
+                }
+                None => {
+                    switch.failed = true;
+                    self.notifications.warning(tr!("autoswitch-unsupported"));
+                    return;
+                }
+            }
+        }
+
+        while let Some(event) = self.autoswitch.watcher.as_ref().and_then(|w| w.try_recv()) {
+            match event {
+                FocusEvent::Changed { focus, profile } => {
+                    self.autoswitch.focus = focus;
+                    self.autoswitch.picked = profile;
+                    self.switch_profile();
+                }
+                FocusEvent::Error(e) => {
+                    self.autoswitch.watcher = None;
+                    self.autoswitch.failed = true;
+                    self.notifications.error(tr!("autoswitch-focus-error"), &e);
+                }
+            }
+        }
+    }
+
+    /// Apply the profile the rules pick for the focused application
+    fn switch_profile(&mut self) {
+        let switch = &self.autoswitch;
+        if switch.manual_override {
+            return;
+        }
+        let Some(profile) = switch.picked.clone() else {
+            return;
+        };
+        if switch.active == switch.picked {
+            return;
+        }
+        let app = switch
+            .focus
+            .as_ref()
+            .map_or_else(|| tr!("autoswitch-nothing"), |focus| focus.process.clone());
+
+        // switches happen without asking, and wait while the user answers another dialog
+        let applied = self.confirm.is_none() && self.load_profile(&profile) && self.apply_config();
+
+        let switch = &mut self.autoswitch;
+        // a failed switch is tried again on the next focus change
+        if applied {
+            switch.active = Some(profile.clone());
+        }
+        if switch.log.len() == MAX_LOG_LEN {
+            switch.log.remove(0);
+        }
+        switch.log.push(Switch {
+            time: Instant::now(),
+            app,
+            profile,
+            applied,
+        });
+    }
+
+    pub fn autoswitch_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.autoswitch.show;
+        let mut rules = self.autoswitch.rules.clone();
+        let profiles = if open {
+            profile::list().unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+        let switch = &mut self.autoswitch;
+
+        egui::Window::new(tr!("autoswitch-title"))
+            .open(&mut open)
+            .default_width(420.)
+            .show(ctx, |ui| {
+                ui.checkbox(&mut rules.enabled, tr!("autoswitch-enable"));
+                if let Some(watcher) = &switch.watcher {
+                    ui.weak(tr!(
+                        "autoswitch-detector",
+                        detector = watcher.detector_name()
+                    ));
+                } else if switch.failed {
+                    ui.colored_label(ui.visuals().error_fg_color, tr!("autoswitch-undetectable"));
+                }
+                match &switch.focus {
+                    Some(focus) => ui.label(tr!(
+                        "autoswitch-focused",
+                        process = focus.process.as_str(),
+                        title = focus.title.as_str()
+                    )),
+                    None => ui.label(tr!("autoswitch-focused-nothing")),
+                };
+                let response = ui
+                    .checkbox(&mut switch.manual_override, tr!("autoswitch-override"))
+                    .on_hover_text(tr!("autoswitch-override-hint"));
+                if response.changed() && !switch.manual_override {
+                    // pick the profile for the focused application again
+                    switch.active = None;
+                }
+                ui.separator();
+
+                let mut removed = None;
+                egui::Grid::new("autoswitch rules")
+                    .num_columns(5)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.label("");
+                        ui.strong(tr!("autoswitch-match"));
+                        ui.strong(tr!("autoswitch-contains"));
+                        ui.strong(tr!("autoswitch-profile"));
+                        ui.end_row();
+                        for (i, rule) in rules.rules.iter_mut().enumerate() {
+                            ui.checkbox(&mut rule.enabled, "");
+                            egui::ComboBox::from_id_source(("rule match", i))
+                                .selected_text(match_on_name(rule.matches))
+                                .show_ui(ui, |ui| {
+                                    for matches in [MatchOn::Process, MatchOn::Title] {
+                                        ui.selectable_value(
+                                            &mut rule.matches,
+                                            matches,
+                                            match_on_name(matches),
+                                        );
+                                    }
+                                });
+                            ui.add(
+                                egui::TextEdit::singleline(&mut rule.pattern).desired_width(120.),
+                            );
+                            profile_combo(ui, ("rule profile", i), &profiles, &mut rule.profile);
+                            let remove = ui
+                                .small_button("🗑")
+                                .on_hover_text(tr!("autoswitch-remove-hint"));
+                            if labeled(remove, &tr!("autoswitch-remove")).clicked() {
+                                removed = Some(i);
+                            }
+                            ui.end_row();
+                        }
+                    });
+                if let Some(i) = removed {
+                    rules.rules.remove(i);
+                }
+
+                ui.horizontal(|ui| {
+                    let first_profile = profiles.first().cloned().unwrap_or_default();
+                    if ui.button(tr!("autoswitch-add-rule")).clicked() {
+                        rules.rules.push(Rule::new(
+                            MatchOn::Process,
+                            String::new(),
+                            first_profile.clone(),
+                        ));
+                    }
+                    if let Some(focus) = &switch.focus {
+                        if ui
+                            .button(tr!(
+                                "autoswitch-add-focused",
+                                process = focus.process.as_str()
+                            ))
+                            .clicked()
+                        {
+                            rules.rules.push(Rule::new(
+                                MatchOn::Process,
+                                focus.process.clone(),
+                                first_profile,
+                            ));
+                        }
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label(tr!("autoswitch-fallback"));
+                    egui::ComboBox::from_id_source("fallback profile")
+                        .selected_text(
+                            rules
+                                .fallback
+                                .clone()
+                                .unwrap_or_else(|| tr!("autoswitch-keep-profile")),
+                        )
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(
+                                &mut rules.fallback,
+                                None,
+                                tr!("autoswitch-keep-profile"),
+                            );
+                            for profile in &profiles {
+                                ui.selectable_value(
+                                    &mut rules.fallback,
+                                    Some(profile.clone()),
+                                    profile,
+                                );
+                            }
+                        });
+                });
+
+                ui.collapsing(tr!("autoswitch-log"), |ui| {
+                    if switch.log.is_empty() {
+                        ui.weak(tr!("autoswitch-log-empty"));
+                    }
+                    for entry in switch.log.iter().rev() {
+                        ui.horizontal(|ui| {
+                            ui.weak(notify::format_age(entry.time.elapsed()));
+                            let text = format!("{} → {}", entry.app, entry.profile);
+                            if entry.applied {
+                                ui.label(text);
+                            } else {
+                                ui.colored_label(ui.visuals().error_fg_color, text)
+                                    .on_hover_text(tr!("autoswitch-not-applied"));
+                            }
+                        });
+                    }
+                });
+            });
+        self.autoswitch.show = open;
+
+        if rules != self.autoswitch.rules {
+            if let Some(watcher) = &self.autoswitch.watcher {
+                watcher.set_rules(rules.clone());
+            }
+            self.autoswitch.rules = rules;
+            // pick again on the next focus change
+            self.autoswitch.active = None;
+            if let Err(e) = self.autoswitch.rules.save() {
+                self.notifications.error(tr!("autoswitch-save-error"), &e);
+            }
+        }
+    }
+}
+
+fn match_on_name(matches: MatchOn) -> String {
+    match matches {
+        MatchOn::Process => tr!("autoswitch-match-process"),
+        MatchOn::Title => tr!("autoswitch-match-title"),
+    }
+}
+
+fn profile_combo(
+    ui: &mut egui::Ui,
+    id: impl std::hash::Hash,
+    profiles: &[String],
+    value: &mut String,
+) {
+    egui::ComboBox::from_id_source(id)
+        .selected_text(value.as_str())
+        .show_ui(ui, |ui| {
+            for profile in profiles {
+                ui.selectable_value(value, profile.clone(), profile);
+            }
+        });
+}
@@ -0,0 +1,226 @@
+use crustility_core::{
+    backup::{self, Backup, Reason},
+    device::{self, Device, DeviceHandle},
+};
+
+use super::{accessibility::labeled, i18n::tr, Confirm, Crustility};
+
+/// Browser for the backups of the selected device
+#[derive(Default)]
+pub struct Backups {
+    pub show: bool,
+    /// backups of the device they were read for, read again when `None`
+    list: Option<(DeviceHandle, Vec<Backup>)>,
+}
+
+impl Backups {
+    /// Read the list again the next time it is shown
+    pub fn refresh(&mut self) {
+        self.list = None;
+    }
+}
+
+/// Save the config a device has before changing it
+///
+/// Does nothing for a device whose config was never read, there is nothing to keep
+pub fn take(handle: &DeviceHandle, device: &Device, reason: Reason) -> Result<(), backup::Error> {
+    let Some(config) = device.synced_config() else {
+        return Ok(());
+    };
+    backup::create(
+        &device.storage_identity(handle),
+        device.name(),
+        reason,
+        config,
+    )
+    .map(|_| ())
+}
+
+enum Action {
+    Load(usize),
+    Restore(usize),
+    Delete(usize),
+}
+
+impl Crustility {
+    pub fn backup_menu(&mut self, ui: &mut egui::Ui) {
+        ui.menu_button(tr!("menu-backups"), |ui| {
+            if ui.button(tr!("backups-browse")).clicked() {
+                self.backups.show = true;
+                self.backups.refresh();
+                ui.close_menu();
+            }
+            ui.separator();
+            if ui
+                .button(tr!("factory-reset"))
+                .on_hover_text(tr!("factory-reset-hint"))
+                .clicked()
+            {
+                // always asked, there is no undo besides the backup
+                self.confirm = Some(Confirm::Reset);
+                ui.close_menu();
+            }
+        });
+    }
+
+    /// Back up the selected device and restore the firmware defaults
+    pub fn reset_device(&mut self) {
+        let Some(handle) = self.device else {
+            return;
+        };
+        let Some(device) = self.devices.get_mut(&handle) else {
+            return;
+        };
+        if let Err(e) = take(&handle, device, Reason::Reset) {
+            self.notifications.error(tr!("backup-error"), &e);
+            return;
+        }
+        self.backups.refresh();
+
+        let name = device.name().clone();
+        let result = device.send_event(device::SendEvent::Reset);
+        if self
+            .consume_error(&tr!("factory-reset-error"), result)
+            .is_some()
+        {
+            self.notifications
+                .success(tr!("factory-reset-done", device = name.as_str()));
+            self.read_config();
+        }
+    }
+
+    pub fn backups_window(&mut self, ctx: &egui::Context) {
+        if !self.backups.show {
+            return;
+        }
+        let Some(handle) = self.device else {
+            return;
+        };
+        let Some(device) = self.devices.get(&handle) else {
+            return;
+        };
+        let identity = device.storage_identity(&handle);
+        if self.backups.list.as_ref().map(|(h, _)| *h) != Some(handle) {
+            match backup::list(&identity) {
+                Ok(list) => self.backups.list = Some((handle, list)),
+                Err(e) => {
+                    self.notifications.error(tr!("backups-list-error"), &e);
+                    self.backups.show = false;
+                    return;
+                }
+            }
+        }
+        let Some((_, list)) = &self.backups.list else {
+            return;
+        };
+
+        let mut open = true;
+        let mut action = None;
+        egui::Window::new(tr!("backups", device = device.name().as_str()))
+            .open(&mut open)
+            .default_width(420.)
+            .show(ctx, |ui| {
+                if list.is_empty() {
+                    ui.weak(tr!("no-backups"));
+                    return;
+                }
+                egui::ScrollArea::vertical()
+                    .max_height(320.)
+                    .show(ui, |ui| {
+                        egui::Grid::new("backups")
+                            .num_columns(4)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                ui.strong(tr!("backup-taken"));
+                                ui.strong(tr!("backup-reason"));
+                                ui.strong(tr!("backup-keys"));
+                                ui.end_row();
+                                for (i, backup) in list.iter().enumerate() {
+                                    let time = backup.timestamp();
+                                    ui.label(&time);
+                                    ui.label(match backup.reason {
+                                        Reason::Apply => tr!("backup-reason-apply"),
+                                        Reason::Reset => tr!("backup-reason-reset"),
+                                    });
+                                    ui.label(backup.config.hkeys.len().to_string());
+                                    ui.horizontal(|ui| {
+                                        if ui
+                                            .small_button(tr!("backup-load"))
+                                            .on_hover_text(tr!("backup-load-hint"))
+                                            .clicked()
+                                        {
+                                            action = Some(Action::Load(i));
+                                        }
+                                        if ui
+                                            .small_button(tr!("backup-restore"))
+                                            .on_hover_text(tr!("backup-restore-hint"))
+                                            .clicked()
+                                        {
+                                            action = Some(Action::Restore(i));
+                                        }
+                                        let delete = ui.small_button("🗑");
+                                        if labeled(
+                                            delete,
+                                            &tr!("delete-backup", time = time.as_str()),
+                                        )
+                                        .clicked()
+                                        {
+                                            action = Some(Action::Delete(i));
+                                        }
+                                    });
+                                    ui.end_row();
+                                }
+                            });
+                    });
+            });
+        self.backups.show = open;
+
+        match action {
+            Some(Action::Load(i)) => {
+                self.load_backup(i);
+            }
+            Some(Action::Restore(i)) if self.load_backup(i) => {
+                self.request_apply();
+            }
+            Some(Action::Delete(i)) => {
+                let Some((_, list)) = &self.backups.list else {
+                    return;
+                };
+                if let Err(e) = backup::delete(&identity, &list[i].id) {
+                    self.notifications.error(tr!("backup-delete-error"), &e);
+                }
+                self.backups.refresh();
+            }
+            Some(Action::Restore(_)) | None => (),
+        }
+    }
+
+    /// Load a backup from the list into the config of the selected device, returns if it was loaded
+    fn load_backup(&mut self, i: usize) -> bool {
+        let Some(backup) = self.backups.list.as_ref().and_then(|(_, list)| list.get(i)) else {
+            return false;
+        };
+        let Some(config) = self
+            .device
+            .and_then(|handle| self.devices.get_mut(&handle))
+            .and_then(|device| device.config_mut())
+        else {
+            return false;
+        };
+
+        let copied = config.apply_settings(&backup.config);
+        if backup.config.hkeys.len() != config.hkeys.len() {
+            self.notifications.warning(tr!(
+                "backup-key-count",
+                backup = backup.config.hkeys.len(),
+                device = config.hkeys.len()
+            ));
+        }
+        self.notifications.info(tr!(
+            "backup-loaded",
+            time = backup.timestamp(),
+            count = copied
+        ));
+        true
+    }
+}
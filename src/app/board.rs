@@ -0,0 +1,521 @@
+use std::{path::Path, time::Duration};
+
+use egui::{emath::Rot2, vec2, Align2, Color32, FontId, Pos2, Rect, Shape, Stroke, Vec2};
+
+use crustility_core::{
+    board::{Board, KeyKind, Placement},
+    config::{Millimeter, Rounding},
+    validate,
+};
+
+use super::{i18n::tr, Crustility};
+
+/// pixels per key unit at zoom 1
+const UNIT: f32 = 60.;
+/// room at the top of a key for its number and the readout
+const HEADER_HEIGHT: f32 = 34.;
+/// height of the area a threshold line can be grabbed in
+const GRAB_HEIGHT: f32 = 8.;
+/// Width of the outline around the focused key
+const FOCUS_WIDTH: f32 = 3.;
+const MIN_ZOOM: f32 = 0.25;
+const MAX_ZOOM: f32 = 4.;
+
+/// Zoom and pan of the board view
+#[derive(Debug, Clone, Copy)]
+pub struct BoardView {
+    zoom: f32,
+    pan: Vec2,
+}
+
+impl Default for BoardView {
+    fn default() -> Self {
+        Self {
+            zoom: 1.,
+            pan: Vec2::ZERO,
+        }
+    }
+}
+
+impl BoardView {
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Zoom while keeping the point under the pointer in place
+    ///
+    /// `anchor` is where the top left corner of the board bounds is drawn, the point that
+    /// stays put when zooming without panning
+    fn zoom_around(&mut self, factor: f32, pointer: Pos2, anchor: Pos2) {
+        let zoom = (self.zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+        let factor = zoom / self.zoom;
+        self.pan += (pointer - anchor) * (1. - factor);
+        self.zoom = zoom;
+    }
+}
+
+/// Key placed on the screen, maps key local coordinates in `0.0..=1.0` to screen positions
+#[derive(Debug, Clone, Copy)]
+struct KeyFrame {
+    center: Pos2,
+    size: Vec2,
+    rotation: Rot2,
+}
+
+impl KeyFrame {
+    fn new(placement: &Placement, origin: Pos2, scale: f32) -> Self {
+        Self {
+            center: origin
+                + vec2(
+                    placement.x + placement.width / 2.,
+                    placement.y + placement.height / 2.,
+                ) * scale,
+            size: vec2(placement.width, placement.height) * scale,
+            rotation: Rot2::from_angle(placement.rotation.to_radians()),
+        }
+    }
+
+    fn point(&self, x: f32, y: f32) -> Pos2 {
+        self.center + self.rotation * (vec2(x - 0.5, y - 0.5) * self.size)
+    }
+
+    fn local(&self, pos: Pos2) -> Vec2 {
+        self.rotation.inverse() * (pos - self.center) / self.size + vec2(0.5, 0.5)
+    }
+
+    fn contains(&self, pos: Pos2) -> bool {
+        let local = self.local(pos);
+        (0.0..=1.).contains(&local.x) && (0.0..=1.).contains(&local.y)
+    }
+
+    /// Screen rectangle around the part of the key between `top` and `bottom`
+    fn bounds(&self, top: f32, bottom: f32) -> Rect {
+        Rect::from_points(&[
+            self.point(0., top),
+            self.point(1., top),
+            self.point(0., bottom),
+            self.point(1., bottom),
+        ])
+    }
+
+    fn band(&self, top: f32, bottom: f32, fill: Color32, stroke: Stroke) -> Shape {
+        Shape::convex_polygon(
+            vec![
+                self.point(0., top),
+                self.point(1., top),
+                self.point(1., bottom),
+                self.point(0., bottom),
+            ],
+            fill,
+            stroke,
+        )
+    }
+
+    fn line(&self, y: f32, stroke: Stroke) -> Shape {
+        Shape::line_segment([self.point(0., y), self.point(1., y)], stroke)
+    }
+
+    fn header(&self) -> f32 {
+        (HEADER_HEIGHT / self.size.y).min(0.5)
+    }
+
+    /// Local height of a position, positions are the distance from the bottom
+    fn position_y(&self, travel: Millimeter, position: Millimeter) -> f32 {
+        let fraction = (position.as_mm() / travel.as_mm()).clamp(0., 1.) as f32;
+        let header = self.header();
+        header + (1. - fraction) * (1. - header)
+    }
+
+    /// Position at a local height, snapped to 0.01 mm
+    fn y_position(&self, travel: Millimeter, y: f32) -> Millimeter {
+        let header = self.header();
+        let fraction = (1. - (y - header) / (1. - header)).clamp(0., 1.) as f64;
+        Millimeter::from_mm(fraction * travel.as_mm(), Rounding::Nearest)
+            .unwrap_or(Millimeter::ZERO)
+    }
+}
+
+enum LayoutAction {
+    Load,
+    Save,
+    UseDefault,
+}
+
+impl Crustility {
+    /// Keys of the selected device placed like on the physical board
+    pub fn board(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) {
+        let Some(handle) = self.device else {
+            return;
+        };
+        let Some(device) = self.devices.get_mut(&handle) else {
+            return;
+        };
+        let board = device.board().into_owned();
+        let Some(config) = device.config_mut() else {
+            return;
+        };
+        let report = validate::validate(config);
+        let travel = config.capabilities.travel_distance;
+        // 60 fps target
+        ctx.request_repaint_after(Duration::from_millis(10));
+
+        let (rect, _) = ui.allocate_exact_size(ui.available_size(), egui::Sense::hover());
+        let painter = ui.painter_at(rect);
+        let visuals = ui.visuals();
+        let scale = UNIT * self.board_view.zoom;
+        let (min, _) = board.bounds();
+        let anchor = rect.min + vec2(10., 10.) + self.board_view.pan;
+        // board coordinates can start anywhere, the bounds are moved to the anchor
+        let origin = anchor - vec2(min[0], min[1]) * scale;
+
+        for key in &mut config.hkeys {
+            key.current_position =
+                key.current_position + (key.target_position - key.current_position) / 2;
+        }
+
+        for placement in &board.keys {
+            let frame = KeyFrame::new(placement, origin, scale);
+            let header = frame.header();
+            let label_pos = frame.point(0.5, header * 0.3);
+            let outline = visuals.widgets.noninteractive.bg_stroke;
+
+            let key = match placement.kind {
+                KeyKind::Hkey => config.hkeys.get_mut(placement.index),
+                KeyKind::Dkey => {
+                    painter.add(frame.band(0., 1., visuals.widgets.inactive.bg_fill, outline));
+                    painter.text(
+                        frame.point(0.5, 0.5),
+                        Align2::CENTER_CENTER,
+                        format!("D{}", placement.index + 1),
+                        FontId::proportional(14.),
+                        visuals.text_color(),
+                    );
+                    continue;
+                }
+            };
+            let Some(key) = key else {
+                // the layout has more keys than the device
+                painter.add(frame.band(
+                    0.,
+                    1.,
+                    Color32::TRANSPARENT,
+                    Stroke::new(1., visuals.error_fg_color),
+                ));
+                painter.text(
+                    label_pos,
+                    Align2::CENTER_CENTER,
+                    "?",
+                    FontId::proportional(14.),
+                    visuals.error_fg_color,
+                );
+                continue;
+            };
+            let i = placement.index;
+            let y = |position| frame.position_y(travel, position);
+            let is_selected = self.selected_key == Some(i);
+
+            //background
+            painter.add(frame.band(0., 1., visuals.widgets.inactive.bg_fill, Stroke::NONE));
+
+            //input
+            painter.add(frame.band(
+                header,
+                y(key.current_position),
+                if is_selected {
+                    visuals.widgets.active.weak_bg_fill
+                } else {
+                    visuals.widgets.inactive.weak_bg_fill
+                },
+                Stroke::NONE,
+            ));
+
+            //rapid trigger, distance to the next press or release
+            if let Some(rt) = &key.rt {
+                let (from, to) = if key.trigger.pressed {
+                    (
+                        key.current_position,
+                        key.current_position + rt.up_sensitivity,
+                    )
+                } else {
+                    (
+                        key.current_position - rt.down_sensitivity,
+                        key.current_position,
+                    )
+                };
+                painter.add(frame.band(
+                    y(to),
+                    y(from),
+                    visuals.selection.bg_fill.gamma_multiply(0.4),
+                    Stroke::NONE,
+                ));
+            }
+
+            //hysteresis, press below the lower line and release above the upper one
+            let id = ui.make_persistent_id(("threshold", i));
+            for (id, value, name, stroke) in [
+                (
+                    id.with("lower"),
+                    &mut key.hysterisis.lower,
+                    tr!("actuation"),
+                    Stroke::new(2., visuals.selection.bg_fill),
+                ),
+                (
+                    id.with("upper"),
+                    &mut key.hysterisis.upper,
+                    tr!("release"),
+                    Stroke::new(1., visuals.widgets.noninteractive.fg_stroke.color),
+                ),
+            ] {
+                let response = threshold_handle(ui, id, &frame, travel, value, &name);
+                let stroke = if response.hovered() || response.dragged() || response.has_focus() {
+                    Stroke::new(stroke.width + 2., stroke.color)
+                } else {
+                    stroke
+                };
+                painter.add(frame.line(y(*value), stroke));
+            }
+
+            //number and how far the key is pressed down
+            painter.text(
+                label_pos,
+                Align2::CENTER_CENTER,
+                format!("{}", i + 1),
+                FontId::proportional(14.),
+                if report.key_is_valid(i) {
+                    visuals.strong_text_color()
+                } else {
+                    visuals.error_fg_color
+                },
+            );
+            painter.text(
+                frame.point(0.5, header * 0.75),
+                Align2::CENTER_CENTER,
+                format!("{:.2}", (travel - key.target_position).as_mm()),
+                FontId::monospace(10.),
+                if key.trigger.pressed {
+                    visuals.strong_text_color()
+                } else {
+                    visuals.weak_text_color()
+                },
+            );
+
+            //pressed indicator
+            painter.circle(
+                frame.point(0.85, header * 0.3),
+                3. * self.board_view.zoom.min(1.),
+                if key.trigger.pressed {
+                    visuals.selection.bg_fill
+                } else {
+                    Color32::TRANSPARENT
+                },
+                visuals.widgets.noninteractive.fg_stroke,
+            );
+
+            // focusable, tab moves between the keys and enter or space selects them
+            let response = ui.interact(frame.bounds(0., 1.), id.with("key"), egui::Sense::click());
+            response.widget_info(|| {
+                let state = if key.trigger.pressed {
+                    tr!("key-pressed")
+                } else {
+                    tr!("key-released")
+                };
+                egui::WidgetInfo::selected(
+                    egui::WidgetType::SelectableLabel,
+                    is_selected,
+                    tr!(
+                        "key-description",
+                        key = i + 1,
+                        state = state,
+                        actuation = (travel - key.hysterisis.lower).to_string(),
+                        release = (travel - key.hysterisis.upper).to_string(),
+                    ),
+                )
+            });
+            let hovered = response.hovered()
+                && response
+                    .hover_pos()
+                    .is_some_and(|pointer| frame.contains(pointer));
+            let outline = if response.has_focus() {
+                focus_outline(visuals)
+            } else if is_selected {
+                visuals.selection.stroke
+            } else if hovered {
+                visuals.widgets.hovered.bg_stroke
+            } else {
+                outline
+            };
+            painter.add(frame.band(0., 1., Color32::TRANSPARENT, outline));
+            // clicks from the keyboard have no pointer position to check
+            let pointer_click = ui.input(|i| i.pointer.any_click());
+            if response.clicked() && (hovered || !pointer_click) {
+                self.selected_key = if is_selected { None } else { Some(i) };
+            }
+        }
+
+        // pan and zoom last so the keys and their handles get the pointer first
+        let response = ui.interact(rect, ui.id().with("board view"), egui::Sense::drag());
+        if response.dragged() {
+            self.board_view.pan += response.drag_delta();
+        }
+        if ui.rect_contains_pointer(rect) {
+            let (scroll, zoom, pointer) =
+                ui.input(|i| (i.scroll_delta.y, i.zoom_delta(), i.pointer.hover_pos()));
+            let factor = zoom * (scroll / 200.).exp();
+            if let Some(pointer) = pointer.filter(|_| factor != 1.) {
+                self.board_view.zoom_around(factor, pointer, anchor);
+            }
+        }
+    }
+
+    /// Loading and saving the layout of the selected device
+    pub fn layout_menu(&mut self, ui: &mut egui::Ui) {
+        let Some(handle) = self.device else {
+            return;
+        };
+        let Some(device) = self.devices.get(&handle) else {
+            return;
+        };
+        let id = ui.make_persistent_id("layout file");
+        let mut path = ui
+            .ctx()
+            .data_mut(|d| d.get_temp::<String>(id))
+            .unwrap_or_default();
+        let mut action = None;
+
+        ui.menu_button(tr!("menu-layout"), |ui| {
+            ui.horizontal(|ui| {
+                ui.label(tr!("file"));
+                ui.add(egui::TextEdit::singleline(&mut path).hint_text("layout.toml"));
+            });
+            ui.horizontal(|ui| {
+                if ui.button(tr!("load")).clicked() {
+                    action = Some(LayoutAction::Load);
+                }
+                if ui.button(tr!("save")).clicked() {
+                    action = Some(LayoutAction::Save);
+                }
+            });
+            ui.separator();
+            if ui
+                .add_enabled(
+                    device.has_custom_board(),
+                    egui::Button::new(tr!("use-default-layout")),
+                )
+                .clicked()
+            {
+                action = Some(LayoutAction::UseDefault);
+            }
+            if ui.button(tr!("reset-view")).clicked() {
+                self.board_view.reset();
+            }
+        });
+
+        match action {
+            Some(LayoutAction::Load) => match Board::load(Path::new(&path)) {
+                Ok(board) => {
+                    let result = self.devices.set_board(&handle, Some(board));
+                    self.consume_error(&tr!("layout-store-error"), result);
+                    self.board_view.reset();
+                }
+                Err(e) => self.notifications.error(tr!("layout-load-error"), &e),
+            },
+            Some(LayoutAction::Save) => match device.board().save(Path::new(&path)) {
+                Ok(()) => self
+                    .notifications
+                    .success(tr!("layout-saved", path = path.as_str())),
+                Err(e) => self.notifications.error(tr!("layout-save-error"), &e),
+            },
+            Some(LayoutAction::UseDefault) => {
+                let result = self.devices.set_board(&handle, None);
+                self.consume_error(&tr!("layout-store-error"), result);
+            }
+            None => (),
+        }
+        ui.ctx().data_mut(|d| d.insert_temp(id, path));
+    }
+}
+
+/// Outline of the key with keyboard focus, the selection stroke of the themes is invisible
+fn focus_outline(visuals: &egui::Visuals) -> Stroke {
+    Stroke::new(FOCUS_WIDTH, visuals.selection.bg_fill)
+}
+
+/// Draggable threshold line, arrow keys nudge it while it has focus
+fn threshold_handle(
+    ui: &egui::Ui,
+    id: egui::Id,
+    frame: &KeyFrame,
+    travel: Millimeter,
+    value: &mut Millimeter,
+    name: &str,
+) -> egui::Response {
+    let line_y = frame.position_y(travel, *value);
+    let grab = GRAB_HEIGHT / 2. / frame.size.y;
+    let response = ui.interact(
+        frame.bounds(line_y - grab, line_y + grab),
+        id,
+        egui::Sense::drag(),
+    );
+
+    response.widget_info(|| {
+        egui::WidgetInfo::slider(value.as_mm(), tr!("threshold-description", name = name))
+    });
+    if response.drag_started() {
+        response.request_focus();
+    }
+    if response.dragged() {
+        if let Some(pointer) = response.interact_pointer_pos() {
+            *value = frame.y_position(travel, frame.local(pointer).y);
+        }
+    }
+    if response.has_focus() {
+        let step = Millimeter::from_hundredths(if ui.input(|i| i.modifiers.shift) {
+            10
+        } else {
+            1
+        });
+        let (up, down) = ui.input(|i| {
+            (
+                i.key_pressed(egui::Key::ArrowUp),
+                i.key_pressed(egui::Key::ArrowDown),
+            )
+        });
+        if up {
+            *value = (*value + step).min(travel);
+        }
+        if down {
+            *value = (*value - step).max(Millimeter::ZERO);
+        }
+    }
+
+    if response.hovered() || response.dragged() {
+        ui.ctx().set_cursor_icon(egui::CursorIcon::ResizeVertical);
+        egui::show_tooltip_text(ui.ctx(), id.with("tooltip"), format!("{name} {value}"));
+    } else if response.has_focus() {
+        egui::show_tooltip_text(
+            ui.ctx(),
+            id.with("tooltip"),
+            tr!(
+                "threshold-keyboard-hint",
+                name = name,
+                value = value.to_string()
+            ),
+        );
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::theme;
+
+    #[test]
+    fn focus_outline_is_visible() {
+        for theme in theme::built_in() {
+            let outline = focus_outline(&theme.palette.visuals());
+            assert!(outline.width > 0., "{}", theme.name);
+            assert_eq!(outline.color.a(), 255, "{}", theme.name);
+        }
+    }
+}
@@ -0,0 +1,335 @@
+use std::collections::{HashMap, HashSet};
+
+use crustility_core::{
+    compare::{self, Setting, Value},
+    config::Config,
+    device::{self, DeviceHandle},
+    profile,
+};
+
+use super::{accessibility::labeled, dashboard, i18n::tr, Crustility};
+
+/// Config that can be compared
+#[derive(Debug, Clone, PartialEq)]
+enum Source {
+    Device(DeviceHandle),
+    Profile(String),
+}
+
+/// Side by side differences of two configs
+#[derive(Default)]
+pub struct Compare {
+    pub show: bool,
+    left: Option<Source>,
+    right: Option<Source>,
+    /// differences picked to be copied from left to right
+    selected: HashSet<(usize, Setting)>,
+    /// names of the stored profiles, read again when `None`
+    profiles: Option<Vec<String>>,
+    /// profiles read so far, so they aren't read from disk every frame
+    profile_configs: HashMap<String, Result<Config, String>>,
+}
+
+impl Compare {
+    /// Read the profiles again the next time they are needed
+    fn refresh(&mut self) {
+        self.profiles = None;
+        self.profile_configs.clear();
+    }
+}
+
+impl Crustility {
+    fn source_name(&self, source: &Source) -> String {
+        match source {
+            Source::Device(handle) => match self.devices.get(handle) {
+                Some(device) => dashboard::device_name(handle, device),
+                None => tr!("disconnected"),
+            },
+            Source::Profile(name) => tr!("compare-profile", name = name.as_str()),
+        }
+    }
+
+    fn source_config(&mut self, source: &Source) -> Result<Config, String> {
+        match source {
+            Source::Device(handle) => self
+                .devices
+                .get(handle)
+                .ok_or_else(|| tr!("compare-disconnected"))?
+                .config()
+                .cloned()
+                .ok_or_else(|| tr!("compare-reading")),
+            Source::Profile(name) => self
+                .compare
+                .profile_configs
+                .entry(name.clone())
+                .or_insert_with(|| profile::load(name).map_err(|e| e.to_string()))
+                .clone(),
+        }
+    }
+
+    /// Make sure a device that gets compared has its config read
+    fn read_source(&mut self, source: &Source) {
+        let Source::Device(handle) = source else {
+            return;
+        };
+        let Some(device) = self.devices.get_mut(handle) else {
+            return;
+        };
+        if device.config().is_some() {
+            return;
+        }
+        let result = device
+            .spawn_event_loop()
+            .and_then(|_| device.send_event(device::SendEvent::ReadConfig));
+        self.consume_error(&tr!("config-read-error"), result);
+    }
+
+    /// Copy the selected settings from the left config into the right one
+    fn copy_selected(&mut self, left: &Config) {
+        let Some(right) = self.compare.right.clone() else {
+            return;
+        };
+        let selected = std::mem::take(&mut self.compare.selected);
+        let copy = |config: &mut Config| {
+            selected
+                .iter()
+                .filter(|(key, setting)| compare::copy(left, config, *key, *setting))
+                .count()
+        };
+        let skipped = |copied: usize| selected.len() - copied;
+
+        match &right {
+            Source::Device(handle) => {
+                let Some(config) = self.devices.get_mut(handle).and_then(|d| d.config_mut()) else {
+                    return;
+                };
+                let copied = copy(config);
+                self.notifications.info(tr!(
+                    "compare-copied-device",
+                    count = copied,
+                    target = self.source_name(&right)
+                ));
+                self.warn_skipped(skipped(copied));
+            }
+            Source::Profile(name) => {
+                let mut config = match profile::load(name) {
+                    Ok(config) => config,
+                    Err(e) => {
+                        self.notifications
+                            .error(tr!("profile-load-error", name = name.as_str()), &e);
+                        return;
+                    }
+                };
+                let copied = copy(&mut config);
+                match profile::save(name, &config) {
+                    Ok(()) => {
+                        self.notifications.success(tr!(
+                            "compare-copied-profile",
+                            count = copied,
+                            name = name.as_str()
+                        ));
+                        self.warn_skipped(skipped(copied));
+                    }
+                    Err(e) => self
+                        .notifications
+                        .error(tr!("profile-save-error", name = name.as_str()), &e),
+                }
+                self.compare.refresh();
+            }
+        }
+    }
+
+    /// Tell about selected settings the right config has no place for
+    fn warn_skipped(&mut self, skipped: usize) {
+        if skipped > 0 {
+            self.notifications
+                .warning(tr!("compare-skipped", count = skipped));
+        }
+    }
+
+    pub fn compare_window(&mut self, ctx: &egui::Context) {
+        if !self.compare.show {
+            // profiles may change while the window is closed
+            self.compare.refresh();
+            return;
+        }
+        let mut open = true;
+        let mut sources = self
+            .devices
+            .iter()
+            .map(|handle| Source::Device(*handle))
+            .collect::<Vec<_>>();
+        let profiles = self
+            .compare
+            .profiles
+            .get_or_insert_with(|| profile::list().unwrap_or_default());
+        sources.extend(profiles.iter().cloned().map(Source::Profile));
+        let names = sources
+            .iter()
+            .map(|source| self.source_name(source))
+            .collect::<Vec<_>>();
+
+        let left = self.compare.left.clone().map(|s| self.source_config(&s));
+        let right = self.compare.right.clone().map(|s| self.source_config(&s));
+        let mut left_source = self.compare.left.clone();
+        let mut right_source = self.compare.right.clone();
+        let mut copy = false;
+
+        egui::Window::new(tr!("compare-title"))
+            .open(&mut open)
+            .default_width(520.)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    source_combo(ui, "compare left", &sources, &names, &mut left_source);
+                    let swap = ui.button("⇄").on_hover_text(tr!("compare-swap-hint"));
+                    if labeled(swap, &tr!("compare-swap")).clicked() {
+                        std::mem::swap(&mut left_source, &mut right_source);
+                    }
+                    source_combo(ui, "compare right", &sources, &names, &mut right_source);
+                });
+                ui.separator();
+
+                let (left, right) = match (&left, &right) {
+                    (Some(Ok(left)), Some(Ok(right))) => (left, right),
+                    (Some(Err(e)), _) | (_, Some(Err(e))) => {
+                        ui.weak(e);
+                        return;
+                    }
+                    _ => {
+                        ui.weak(tr!("compare-pick"));
+                        return;
+                    }
+                };
+                let differences = compare::diff(left, right);
+                if differences.is_empty() {
+                    ui.label(tr!("compare-same"));
+                    return;
+                }
+
+                let selected = &mut self.compare.selected;
+                egui::ScrollArea::vertical()
+                    .max_height(320.)
+                    .show(ui, |ui| {
+                        egui::Grid::new("differences")
+                            .num_columns(5)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                ui.label("");
+                                ui.strong(tr!("compare-key"));
+                                ui.strong(tr!("compare-setting"));
+                                ui.strong(tr!("compare-left"));
+                                ui.strong(tr!("compare-right"));
+                                ui.end_row();
+                                for difference in &differences {
+                                    let id = (difference.key, difference.setting);
+                                    let mut checked = selected.contains(&id);
+                                    let response = ui.add_enabled(
+                                        difference.setting != Setting::Key,
+                                        egui::Checkbox::new(&mut checked, ""),
+                                    );
+                                    if response.changed() {
+                                        if checked {
+                                            selected.insert(id);
+                                        } else {
+                                            selected.remove(&id);
+                                        }
+                                    }
+                                    ui.label((difference.key + 1).to_string());
+                                    ui.label(setting_name(difference.setting));
+                                    ui.label(value_text(difference.left.as_ref()));
+                                    ui.label(value_text(difference.right.as_ref()));
+                                    ui.end_row();
+                                }
+                            });
+                    });
+                // forget selections of differences that went away
+                selected.retain(|(key, setting)| {
+                    differences
+                        .iter()
+                        .any(|d| d.key == *key && d.setting == *setting)
+                });
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button(tr!("compare-select-all")).clicked() {
+                        selected.extend(
+                            differences
+                                .iter()
+                                .filter(|d| d.setting != Setting::Key)
+                                .map(|d| (d.key, d.setting)),
+                        );
+                    }
+                    copy = ui
+                        .add_enabled(
+                            !selected.is_empty(),
+                            egui::Button::new(tr!("compare-copy", count = selected.len())),
+                        )
+                        .clicked();
+                });
+            });
+        self.compare.show = open;
+
+        if left_source != self.compare.left || right_source != self.compare.right {
+            self.compare.selected.clear();
+            // picking a profile reads it again in case it was saved since
+            self.compare.refresh();
+            for source in [&left_source, &right_source].into_iter().flatten() {
+                self.read_source(source);
+            }
+            self.compare.left = left_source;
+            self.compare.right = right_source;
+        } else if copy {
+            if let Some(Ok(left)) = left {
+                self.copy_selected(&left);
+            }
+        }
+    }
+}
+
+/// Translated name of a setting
+pub(super) fn setting_name(setting: Setting) -> String {
+    match setting {
+        Setting::Key => tr!("setting-key"),
+        Setting::RapidTrigger => tr!("setting-rapid-trigger"),
+        Setting::Continuous => tr!("setting-continuous"),
+        Setting::UpSensitivity => tr!("setting-up-sensitivity"),
+        Setting::DownSensitivity => tr!("setting-down-sensitivity"),
+        Setting::HysteresisLower => tr!("setting-hysteresis-lower"),
+        Setting::HysteresisUpper => tr!("setting-hysteresis-upper"),
+        Setting::KeyCode => tr!("setting-key-code"),
+        Setting::Hid => tr!("setting-hid"),
+    }
+}
+
+/// Translated value of a setting, a dash for keys a config does not have
+pub(super) fn value_text(value: Option<&Value>) -> String {
+    match value {
+        None => "—".to_string(),
+        Some(Value::Present) => tr!("value-present"),
+        Some(Value::Switch(true)) => tr!("value-on"),
+        Some(Value::Switch(false)) => tr!("value-off"),
+        Some(Value::Distance(distance)) => distance.to_string(),
+        Some(Value::KeyCode(keycode)) => keycode.to_string(),
+    }
+}
+
+fn source_combo(
+    ui: &mut egui::Ui,
+    id: &str,
+    sources: &[Source],
+    names: &[String],
+    value: &mut Option<Source>,
+) {
+    let selected = value
+        .as_ref()
+        .and_then(|value| sources.iter().position(|source| source == value))
+        .map_or_else(|| tr!("compare-select"), |i| names[i].clone());
+    egui::ComboBox::from_id_source(id)
+        .selected_text(selected)
+        .width(180.)
+        .show_ui(ui, |ui| {
+            for (source, name) in sources.iter().zip(names) {
+                ui.selectable_value(value, Some(source.clone()), name);
+            }
+        });
+}
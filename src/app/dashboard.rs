@@ -0,0 +1,435 @@
+use std::collections::{HashMap, HashSet};
+
+use crustility_core::{
+    backup,
+    config::Config,
+    device::{self, Device, DeviceHandle},
+    profile, validate,
+};
+
+use super::{backups, i18n::tr, Crustility};
+
+/// Outcome of pushing settings to one device
+#[derive(Debug, Clone, PartialEq)]
+pub enum PushResult {
+    /// waiting for the device to report its config
+    Pending,
+    Applied {
+        keys: usize,
+        device_keys: usize,
+    },
+    Failed(String),
+}
+
+#[derive(Debug, Clone)]
+struct Push {
+    handle: DeviceHandle,
+    name: String,
+    result: PushResult,
+}
+
+/// Overview of every connected device and pushing settings to several of them
+#[derive(Default)]
+pub struct Dashboard {
+    pub show: bool,
+    /// devices the settings get pushed to
+    targets: HashSet<DeviceHandle>,
+    /// profile to push, the config of the selected device when `None`
+    source: Option<String>,
+    /// settings of the last push, kept for the devices that still have to report their config
+    pushed: Option<Config>,
+    results: Vec<Push>,
+    /// why the event loop of a device stopped
+    errors: HashMap<DeviceHandle, String>,
+}
+
+impl Dashboard {
+    pub fn set_error(&mut self, handle: DeviceHandle, error: &device::Error) {
+        self.errors.insert(handle, error.to_string());
+    }
+
+    pub fn clear_error(&mut self, handle: &DeviceHandle) {
+        self.errors.remove(handle);
+    }
+
+    /// A pending push can't finish once the device is gone
+    pub fn connection_lost(&mut self, handle: &DeviceHandle) {
+        for push in &mut self.results {
+            if push.handle == *handle && push.result == PushResult::Pending {
+                push.result = PushResult::Failed(tr!("push-connection-lost"));
+            }
+        }
+    }
+}
+
+/// Name of a device, numbered when there are several of the same model
+pub fn device_name(handle: &DeviceHandle, device: &Device) -> String {
+    match handle.instance() {
+        0 => device.name().clone(),
+        instance => format!("{} #{}", device.name(), instance + 1),
+    }
+}
+
+impl Crustility {
+    /// Push the source settings to every target device
+    fn push_to_targets(&mut self) {
+        let source = match &self.dashboard.source {
+            Some(name) => match profile::load(name) {
+                Ok(config) => config,
+                Err(e) => {
+                    self.notifications
+                        .error(tr!("profile-load-error", name = name.as_str()), &e);
+                    return;
+                }
+            },
+            None => {
+                let config = self
+                    .device
+                    .and_then(|handle| self.devices.get(&handle))
+                    .and_then(|device| device.config());
+                match config {
+                    Some(config) => config.clone(),
+                    None => {
+                        self.notifications.warning(tr!("push-no-config"));
+                        return;
+                    }
+                }
+            }
+        };
+        if !validate::validate(&source).is_valid() {
+            self.notifications.warning(tr!("push-invalid"));
+            return;
+        }
+
+        let targets = self
+            .devices
+            .iter()
+            .filter(|handle| self.dashboard.targets.contains(handle))
+            .copied()
+            .collect::<Vec<_>>();
+        self.dashboard.results = targets
+            .into_iter()
+            .filter_map(|handle| {
+                let device = self.devices.get_mut(&handle)?;
+                Some(Push {
+                    handle,
+                    name: device_name(&handle, device),
+                    result: push(&handle, device, &source),
+                })
+            })
+            .collect();
+        self.dashboard.pushed = Some(source);
+        self.backups.refresh();
+
+        let applied = self
+            .dashboard
+            .results
+            .iter()
+            .filter(|push| matches!(push.result, PushResult::Applied { .. }))
+            .count();
+        let total = self.dashboard.results.len();
+        if applied == total {
+            self.notifications.success(tr!("push-done", count = total));
+        } else if self.dashboard.results.iter().all(|push| {
+            matches!(
+                push.result,
+                PushResult::Applied { .. } | PushResult::Pending
+            )
+        }) {
+            self.notifications
+                .info(tr!("push-waiting", applied = applied, count = total));
+        } else {
+            self.notifications
+                .warning(tr!("push-partial", applied = applied, count = total));
+        }
+    }
+
+    /// Finish a push that was waiting for the config of a device
+    pub fn resume_push(&mut self, handle: DeviceHandle) {
+        let Some(source) = &self.dashboard.pushed else {
+            return;
+        };
+        let Some(device) = self.devices.get_mut(&handle) else {
+            return;
+        };
+        for entry in &mut self.dashboard.results {
+            if entry.handle == handle && entry.result == PushResult::Pending {
+                entry.result = push(&handle, device, source);
+            }
+        }
+        self.backups.refresh();
+    }
+
+    /// Start the event loops of every device and ask them for their config
+    fn read_all_configs(&mut self) {
+        let handles = self.devices.iter().copied().collect::<Vec<_>>();
+        for handle in handles {
+            let Some(device) = self.devices.get_mut(&handle) else {
+                continue;
+            };
+            let result = device
+                .spawn_event_loop()
+                .and_then(|_| device.send_event(device::SendEvent::ReadConfig));
+            if let Err(e) = result {
+                self.notifications.error(
+                    tr!("device-read-error", device = device.name().as_str()),
+                    &e,
+                );
+            }
+        }
+    }
+
+    pub fn dashboard_window(&mut self, ctx: &egui::Context) {
+        if !self.dashboard.show {
+            return;
+        }
+        let mut open = true;
+        let mut select = None;
+        let mut push = false;
+        let mut rescan = false;
+        let mut read_all = false;
+        let profiles = profile::list().unwrap_or_default();
+
+        egui::Window::new(tr!("dashboard-title"))
+            .open(&mut open)
+            .default_width(560.)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    rescan = ui.button(tr!("dashboard-rescan")).clicked();
+                    read_all = ui
+                        .button(tr!("dashboard-read-all"))
+                        .on_hover_text(tr!("dashboard-read-all-hint"))
+                        .clicked();
+                });
+                ui.separator();
+
+                egui::Grid::new("devices")
+                    .num_columns(6)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.label("");
+                        ui.strong(tr!("dashboard-device"));
+                        ui.strong(tr!("dashboard-port"));
+                        ui.strong(tr!("dashboard-status"));
+                        ui.strong(tr!("dashboard-keys"));
+                        ui.strong(tr!("dashboard-firmware"));
+                        ui.end_row();
+
+                        for handle in self.devices.iter() {
+                            let Some(device) = self.devices.get(handle) else {
+                                continue;
+                            };
+                            let mut target = self.dashboard.targets.contains(handle);
+                            if ui
+                                .checkbox(&mut target, "")
+                                .on_hover_text(tr!("dashboard-target-hint"))
+                                .changed()
+                            {
+                                if target {
+                                    self.dashboard.targets.insert(*handle);
+                                } else {
+                                    self.dashboard.targets.remove(handle);
+                                }
+                            }
+
+                            let selected = self.device == Some(*handle);
+                            let mut details = tr!(
+                                "dashboard-usb",
+                                id = format!("{:04x}:{:04x}", handle.vid(), handle.pid())
+                            );
+                            if let Some(manufacturer) = device.manufacturer() {
+                                details += "\n";
+                                details +=
+                                    &tr!("dashboard-manufacturer", manufacturer = manufacturer);
+                            }
+                            if let Some(serial_number) = device.serial_number() {
+                                details += "\n";
+                                details +=
+                                    &tr!("dashboard-serial-number", serial_number = serial_number);
+                            }
+                            if ui
+                                .selectable_label(selected, device_name(handle, device))
+                                .on_hover_text(details)
+                                .clicked()
+                                && !selected
+                            {
+                                select = Some(*handle);
+                            }
+                            ui.label(device.port_name());
+
+                            if let Some(error) = self.dashboard.errors.get(handle) {
+                                ui.colored_label(
+                                    ui.visuals().error_fg_color,
+                                    tr!("dashboard-error"),
+                                )
+                                .on_hover_text(error);
+                            } else if device.is_connected() {
+                                let status = match device.poll_stats() {
+                                    Some(stats) if selected => {
+                                        tr!(
+                                            "dashboard-polling",
+                                            rate = format!("{:.0}", stats.effective_rate)
+                                        )
+                                    }
+                                    _ => tr!("dashboard-connected"),
+                                };
+                                ui.label(status);
+                            } else {
+                                ui.weak(tr!("dashboard-not-connected"));
+                            }
+
+                            match device.config() {
+                                Some(config) => ui
+                                    .label(format!(
+                                        "{} + {}",
+                                        config.hkeys.len(),
+                                        config.dkeys.len()
+                                    ))
+                                    .on_hover_text(tr!("dashboard-keys-hint")),
+                                None => ui.weak("?"),
+                            };
+
+                            let firmware = device
+                                .config()
+                                .filter(|config| !config.firmware.is_empty())
+                                .map(|config| {
+                                    config
+                                        .firmware
+                                        .iter()
+                                        .map(|(key, value)| format!("{key} {value}"))
+                                        .collect::<Vec<_>>()
+                                        .join(", ")
+                                });
+                            match firmware {
+                                Some(firmware) => ui.label(firmware),
+                                None => ui.weak(tr!("dashboard-firmware-unknown")),
+                            };
+                            ui.end_row();
+                        }
+                    });
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label(tr!("dashboard-push"));
+                    egui::ComboBox::from_id_source("push source")
+                        .selected_text(match &self.dashboard.source {
+                            Some(profile) => tr!("dashboard-push-profile", name = profile.as_str()),
+                            None => tr!("dashboard-push-selected"),
+                        })
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(
+                                &mut self.dashboard.source,
+                                None,
+                                tr!("dashboard-push-selected"),
+                            );
+                            for profile in &profiles {
+                                ui.selectable_value(
+                                    &mut self.dashboard.source,
+                                    Some(profile.clone()),
+                                    tr!("dashboard-push-profile", name = profile.as_str()),
+                                );
+                            }
+                        });
+                    let targets = self.dashboard.targets.len();
+                    push = ui
+                        .add_enabled(
+                            targets > 0,
+                            egui::Button::new(tr!("dashboard-push-targets", count = targets)),
+                        )
+                        .clicked();
+                });
+
+                if !self.dashboard.results.is_empty() {
+                    ui.separator();
+                    ui.strong(tr!("dashboard-last-push"));
+                    for push in &self.dashboard.results {
+                        ui.horizontal(|ui| {
+                            ui.label(&push.name);
+                            match &push.result {
+                                PushResult::Pending => {
+                                    ui.spinner();
+                                    ui.weak(tr!("dashboard-push-pending"));
+                                }
+                                PushResult::Applied { keys, device_keys }
+                                    if keys == device_keys =>
+                                {
+                                    ui.label(tr!("dashboard-push-applied", count = *keys));
+                                }
+                                PushResult::Applied { keys, device_keys } => {
+                                    ui.colored_label(
+                                        ui.visuals().warn_fg_color,
+                                        tr!(
+                                            "dashboard-push-applied-partly",
+                                            keys = *keys,
+                                            count = *device_keys
+                                        ),
+                                    );
+                                }
+                                PushResult::Failed(reason) => {
+                                    ui.colored_label(ui.visuals().error_fg_color, reason);
+                                }
+                            }
+                        });
+                    }
+                }
+            });
+        self.dashboard.show = open;
+
+        if rescan {
+            self.devices.scan();
+            // forget the devices that are gone
+            let devices = &self.devices;
+            self.dashboard
+                .targets
+                .retain(|handle| devices.get(handle).is_some());
+        }
+        if read_all {
+            self.read_all_configs();
+        }
+        if let Some(handle) = select {
+            self.select_device(handle);
+        }
+        if push {
+            self.push_to_targets();
+        }
+    }
+}
+
+/// Copy the settings into the config of a device and send them
+///
+/// Devices that did not report their config yet are asked for it first,
+/// the config they had is backed up before it gets replaced
+pub fn push(handle: &DeviceHandle, device: &mut Device, source: &Config) -> PushResult {
+    if let Err(e) = device.spawn_event_loop() {
+        return PushResult::Failed(e.to_string());
+    }
+    let Some(config) = device.config() else {
+        return match device.send_event(device::SendEvent::ReadConfig) {
+            Ok(()) => PushResult::Pending,
+            Err(e) => PushResult::Failed(e.to_string()),
+        };
+    };
+
+    let mut pushed = config.clone();
+    let keys = pushed.apply_settings(source);
+    let device_keys = pushed.hkeys.len();
+    if !validate::validate(&pushed).is_valid() {
+        return PushResult::Failed(tr!("push-invalid-for-device"));
+    }
+    if let Err(e) = backups::take(handle, device, backup::Reason::Apply) {
+        return PushResult::Failed(tr!("push-backup-error", error = e.to_string()));
+    }
+    if let Some(config) = device.config_mut() {
+        *config = pushed;
+    }
+    let result = device
+        .serialize_config()
+        .and_then(|raw_config| device.send_event(device::SendEvent::SendCommands(raw_config)));
+    match result {
+        Ok(()) => {
+            device.mark_synced();
+            PushResult::Applied { keys, device_keys }
+        }
+        Err(e) => PushResult::Failed(e.to_string()),
+    }
+}
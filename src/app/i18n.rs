@@ -0,0 +1,134 @@
+use std::sync::{OnceLock, RwLock};
+
+use fluent_bundle::{concurrent::FluentBundle, FluentArgs, FluentResource};
+use unic_langid::LanguageIdentifier;
+
+/// Translation bundled with the app
+pub struct Language {
+    /// BCP 47 language tag, what the settings store
+    pub code: &'static str,
+    /// name of the language in the language itself
+    pub name: &'static str,
+    source: &'static str,
+}
+
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+/// The first one is the fallback for messages missing in the others
+pub const LANGUAGES: [Language; 4] = [
+    Language {
+        code: "en",
+        name: "English",
+        source: include_str!("../locales/en.ftl"),
+    },
+    Language {
+        code: "de",
+        name: "Deutsch",
+        source: include_str!("../locales/de.ftl"),
+    },
+    Language {
+        code: "es",
+        name: "Español",
+        source: include_str!("../locales/es.ftl"),
+    },
+    Language {
+        code: "fr",
+        name: "Français",
+        source: include_str!("../locales/fr.ftl"),
+    },
+];
+
+struct Localizer {
+    code: &'static str,
+    bundle: FluentBundle<FluentResource>,
+    fallback: FluentBundle<FluentResource>,
+}
+
+fn bundle(language: &Language) -> FluentBundle<FluentResource> {
+    let id = language
+        .code
+        .parse::<LanguageIdentifier>()
+        .unwrap_or_default();
+    let mut bundle = FluentBundle::new_concurrent(vec![id]);
+    // egui would draw the unicode isolation marks around arguments
+    bundle.set_use_isolating(false);
+    let resource = FluentResource::try_new(language.source.to_string()).unwrap_or_else(
+        |(resource, errors)| {
+            log::error!("{} translation has errors: {errors:?}", language.code);
+            resource
+        },
+    );
+    if let Err(errors) = bundle.add_resource(resource) {
+        log::error!("{} translation has errors: {errors:?}", language.code);
+    }
+    bundle
+}
+
+fn localizer() -> &'static RwLock<Localizer> {
+    static LOCALIZER: OnceLock<RwLock<Localizer>> = OnceLock::new();
+    LOCALIZER.get_or_init(|| {
+        RwLock::new(Localizer {
+            code: LANGUAGES[0].code,
+            bundle: bundle(&LANGUAGES[0]),
+            fallback: bundle(&LANGUAGES[0]),
+        })
+    })
+}
+
+/// Switch the language of the ui, unknown codes fall back to english
+pub fn set_language(code: &str) {
+    let language = LANGUAGES
+        .iter()
+        .find(|language| language.code == code)
+        .unwrap_or(&LANGUAGES[0]);
+    let Ok(mut localizer) = localizer().write() else {
+        return;
+    };
+    if localizer.code != language.code {
+        localizer.code = language.code;
+        localizer.bundle = bundle(language);
+    }
+}
+
+/// Name of a language code for the picker
+pub fn language_name(code: &str) -> &str {
+    LANGUAGES
+        .iter()
+        .find(|language| language.code == code)
+        .map_or(code, |language| language.name)
+}
+
+/// Message in the selected language, in english when it has not been translated
+///
+/// Use [`tr!`] instead of calling this directly
+pub fn translate(id: &str, args: Option<&FluentArgs>) -> String {
+    let Ok(localizer) = localizer().read() else {
+        return id.to_string();
+    };
+    for bundle in [&localizer.bundle, &localizer.fallback] {
+        let Some(pattern) = bundle.get_message(id).and_then(|message| message.value()) else {
+            continue;
+        };
+        let mut errors = Vec::new();
+        let text = bundle.format_pattern(pattern, args, &mut errors);
+        if !errors.is_empty() {
+            log::warn!("translating {id}: {errors:?}");
+        }
+        return text.into_owned();
+    }
+    log::warn!("no translation for {id}");
+    id.to_string()
+}
+
+/// Translate a message, `tr!("key-heading", key = 1)` passes arguments
+macro_rules! tr {
+    ($id:literal) => {
+        $crate::app::i18n::translate($id, None)
+    };
+    ($id:literal, $($name:ident = $value:expr),+ $(,)?) => {{
+        let mut args = fluent_bundle::FluentArgs::new();
+        $(args.set(stringify!($name), $value);)+
+        $crate::app::i18n::translate($id, Some(&args))
+    }};
+}
+pub(crate) use tr;
@@ -0,0 +1,314 @@
+use crustility_core::{
+    config::Config,
+    interop::{self, Format, Reason, Report},
+    profile,
+};
+
+use super::{i18n::tr, notify::Level, Crustility};
+
+/// Import and export of the key settings as serial commands or json
+pub struct Interop {
+    pub show: bool,
+    format: Format,
+    /// file to read from or write to
+    path: String,
+    /// content of the file, can also be pasted or copied
+    text: String,
+    /// name to save an import as
+    profile: String,
+    /// what was left out by the last import or export
+    report: Option<(String, Report)>,
+}
+
+impl Default for Interop {
+    fn default() -> Self {
+        Self {
+            show: false,
+            format: Format::Commands,
+            path: String::new(),
+            text: String::new(),
+            profile: String::new(),
+            report: None,
+        }
+    }
+}
+
+enum Action {
+    Open,
+    Save,
+    Export,
+    ImportDevice,
+    ImportProfile,
+}
+
+impl Crustility {
+    pub fn interop_window(&mut self, ctx: &egui::Context) {
+        if !self.interop.show {
+            return;
+        }
+        let mut open = true;
+        let mut action = None;
+        let has_config = self
+            .device
+            .and_then(|handle| self.devices.get(&handle))
+            .is_some_and(|device| device.config().is_some());
+
+        egui::Window::new(tr!("interop-title"))
+            .open(&mut open)
+            .default_width(480.)
+            .show(ctx, |ui| {
+                let state = &mut self.interop;
+                ui.horizontal(|ui| {
+                    ui.label(tr!("interop-format"));
+                    egui::ComboBox::from_id_source("interop format")
+                        .selected_text(format_name(state.format))
+                        .show_ui(ui, |ui| {
+                            for format in Format::ALL {
+                                ui.selectable_value(&mut state.format, format, format_name(format));
+                            }
+                        });
+                });
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut state.path)
+                            .hint_text(format!("settings.{}", state.format.extension()))
+                            .desired_width(260.),
+                    );
+                    let has_path = !state.path.trim().is_empty();
+                    if ui
+                        .add_enabled(has_path, egui::Button::new(tr!("interop-open")))
+                        .clicked()
+                    {
+                        action = Some(Action::Open);
+                    }
+                    if ui
+                        .add_enabled(
+                            has_path && !state.text.is_empty(),
+                            egui::Button::new(tr!("interop-save")),
+                        )
+                        .clicked()
+                    {
+                        action = Some(Action::Save);
+                    }
+                });
+                egui::ScrollArea::vertical()
+                    .max_height(240.)
+                    .show(ui, |ui| {
+                        ui.add(
+                            egui::TextEdit::multiline(&mut state.text)
+                                .code_editor()
+                                .hint_text(tr!("interop-text-hint"))
+                                .desired_width(f32::INFINITY)
+                                .desired_rows(10),
+                        );
+                    });
+
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(has_config, egui::Button::new(tr!("interop-export")))
+                        .on_hover_text(tr!("interop-export-hint"))
+                        .clicked()
+                    {
+                        action = Some(Action::Export);
+                    }
+                    let has_text = !state.text.trim().is_empty();
+                    if ui
+                        .add_enabled(
+                            has_text && has_config,
+                            egui::Button::new(tr!("interop-import-device")),
+                        )
+                        .on_hover_text(tr!("interop-import-device-hint"))
+                        .clicked()
+                    {
+                        action = Some(Action::ImportDevice);
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut state.profile)
+                            .hint_text(tr!("interop-profile-name"))
+                            .desired_width(120.),
+                    );
+                    let can_save =
+                        !state.text.trim().is_empty() && !state.profile.trim().is_empty();
+                    if ui
+                        .add_enabled(can_save, egui::Button::new(tr!("interop-import-profile")))
+                        .clicked()
+                    {
+                        action = Some(Action::ImportProfile);
+                    }
+                });
+
+                if let Some((title, report)) = &state.report {
+                    ui.separator();
+                    report_grid(ui, title, report);
+                }
+            });
+        self.interop.show &= open;
+
+        match action {
+            Some(Action::Open) => self.open_interop_file(),
+            Some(Action::Save) => {
+                let path = self.interop.path.trim().to_string();
+                match std::fs::write(&path, &self.interop.text) {
+                    Ok(()) => self
+                        .notifications
+                        .success(tr!("interop-saved", path = path.as_str())),
+                    Err(e) => self
+                        .notifications
+                        .error(tr!("interop-save-error", path = path.as_str()), &e),
+                }
+            }
+            Some(Action::Export) => {
+                let Some(config) = self
+                    .device
+                    .and_then(|handle| self.devices.get(&handle))
+                    .and_then(|device| device.config())
+                else {
+                    return;
+                };
+                let (text, report) = interop::export(self.interop.format, config);
+                self.interop.text = text;
+                self.notify_report(tr!("interop-exported", count = report.len()), &report);
+                self.interop.report = Some((tr!("interop-export-report"), report));
+            }
+            Some(Action::ImportDevice) => {
+                if let Some(imported) = self.import_text() {
+                    self.import_to_device(&imported);
+                }
+            }
+            Some(Action::ImportProfile) => {
+                let name = self.interop.profile.trim().to_string();
+                if let Some(imported) = self.import_text() {
+                    match profile::save(&name, &imported) {
+                        Ok(()) => self
+                            .notifications
+                            .success(tr!("interop-imported-profile", name = name.as_str())),
+                        Err(e) => self
+                            .notifications
+                            .error(tr!("profile-save-error", name = name.as_str()), &e),
+                    }
+                }
+            }
+            None => (),
+        }
+    }
+
+    fn open_interop_file(&mut self) {
+        let path = self.interop.path.trim().to_string();
+        match std::fs::read_to_string(&path) {
+            Ok(text) => {
+                self.interop.format = Format::detect(&text);
+                self.interop.text = text;
+                self.interop.report = None;
+            }
+            Err(e) => self
+                .notifications
+                .error(tr!("interop-open-error", path = path.as_str()), &e),
+        }
+    }
+
+    /// Translate the text, keeping the report to show it in the window
+    fn import_text(&mut self) -> Option<Config> {
+        match interop::import(self.interop.format, &self.interop.text) {
+            Ok((config, report)) => {
+                self.notify_report(tr!("interop-imported", count = report.len()), &report);
+                self.interop.report = Some((tr!("interop-import-report"), report));
+                Some(config)
+            }
+            Err(e) => {
+                self.notifications.error(
+                    tr!(
+                        "interop-import-error",
+                        format = format_name(self.interop.format)
+                    ),
+                    &e,
+                );
+                None
+            }
+        }
+    }
+
+    fn import_to_device(&mut self, imported: &Config) {
+        let Some(config) = self
+            .device
+            .and_then(|handle| self.devices.get_mut(&handle))
+            .and_then(|device| device.config_mut())
+        else {
+            return;
+        };
+        let copied = config.apply_settings(imported);
+        if imported.hkeys.len() != config.hkeys.len() {
+            self.notifications.warning(tr!(
+                "interop-key-count",
+                import = imported.hkeys.len(),
+                device = config.hkeys.len()
+            ));
+        }
+        self.notifications
+            .info(tr!("interop-imported-device", count = copied));
+    }
+
+    fn notify_report(&mut self, message: String, report: &Report) {
+        if report.is_empty() {
+            return;
+        }
+        let details = report
+            .untranslated
+            .iter()
+            .map(|u| format!("{} = {}: {}", u.field, u.value, reason_text(u.reason)))
+            .collect::<Vec<_>>()
+            .join("\n");
+        self.notifications
+            .push(Level::Warning, message, Some(details));
+    }
+}
+
+fn format_name(format: Format) -> String {
+    match format {
+        Format::Commands => tr!("interop-format-commands"),
+        Format::Json => tr!("interop-format-json"),
+    }
+}
+
+fn reason_text(reason: Reason) -> String {
+    match reason {
+        Reason::DigitalKeys => tr!("interop-reason-digital-keys"),
+        Reason::OutOfRange => tr!("interop-reason-out-of-range"),
+        Reason::NotASetting => tr!("interop-reason-not-a-setting"),
+        Reason::NotAKeySetting => tr!("interop-reason-not-a-key-setting"),
+        Reason::PastKeyCount(count) => tr!("interop-reason-past-key-count", count = count),
+        Reason::UnknownSetting => tr!("interop-reason-unknown-setting"),
+        Reason::InvalidValue => tr!("interop-reason-invalid-value"),
+        Reason::Calibration => tr!("interop-reason-calibration"),
+        Reason::RapidTriggerOff => tr!("interop-reason-rapid-trigger-off"),
+    }
+}
+
+fn report_grid(ui: &mut egui::Ui, title: &str, report: &Report) {
+    if report.is_empty() {
+        ui.weak(tr!("interop-all-translated"));
+        return;
+    }
+    ui.strong(title);
+    egui::ScrollArea::vertical()
+        .id_source("interop report")
+        .max_height(160.)
+        .show(ui, |ui| {
+            egui::Grid::new("untranslated")
+                .num_columns(3)
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.strong(tr!("interop-setting"));
+                    ui.strong(tr!("interop-value"));
+                    ui.strong(tr!("interop-reason"));
+                    ui.end_row();
+                    for untranslated in &report.untranslated {
+                        ui.monospace(&untranslated.field);
+                        ui.monospace(&untranslated.value);
+                        ui.label(reason_text(untranslated.reason));
+                        ui.end_row();
+                    }
+                });
+        });
+}
@@ -0,0 +1,171 @@
+use egui::RichText;
+
+use crustility_core::keycode::{Category, KeyCode};
+
+use super::i18n::tr;
+
+/// Button that binds the next key pressed while it is active
+pub fn capture(ui: &mut egui::Ui, keycode: &mut KeyCode) -> egui::Response {
+    let id = ui.make_persistent_id("key capture");
+    let mut capturing = ui
+        .ctx()
+        .data_mut(|d| d.get_temp::<bool>(id))
+        .unwrap_or(false);
+
+    let captured = if capturing {
+        ui.input_mut(|i| {
+            // prefer the typed character so shifted symbols end up as what the user sees
+            let captured = i.events.iter().find_map(|event| match event {
+                egui::Event::Text(text) => {
+                    let mut chars = text.chars();
+                    match (chars.next(), chars.next()) {
+                        (Some(c), None) => KeyCode::from_char(c),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            });
+            let captured = captured.or_else(|| {
+                i.events.iter().find_map(|event| match event {
+                    egui::Event::Key {
+                        key, pressed: true, ..
+                    } => from_egui_key(*key),
+                    _ => None,
+                })
+            });
+            if captured.is_some() {
+                // don't let the key do anything else this frame
+                i.events
+                    .retain(|e| !matches!(e, egui::Event::Key { .. } | egui::Event::Text(_)));
+            }
+            captured
+        })
+    } else {
+        None
+    };
+
+    let text = if capturing {
+        tr!("keybind-capturing")
+    } else {
+        tr!("keybind-bind")
+    };
+    let mut response = ui
+        .selectable_label(capturing, text)
+        .on_hover_text(tr!("keybind-bind-hint"));
+
+    if let Some(code) = captured {
+        *keycode = code;
+        capturing = false;
+        response.mark_changed();
+    } else if response.clicked() {
+        capturing = !capturing;
+        // keep space and enter from toggling the button while capturing
+        response.surrender_focus();
+    } else if response.clicked_elsewhere() {
+        capturing = false;
+    }
+
+    ui.ctx().data_mut(|d| d.insert_temp(id, capturing));
+    response
+}
+
+/// Searchable list of every key code the firmware accepts
+pub fn picker(ui: &mut egui::Ui, keycode: &mut KeyCode) {
+    let id = ui.make_persistent_id("key picker filter");
+    ui.menu_button(keycode.name().into_owned(), |ui| {
+        let mut filter = ui
+            .ctx()
+            .data_mut(|d| d.get_temp::<String>(id))
+            .unwrap_or_default();
+        ui.add(egui::TextEdit::singleline(&mut filter).hint_text(tr!("keybind-search")));
+        let filter_lower = filter.to_lowercase();
+
+        egui::ScrollArea::vertical()
+            .max_height(240.)
+            .show(ui, |ui| {
+                let mut category = None;
+                for code in KeyCode::all() {
+                    let name = code.name();
+                    if !filter_lower.is_empty() && !name.to_lowercase().contains(&filter_lower) {
+                        continue;
+                    }
+                    if category != Some(code.category()) {
+                        category = Some(code.category());
+                        ui.label(RichText::new(category_name(code.category())).weak());
+                    }
+                    if ui
+                        .selectable_label(*keycode == code, name.into_owned())
+                        .clicked()
+                    {
+                        *keycode = code;
+                        ui.close_menu();
+                    }
+                }
+            });
+
+        ui.ctx().data_mut(|d| d.insert_temp(id, filter));
+    });
+}
+
+fn category_name(category: Category) -> String {
+    match category {
+        Category::None => tr!("keycode-unbound"),
+        Category::Character => tr!("keycode-characters"),
+        Category::Modifier => tr!("keycode-modifiers"),
+        Category::Usage => tr!("keycode-usages"),
+    }
+}
+
+fn from_egui_key(key: egui::Key) -> Option<KeyCode> {
+    use egui::Key;
+
+    let usage = match key {
+        Key::ArrowDown => 0x51,
+        Key::ArrowLeft => 0x50,
+        Key::ArrowRight => 0x4f,
+        Key::ArrowUp => 0x52,
+        Key::Escape => 0x29,
+        Key::Tab => 0x2b,
+        Key::Backspace => 0x2a,
+        Key::Enter => 0x28,
+        Key::Insert => 0x49,
+        Key::Delete => 0x4c,
+        Key::Home => 0x4a,
+        Key::End => 0x4d,
+        Key::PageUp => 0x4b,
+        Key::PageDown => 0x4e,
+        Key::F1 => 0x3a,
+        Key::F2 => 0x3b,
+        Key::F3 => 0x3c,
+        Key::F4 => 0x3d,
+        Key::F5 => 0x3e,
+        Key::F6 => 0x3f,
+        Key::F7 => 0x40,
+        Key::F8 => 0x41,
+        Key::F9 => 0x42,
+        Key::F10 => 0x43,
+        Key::F11 => 0x44,
+        Key::F12 => 0x45,
+        Key::F13 => 0x68,
+        Key::F14 => 0x69,
+        Key::F15 => 0x6a,
+        Key::F16 => 0x6b,
+        Key::F17 => 0x6c,
+        Key::F18 => 0x6d,
+        Key::F19 => 0x6e,
+        Key::F20 => 0x6f,
+        // keys that type something are bound as their character
+        Key::Space => return KeyCode::from_char(' '),
+        Key::Minus => return KeyCode::from_char('-'),
+        Key::PlusEquals => return KeyCode::from_char('='),
+        _ => {
+            let name = key.name();
+            let mut chars = name.chars();
+            return match (chars.next(), chars.next()) {
+                (Some(c), None) => KeyCode::from_char(c.to_ascii_lowercase()),
+                _ => None,
+            };
+        }
+    };
+    KeyCode::from_usage(usage)
+}
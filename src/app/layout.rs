@@ -1,27 +1,21 @@
-use std::{
-    sync::atomic::{AtomicBool, Ordering},
-    time::Duration,
-};
+use std::sync::atomic::{AtomicBool, Ordering};
 
-use egui::{pos2, vec2, Color32, RichText};
+use egui::{pos2, RichText};
 
-use crate::{
-    app::combobox,
-    config::{HKey, Millimeter},
-    device,
+use crate::app::{accessibility, combobox, i18n::tr, keybind, theme};
+use crustility_core::{
+    config::{HKey, Millimeter, Rounding},
+    device::{self, transport, SerialSettings},
+    validate::{self, Field, Issue, Problem, Report},
 };
 
-use super::Crustility;
+use super::{Confirm, Crustility};
 
 impl Crustility {
     pub fn device_panel(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) {
         self.key_options(ctx, ui);
         egui::CentralPanel::default().show(ctx, |ui| {
-            egui::ScrollArea::horizontal().show(ui, |ui| {
-                ui.horizontal_top(|ui| {
-                    self.keys(ctx, ui, 60., 10.);
-                });
-            })
+            self.board(ctx, ui);
         });
     }
 
@@ -36,56 +30,71 @@ impl Crustility {
             return;
         };
 
+        let report = validate::validate(cfg);
+        let travel = f32::from(cfg.capabilities.travel_distance);
+
         let Some(key) = self.selected_key else {
             egui::panel::SidePanel::left("Key options")
                 .frame(egui::Frame::central_panel(ui.style()))
                 .resizable(false)
                 .show(ctx, |ui| {
                     ui.centered_and_justified(|ui| {
-                        ui.heading("Select Key");
+                        ui.heading(tr!("select-key"));
                     });
                 });
             return;
         };
 
-        let rt_section = |ui: &mut egui::Ui, key: &mut HKey| {
+        let rt_section = |ui: &mut egui::Ui, i: usize, key: &mut HKey| {
             ui.add_space(10.);
             ui.group(|ui| {
-                ui.heading("Rapid Trigger");
+                ui.heading(tr!("rapid-trigger"));
                 let mut rt_enabled = key.rt.is_some();
-                ui.checkbox(&mut rt_enabled, "Enable");
+                ui.checkbox(&mut rt_enabled, tr!("enable"));
 
                 if !rt_enabled {
                     key.rt = None;
                 } else if key.rt.is_none() {
-                    key.rt = Some(crate::config::RapidTrigger::default());
+                    key.rt = Some(crustility_core::config::RapidTrigger::default());
                 }
 
                 if let Some(rt) = &mut key.rt {
-                    ui.checkbox(&mut rt.continuos, "Continuos");
-                    ui.add(
-                        egui::Slider::new((&mut rt.up_sensitivity).into(), 0.0..=4.)
-                            .text("mm up sensitivity"),
+                    ui.checkbox(&mut rt.continuos, tr!("continuous"));
+                    setting_slider(
+                        ui,
+                        &mut rt.up_sensitivity,
+                        travel,
+                        &tr!("up-sensitivity"),
+                        report.field(i, Field::UpSensitivity),
                     );
-                    ui.add(
-                        egui::Slider::new((&mut rt.down_sensitivity).into(), 0.0..=4.)
-                            .text("mm down sensitivity"),
+                    setting_slider(
+                        ui,
+                        &mut rt.down_sensitivity,
+                        travel,
+                        &tr!("down-sensitivity"),
+                        report.field(i, Field::DownSensitivity),
                     );
                 }
             });
         };
 
-        let hysterisis_section = |ui: &mut egui::Ui, key: &mut HKey| {
+        let hysterisis_section = |ui: &mut egui::Ui, i: usize, key: &mut HKey| {
             ui.add_space(20.);
             ui.group(|ui| {
-                ui.heading("Hysterisis");
-                ui.add(
-                    egui::Slider::new((&mut key.hysterisis.upper).into(), 0.0..=4.)
-                        .text("mm upper"),
+                ui.heading(tr!("hysteresis"));
+                setting_slider(
+                    ui,
+                    &mut key.hysterisis.upper,
+                    travel,
+                    &tr!("hysteresis-upper"),
+                    report.field(i, Field::HysteresisUpper),
                 );
-                ui.add(
-                    egui::Slider::new((&mut key.hysterisis.lower).into(), 0.0..=4.)
-                        .text("mm lower"),
+                setting_slider(
+                    ui,
+                    &mut key.hysterisis.lower,
+                    travel,
+                    &tr!("hysteresis-lower"),
+                    report.field(i, Field::HysteresisLower),
                 );
             });
         };
@@ -93,9 +102,12 @@ impl Crustility {
         let hid_section = |ui: &mut egui::Ui, key: &mut HKey| {
             ui.add_space(20.);
             ui.group(|ui| {
-                ui.heading("HID");
-                ui.checkbox(&mut key.hid, "Enable");
-                ui.add(egui::TextEdit::singleline(&mut key.char).char_limit(1));
+                ui.heading(tr!("hid"));
+                ui.checkbox(&mut key.hid, tr!("enable"));
+                ui.horizontal(|ui| {
+                    keybind::picker(ui, &mut key.keycode);
+                    keybind::capture(ui, &mut key.keycode);
+                });
             });
         };
 
@@ -104,13 +116,19 @@ impl Crustility {
             .resizable(false)
             .show(ctx, |ui| {
                 egui::ScrollArea::vertical().show(ui, |ui| {
-                    ui.heading("Key ".to_string() + &(key + 1).to_string());
+                    let heading = RichText::new(tr!("key-heading", key = key + 1));
+                    if report.key_is_valid(key) {
+                        ui.heading(heading);
+                    } else {
+                        ui.heading(heading.color(ui.visuals().error_fg_color));
+                    }
                     ui.separator();
 
-                    let key = &mut cfg.hkeys[key];
+                    let i = key;
+                    let key = &mut cfg.hkeys[i];
 
-                    rt_section(ui, key);
-                    hysterisis_section(ui, key);
+                    rt_section(ui, i, key);
+                    hysterisis_section(ui, i, key);
                     hid_section(ui, key);
                 });
             });
@@ -119,16 +137,64 @@ impl Crustility {
     pub fn menu_bar(&mut self, ctx: &egui::Context, _ui: &mut egui::Ui) {
         egui::TopBottomPanel::top("Menu").show(ctx, |ui| {
             egui::menu::bar(ui, |ui| {
-                if ui.button("Apply Config").clicked() {
-                    if let Some(device) = self.device {
-                        if let Some(device) = self.devices.get_mut(&device) {
-                            if let Ok(raw_config) = device.serialize_config() {
-                                let _ =
-                                    device.send_event(device::SendEvent::SendCommands(raw_config));
-                            }
-                        }
-                    }
+                let report = self.device_report();
+                if ui
+                    .add_enabled(report.is_valid(), egui::Button::new(tr!("apply-config")))
+                    .on_hover_text(accessibility::shortcut_hint(
+                        ctx,
+                        &tr!("apply-config-hint"),
+                        &accessibility::APPLY,
+                    ))
+                    .on_disabled_hover_text(tr!("invalid-settings", count = report.len()))
+                    .clicked()
+                {
+                    self.request_apply();
+                };
+                self.profile_menu(ui);
+                self.share_menu(ui);
+                self.preset_menu(ui);
+                self.backup_menu(ui);
+                self.serial_settings_menu(ui);
+                self.layout_menu(ui);
+                self.theme_menu(ui);
+                if ui
+                    .selectable_label(self.dashboard.show, tr!("menu-devices"))
+                    .clicked()
+                {
+                    self.dashboard.show = !self.dashboard.show;
+                }
+                if ui
+                    .selectable_label(self.compare.show, tr!("menu-compare"))
+                    .clicked()
+                {
+                    self.compare.show = !self.compare.show;
+                }
+                if ui
+                    .selectable_label(self.interop.show, tr!("menu-import-export"))
+                    .clicked()
+                {
+                    self.interop.show = !self.interop.show;
+                }
+                if ui
+                    .selectable_label(self.autoswitch.show, tr!("menu-auto-profiles"))
+                    .clicked()
+                {
+                    self.autoswitch.show = !self.autoswitch.show;
+                }
+                if ui.button(tr!("menu-settings")).clicked() {
+                    self.show_settings = !self.show_settings;
+                }
+
+                let unread = self.notifications.unread_errors();
+                let log_text = if unread > 0 {
+                    RichText::new(tr!("menu-log-unread", count = unread))
+                        .color(ui.visuals().error_fg_color)
+                } else {
+                    RichText::new(tr!("menu-log"))
                 };
+                if ui.button(log_text).clicked() {
+                    self.notifications.toggle_log();
+                }
 
                 let current_rect = ui.max_rect();
                 let combo_box_width = 80.;
@@ -146,8 +212,245 @@ impl Crustility {
                         ),
                     ),
                 );
+
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    self.polling_status(ui);
+                });
+            });
+        });
+    }
+
+    /// Ask before an action when the settings say so
+    pub fn confirm_window(&mut self, ctx: &egui::Context) {
+        let Some(confirm) = self.confirm.clone() else {
+            return;
+        };
+        let question = match &confirm {
+            Confirm::Apply => tr!("confirm-apply"),
+            Confirm::DeleteProfile(name) => tr!("confirm-delete-profile", name = name.as_str()),
+            Confirm::Reset => tr!("confirm-reset"),
+        };
+
+        let mut answer = None;
+        egui::Window::new(tr!("confirm"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(question);
+                ui.horizontal(|ui| {
+                    if ui.button(tr!("yes")).clicked() {
+                        answer = Some(true);
+                    }
+                    if ui.button(tr!("no")).clicked() {
+                        answer = Some(false);
+                    }
+                });
+            });
+
+        match answer {
+            Some(true) => {
+                self.confirm = None;
+                match confirm {
+                    Confirm::Apply => {
+                        self.apply_config();
+                    }
+                    Confirm::DeleteProfile(name) => self.delete_profile(&name),
+                    Confirm::Reset => self.reset_device(),
+                }
+            }
+            Some(false) => self.confirm = None,
+            None => (),
+        }
+    }
+
+    /// validation of the selected device's config
+    pub fn device_report(&self) -> Report {
+        self.device
+            .and_then(|handle| self.devices.get(&handle))
+            .and_then(|device| device.config())
+            .map(validate::validate)
+            .unwrap_or_default()
+    }
+
+    pub fn theme_menu(&mut self, ui: &mut egui::Ui) {
+        let mut selected = None;
+        let mut reload = false;
+        ui.menu_button(tr!("menu-theme"), |ui| {
+            for theme in &self.themes {
+                if ui
+                    .selectable_label(theme.name == self.settings.theme, &theme.name)
+                    .clicked()
+                {
+                    selected = Some(theme.name.clone());
+                    ui.close_menu();
+                }
+            }
+            ui.separator();
+            let hint = theme::themes_dir()
+                .map(|dir| tr!("reload-themes-hint", dir = dir.display().to_string()))
+                .unwrap_or_default();
+            if ui
+                .button(tr!("reload-themes"))
+                .on_hover_text(hint)
+                .clicked()
+            {
+                reload = true;
+            }
+        });
+
+        if let Some(name) = selected {
+            self.set_theme(name);
+        }
+        if reload {
+            self.reload_themes();
+        }
+    }
+
+    /// serial settings of the selected device
+    pub fn serial_settings_menu(&mut self, ui: &mut egui::Ui) {
+        let Some(handle) = self.device else {
+            return;
+        };
+        let Some(device) = self.devices.get(&handle) else {
+            return;
+        };
+        let mut settings = device.serial_settings().clone();
+
+        ui.menu_button(tr!("menu-port"), |ui| {
+            egui::Grid::new("serial settings").show(ui, |ui| {
+                ui.label(tr!("baud-rate"));
+                egui::ComboBox::from_id_source("baud rate")
+                    .selected_text(settings.baud_rate.to_string())
+                    .show_ui(ui, |ui| {
+                        for rate in transport::BAUD_RATES {
+                            ui.selectable_value(&mut settings.baud_rate, rate, rate.to_string());
+                        }
+                    });
+                ui.end_row();
+
+                ui.label(tr!("flow-control"));
+                egui::ComboBox::from_id_source("flow control")
+                    .selected_text(flow_control_name(settings.flow_control))
+                    .show_ui(ui, |ui| {
+                        for flow_control in [
+                            transport::FlowControl::None,
+                            transport::FlowControl::Software,
+                            transport::FlowControl::Hardware,
+                        ] {
+                            ui.selectable_value(
+                                &mut settings.flow_control,
+                                flow_control,
+                                flow_control_name(flow_control),
+                            );
+                        }
+                    });
+                ui.end_row();
+
+                ui.label(tr!("parity"));
+                egui::ComboBox::from_id_source("parity")
+                    .selected_text(parity_name(settings.parity))
+                    .show_ui(ui, |ui| {
+                        for parity in [
+                            transport::Parity::None,
+                            transport::Parity::Odd,
+                            transport::Parity::Even,
+                        ] {
+                            ui.selectable_value(&mut settings.parity, parity, parity_name(parity));
+                        }
+                    });
+                ui.end_row();
+
+                ui.label(tr!("data-bits"));
+                egui::ComboBox::from_id_source("data bits")
+                    .selected_text(data_bits_count(settings.data_bits).to_string())
+                    .show_ui(ui, |ui| {
+                        for data_bits in [
+                            transport::DataBits::Five,
+                            transport::DataBits::Six,
+                            transport::DataBits::Seven,
+                            transport::DataBits::Eight,
+                        ] {
+                            ui.selectable_value(
+                                &mut settings.data_bits,
+                                data_bits,
+                                data_bits_count(data_bits).to_string(),
+                            );
+                        }
+                    });
+                ui.end_row();
+
+                ui.label(tr!("stop-bits"));
+                egui::ComboBox::from_id_source("stop bits")
+                    .selected_text(stop_bits_count(settings.stop_bits).to_string())
+                    .show_ui(ui, |ui| {
+                        for stop_bits in [transport::StopBits::One, transport::StopBits::Two] {
+                            ui.selectable_value(
+                                &mut settings.stop_bits,
+                                stop_bits,
+                                stop_bits_count(stop_bits).to_string(),
+                            );
+                        }
+                    });
+                ui.end_row();
+
+                ui.label(tr!("timeout"));
+                ui.add(
+                    egui::DragValue::new(&mut settings.timeout)
+                        .clamp_range(10..=5000)
+                        .suffix(" ms"),
+                );
+                ui.end_row();
             });
+
+            if ui.button(tr!("reset-defaults")).clicked() {
+                settings = SerialSettings::default();
+            }
         });
+
+        if &settings != device.serial_settings() {
+            let result = self.devices.set_serial_settings(&handle, settings);
+            self.consume_error(&tr!("serial-settings-error"), result);
+        }
+    }
+
+    /// polling rate control and the measured sensor timing
+    pub fn polling_status(&mut self, ui: &mut egui::Ui) {
+        let Some(device) = self.device else {
+            return;
+        };
+        let Some(device) = self.devices.get_mut(&device) else {
+            return;
+        };
+
+        let response = ui.add(
+            egui::DragValue::new(&mut self.settings.polling_rate)
+                .clamp_range(device::poll::MIN_RATE..=device::poll::MAX_RATE)
+                .suffix(" Hz"),
+        );
+        if response.changed() {
+            let result = device.send_event(device::SendEvent::SetPollingRate(
+                self.settings.polling_rate,
+            ));
+            if let Err(e) = result {
+                self.notifications.error(tr!("polling-rate-error"), &e);
+            }
+        }
+
+        if let Some(stats) = device.poll_stats() {
+            let text = RichText::new(format!(
+                "{:.0} Hz  {:.1} ± {:.1} ms",
+                stats.effective_rate,
+                stats.latency.as_secs_f32() * 1000.,
+                stats.jitter.as_secs_f32() * 1000.,
+            ));
+            if stats.throttled {
+                ui.label(text.color(ui.visuals().warn_fg_color))
+                    .on_hover_text(tr!("polling-throttled"));
+            } else {
+                ui.label(text);
+            }
+        }
     }
 
     pub fn default_panel(&mut self, ctx: &egui::Context, _ui: &mut egui::Ui) {
@@ -170,23 +473,34 @@ impl Crustility {
                         ),
                     ),
                 );
+                let dashboard_button = egui::Rect::from_center_size(
+                    current_rect.center() + egui::vec2(0., combo_box_height * 4.),
+                    egui::vec2(combo_box_width * 2., combo_box_height * 2.),
+                );
+                if ui
+                    .put(dashboard_button, egui::Button::new(tr!("all-devices")))
+                    .clicked()
+                {
+                    self.dashboard.show = true;
+                }
             });
         });
     }
 
     pub fn device_dropdown(&mut self, ui: &mut egui::Ui, id: &str, rect: egui::Rect) {
         static REFRESH: AtomicBool = AtomicBool::new(true);
+        let previous = self.device;
         ui.allocate_ui_at_rect(rect, |ui| {
             let response = combobox::ComboBox::from_id_source(id)
                 .selected_text(
                     RichText::new(if let Some(d) = self.device {
                         if let Some(d) = self.devices.get(&d) {
-                            d.name().as_str()
+                            d.name().clone()
                         } else {
-                            "<Select Device>" // device got disconnected
+                            tr!("select-device") // device got disconnected
                         }
                     } else {
-                        "<Select Device>"
+                        tr!("select-device")
                     })
                     .size(16.),
                 )
@@ -203,9 +517,9 @@ impl Crustility {
                                 &mut self.device,
                                 Some(device.to_owned()),
                                 RichText::new(if let Some(d) = devices.get(device) {
-                                    d.name()
+                                    d.name().clone()
                                 } else {
-                                    "<Disconnected>"
+                                    tr!("disconnected")
                                 })
                                 .size(16.),
                             );
@@ -215,115 +529,87 @@ impl Crustility {
 
             response.inner.is_none().then(|| {
                 if !REFRESH.swap(true, Ordering::SeqCst) {
-                    let mut result = Ok(());
-                    if let Some(d) = self.device {
-                        if let Some(d) = self.devices.get_mut(&d) {
-                            result = d.send_event(device::SendEvent::ReadConfig);
-                        }
-                    }
-
-                    self.consume_error(result);
+                    self.read_config();
                 };
             });
         });
+        if self.device != previous {
+            self.stop_sensors(previous);
+        }
     }
+}
 
-    pub fn keys(&mut self, ctx: &egui::Context, ui: &mut egui::Ui, key_width: f32, key_gap: f32) {
-        let current_rect = ui.max_rect();
-        let cursor = ui.cursor().min;
-        let Some(device) = self.device else {
-            return;
-        };
-        let Some(device) = self.devices.get_mut(&device) else {
-            return;
-        };
-        let Some(config) = device.config_mut() else {
-            return;
-        };
-        // 60 fps target
-        ctx.request_repaint_after(Duration::from_millis(10));
-
-        let mut draw_visualizer = |ui: &mut egui::Ui, (i, key): (usize, &mut HKey)| {
-            key.current_position = key.current_position
-                + (key.target_position - key.current_position) / Millimeter::from(2.);
-
-            let key_rect = egui::Rect::from_two_pos(
-                egui::pos2(
-                    current_rect.min.x + i as f32 * (key_width + key_gap),
-                    cursor.y,
-                ),
-                egui::pos2(
-                    current_rect.min.x + i as f32 * (key_width + key_gap) + key_width,
-                    current_rect.max.y,
-                ),
-            );
-
-            ui.allocate_ui_at_rect(key_rect, |ui| {
-                ui.vertical_centered(|ui| {
-                    ui.heading(format!("{}", i + 1));
-                    ui.separator();
-                    let key_visualizer_rect = egui::Rect::from_two_pos(
-                        pos2(key_rect.min.x, ui.cursor().min.y),
-                        key_rect.max,
-                    );
+/// Slider for a distance setting, outlined with the reason when it is invalid
+pub(super) fn setting_slider(
+    ui: &mut egui::Ui,
+    value: &mut Millimeter,
+    travel: f32,
+    text: &str,
+    issue: Option<&Issue>,
+) {
+    let response = ui.add(
+        egui::Slider::from_get_set(0.0..=travel as f64, |new| {
+            if let Some(new) = new.and_then(|new| Millimeter::from_mm(new, Rounding::Nearest)) {
+                *value = new;
+            }
+            value.as_mm()
+        })
+        .step_by(0.01)
+        .fixed_decimals(2)
+        .text(text),
+    );
+    if let Some(issue) = issue {
+        let color = ui.visuals().error_fg_color;
+        ui.painter().rect_stroke(
+            response.rect.expand(2.),
+            egui::Rounding::same(2.),
+            egui::Stroke::new(1., color),
+        );
+        ui.colored_label(color, problem_text(issue.problem));
+    }
+}
 
-                    let is_selected = if let Some(idx) = self.selected_key {
-                        idx == i
-                    } else {
-                        false
-                    };
-
-                    //background
-                    ui.painter().rect(
-                        key_visualizer_rect,
-                        egui::Rounding::ZERO,
-                        ui.visuals().widgets.inactive.bg_fill,
-                        egui::Stroke::NONE,
-                    );
+fn problem_text(problem: Problem) -> String {
+    match problem {
+        Problem::OutOfRange { min, max } => tr!(
+            "problem-out-of-range",
+            min = min.to_string(),
+            max = max.to_string()
+        ),
+        Problem::TooClose { tolerance } => {
+            tr!("problem-too-close", tolerance = tolerance.to_string())
+        }
+    }
+}
 
-                    //input
-                    ui.painter().rect(
-                        egui::Rect::from_two_pos(
-                            key_visualizer_rect.min,
-                            key_visualizer_rect.max
-                                - vec2(
-                                    0.,
-                                    std::convert::Into::<f32>::into(key.current_position) / 4.
-                                        * key_visualizer_rect.height(),
-                                ),
-                        ),
-                        egui::Rounding::ZERO,
-                        if is_selected {
-                            ui.visuals().widgets.active.weak_bg_fill
-                        } else {
-                            ui.visuals().widgets.inactive.weak_bg_fill
-                        },
-                        egui::Stroke::NONE,
-                    );
+fn flow_control_name(flow_control: transport::FlowControl) -> String {
+    match flow_control {
+        transport::FlowControl::None => tr!("flow-control-none"),
+        transport::FlowControl::Software => tr!("flow-control-software"),
+        transport::FlowControl::Hardware => tr!("flow-control-hardware"),
+    }
+}
 
-                    if ui
-                        .add_sized(
-                            vec2(key_rect.width(), key_visualizer_rect.height()),
-                            egui::Button::new("").fill(Color32::TRANSPARENT),
-                        )
-                        .clicked()
-                    {
-                        if is_selected {
-                            self.selected_key = None;
-                        } else {
-                            self.selected_key = Some(i);
-                        }
-                    };
-                });
-            });
-        };
+fn parity_name(parity: transport::Parity) -> String {
+    match parity {
+        transport::Parity::None => tr!("parity-none"),
+        transport::Parity::Odd => tr!("parity-odd"),
+        transport::Parity::Even => tr!("parity-even"),
+    }
+}
 
-        ui.horizontal(|ui| {
-            config
-                .hkeys
-                .iter_mut()
-                .enumerate()
-                .for_each(|key| draw_visualizer(ui, key));
-        });
+fn data_bits_count(data_bits: transport::DataBits) -> u8 {
+    match data_bits {
+        transport::DataBits::Five => 5,
+        transport::DataBits::Six => 6,
+        transport::DataBits::Seven => 7,
+        transport::DataBits::Eight => 8,
+    }
+}
+
+fn stop_bits_count(stop_bits: transport::StopBits) -> u8 {
+    match stop_bits {
+        transport::StopBits::One => 1,
+        transport::StopBits::Two => 2,
     }
 }
@@ -1,15 +1,36 @@
-use crate::device::{self, DeviceHandle, Devices};
+use crustility_core::device::{self, DeviceHandle, Devices};
+use crustility_core::{backup, validate};
 
+mod accessibility;
+mod autoswitch;
+mod backups;
+mod board;
 mod combobox;
+mod compare;
+mod dashboard;
+mod i18n;
+mod interop;
+mod keybind;
 mod layout;
+mod notify;
+mod overlay;
+mod permissions;
+mod presets;
+mod profiles;
+mod resync;
+mod rpc;
+mod settings;
+mod share;
 mod theme;
 
 pub fn run() -> Result<(), eframe::Error> {
     env_logger::init();
-    let options = eframe::NativeOptions {
+    let settings = settings::Settings::load();
+    let mut options = eframe::NativeOptions {
         initial_window_size: Some(egui::vec2(720.0, 480.0)),
         ..Default::default()
     };
+    settings.window_options(&mut options);
 
     let mut devices = Devices::default();
     devices.scan();
@@ -17,44 +38,157 @@ pub fn run() -> Result<(), eframe::Error> {
     eframe::run_native(
         "Crustility",
         options,
-        Box::new(|_cc| Box::new(Crustility::new(devices))),
+        Box::new(|_cc| Box::new(Crustility::new(devices, settings))),
     )
 }
 
 struct Crustility {
     device: Option<DeviceHandle>,
-    error: Option<device::Error>,
+    notifications: notify::Notifications,
     selected_key: Option<usize>,
     devices: Devices,
+    settings: settings::Settings,
+    themes: Vec<theme::Theme>,
+    /// visuals of the selected theme
     theme: egui::Visuals,
+    permission_prompt: Option<permissions::PermissionPrompt>,
+    board_view: board::BoardView,
+    show_settings: bool,
+    autoswitch: autoswitch::AutoSwitch,
+    dashboard: dashboard::Dashboard,
+    compare: compare::Compare,
+    interop: interop::Interop,
+    rpc: rpc::Rpc,
+    overlay: overlay::Overlay,
+    presets: presets::Presets,
+    share: share::Share,
+    resync: resync::Resync,
+    backups: backups::Backups,
+    /// action waiting for the user to confirm it
+    confirm: Option<Confirm>,
+}
+
+/// Actions that can be set to ask before they happen
+#[derive(Debug, Clone, PartialEq)]
+enum Confirm {
+    Apply,
+    DeleteProfile(String),
+    /// restore the firmware defaults of the selected device
+    Reset,
 }
 
 impl Crustility {
-    fn new(devices: Devices) -> Self {
-        Self {
+    fn new(devices: Devices, settings: settings::Settings) -> Self {
+        i18n::set_language(&settings.language);
+        let mut app = Self {
             device: None,
-            error: None,
+            notifications: notify::Notifications::default(),
             selected_key: None,
             devices,
-            theme: theme::horizon_dark(),
+            settings,
+            themes: Vec::new(),
+            theme: theme::horizon_dark().visuals(),
+            permission_prompt: None,
+            board_view: board::BoardView::default(),
+            show_settings: false,
+            autoswitch: autoswitch::AutoSwitch::load(),
+            dashboard: dashboard::Dashboard::default(),
+            compare: compare::Compare::default(),
+            interop: interop::Interop::default(),
+            rpc: rpc::Rpc::default(),
+            overlay: overlay::Overlay::default(),
+            presets: presets::Presets::default(),
+            share: share::Share::default(),
+            resync: resync::Resync::default(),
+            backups: backups::Backups::default(),
+            confirm: None,
+        };
+        app.reload_themes();
+
+        if let Some(identity) = &app.settings.last_device {
+            app.device = app
+                .devices
+                .iter()
+                .find(|handle| handle.identity() == *identity)
+                .copied();
+            app.read_config();
+        }
+        app
+    }
+
+    /// Load the built in and user themes and apply the selected one
+    fn reload_themes(&mut self) {
+        let (themes, errors) = theme::load_all();
+        for (path, e) in errors {
+            self.notifications.error(
+                i18n::tr!("theme-load-error", path = path.display().to_string()),
+                &e,
+            );
+        }
+        self.themes = themes;
+
+        let selected = self
+            .themes
+            .iter()
+            .find(|theme| theme.name == self.settings.theme);
+        match selected {
+            Some(theme) => self.theme = theme.palette.visuals(),
+            None => {
+                self.notifications.warning(i18n::tr!(
+                    "theme-missing",
+                    name = self.settings.theme.as_str(),
+                    default = theme::DEFAULT_THEME
+                ));
+                self.set_theme(theme::DEFAULT_THEME.to_string());
+            }
         }
     }
 
-    /// gracefully consume error and log it
-    /// you can use crustility.error to display it on the gui
-    fn consume_error<T>(&mut self, result: Result<T, device::Error>) {
-        if let Err(e) = result {
-            log::error!("{e}");
-            self.error = Some(e);
+    /// Switch to a theme and remember it
+    fn set_theme(&mut self, name: String) {
+        if let Some(theme) = self.themes.iter().find(|theme| theme.name == name) {
+            self.theme = theme.palette.visuals();
+        }
+        self.settings.theme = name;
+        if let Err(e) = self.settings.save() {
+            self.notifications
+                .error(i18n::tr!("settings-save-error"), &e);
+        }
+    }
+
+    /// gracefully consume error, logging it and showing it as a notification
+    fn consume_error<T>(&mut self, context: &str, result: Result<T, device::Error>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(e) => {
+                self.notifications.error(context, &e);
+                None
+            }
         }
     }
 }
 
 impl eframe::App for Crustility {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        self.record_window(&frame.info().window_info);
         self.handle_data();
-        ctx.set_pixels_per_point(1.5);
+        self.poll_autoswitch(ctx);
+        self.poll_rpc(ctx);
+        self.poll_overlay(ctx);
+        self.poll_resync(ctx);
+        ctx.set_pixels_per_point(self.settings.ui_scale);
         ctx.set_visuals(self.theme.clone());
+        self.permission_window(ctx);
+        self.confirm_window(ctx);
+        self.settings_window(ctx);
+        self.autoswitch_window(ctx);
+        self.dashboard_window(ctx);
+        self.compare_window(ctx);
+        self.interop_window(ctx);
+        self.share_window(ctx);
+        self.preset_window(ctx);
+        self.resync_window(ctx);
+        self.backups_window(ctx);
         egui::CentralPanel::default().show(ctx, |ui| {
             if self.device.is_some() {
                 self.menu_bar(ctx, ui);
@@ -63,33 +197,242 @@ impl eframe::App for Crustility {
                 self.default_panel(ctx, ui);
             }
         });
+        self.keyboard_shortcuts(ctx);
+        self.notifications.show_log(ctx);
+        self.notifications.show_toasts(ctx);
+    }
+
+    fn on_close_event(&mut self) -> bool {
+        self.save_settings();
+        true
     }
 }
 
 impl Crustility {
+    /// Handle the events of every connected device
     fn handle_data(&mut self) {
-        if let Some(device) = self.device {
-            if let Some(device) = self.devices.get_mut(&device) {
-                let _ = device.spawn_event_loop(); //TODO handle this error
-                let Ok(data) = device.recv_data() else {
-                    return;
-                };
-
-                match data {
-                    device::Event::Init => {
-                        let _ = device.send_event(device::SendEvent::ReadSensorsBegin);
+        if let Some(handle) = self.device {
+            if let Some(device) = self.devices.get_mut(&handle) {
+                if let Err(e) = device.spawn_event_loop() {
+                    self.notifications.error(
+                        i18n::tr!("device-connect-error", device = device.name().as_str()),
+                        &e,
+                    );
+                }
+            }
+        }
+        let handles = self.devices.iter().copied().collect::<Vec<_>>();
+        for handle in handles {
+            self.handle_device_data(handle);
+        }
+    }
+
+    fn handle_device_data(&mut self, handle: DeviceHandle) {
+        let selected = self.device == Some(handle);
+        let Some(device) = self.devices.get_mut(&handle) else {
+            return;
+        };
+        if !device.is_connected() {
+            return;
+        }
+
+        let mut lost_connection = false;
+        let mut read_config = false;
+        let mut checked = None;
+        while let Ok(data) = device.recv_data() {
+            match data {
+                device::Event::Init => {
+                    self.dashboard.clear_error(&handle);
+                    let mut result = device.send_event(device::SendEvent::SetPollingRate(
+                        self.settings.polling_rate,
+                    ));
+                    // only the selected device shows live positions
+                    if selected {
+                        result = result
+                            .and_then(|_| device.send_event(device::SendEvent::ReadSensorsBegin));
                     }
-                    device::Event::Sensor(v) => {
-                        let Some(config) = device.config_mut() else {
-                            return;
-                        };
-                        config.hkeys[v.key].target_position = v.mapped
+                    if let Err(e) = result {
+                        self.notifications
+                            .error(i18n::tr!("sensors-start-error"), &e);
                     }
-                    device::Event::Config(v) => {
-                        device.set_config(v);
+                }
+                device::Event::Sensor(v) => {
+                    let pressed = device.config_mut().and_then(|config| {
+                        let capabilities = config.capabilities;
+                        let key = config.hkeys.get_mut(v.key)?;
+                        key.target_position = v.mapped;
+                        key.update_trigger(&capabilities, v.mapped);
+                        Some(key.trigger.pressed)
+                    });
+                    self.rpc.publish_sensor(handle, &v, pressed);
+                }
+                device::Event::PollStats(v) => {
+                    let was_throttled = device.poll_stats().is_some_and(|s| s.throttled);
+                    if v.throttled && !was_throttled {
+                        self.notifications.warning(i18n::tr!(
+                            "device-throttled",
+                            device = device.name().as_str(),
+                            rate = v.target_rate
+                        ));
+                    }
+                    device.set_poll_stats(v)
+                }
+                device::Event::Config(v) => {
+                    self.notifications.info(i18n::tr!(
+                        "config-read",
+                        count = v.hkeys.len(),
+                        device = device.name().as_str()
+                    ));
+                    device.set_config(v);
+                    read_config = true;
+                }
+                device::Event::CheckedConfig(v) => checked = Some(v),
+                // tried again on the next check
+                device::Event::CheckFailed(e) => {
+                    log::warn!("could not check {} for changes: {e}", device.name());
+                }
+                device::Event::Error(e) => {
+                    // the prompt explains what to do instead of a notification
+                    if let device::Error::PermissionDenied(port_name) = &e {
+                        self.permission_prompt = Some(permissions::PermissionPrompt::new(
+                            handle,
+                            port_name.clone(),
+                        ));
+                    } else {
+                        self.notifications.error(
+                            i18n::tr!("device-connection-lost", device = device.name().as_str()),
+                            &e,
+                        );
                     }
+                    self.dashboard.set_error(handle, &e);
+                    lost_connection = true;
                 }
-            };
+            }
+        }
+
+        if lost_connection {
+            // connect again once the device gets selected
+            device.restart_event_loop();
+            if selected {
+                self.device = None;
+                self.selected_key = None;
+            }
+            self.dashboard.connection_lost(&handle);
+            self.rpc.connection_lost(&handle);
+        } else if read_config {
+            self.resume_push(handle);
+            self.resume_rpc(handle);
+        }
+        if let Some(config) = checked.filter(|_| !lost_connection) {
+            self.config_checked(handle, config);
+        }
+    }
+
+    /// Apply the config, asking first if the settings say so
+    ///
+    /// Returns false if it could not be applied, waiting for the answer counts as applied
+    fn request_apply(&mut self) -> bool {
+        if self.settings.confirm_apply {
+            self.confirm = Some(Confirm::Apply);
+            true
+        } else {
+            self.apply_config()
+        }
+    }
+
+    /// Send the edited config to the selected device, returns if it was sent
+    fn apply_config(&mut self) -> bool {
+        let Some(handle) = self.device else {
+            return false;
+        };
+        let Some(device) = self.devices.get_mut(&handle) else {
+            return false;
+        };
+
+        let Some(config) = device.config() else {
+            return false;
+        };
+        if !validate::validate(config).is_valid() {
+            self.notifications.warning(i18n::tr!("apply-invalid"));
+            return false;
+        }
+        let key_count = config.hkeys.len();
+        if let Err(e) = backups::take(&handle, device, backup::Reason::Apply) {
+            self.notifications.error(i18n::tr!("backup-error"), &e);
+            return false;
+        }
+        self.backups.refresh();
+
+        let result = device
+            .serialize_config()
+            .and_then(|raw_config| device.send_event(device::SendEvent::SendCommands(raw_config)));
+        match result {
+            Ok(()) => {
+                device.mark_synced();
+                self.notifications
+                    .success(i18n::tr!("config-applied", count = key_count));
+                true
+            }
+            Err(e) => {
+                self.notifications.error(i18n::tr!("apply-error"), &e);
+                false
+            }
+        }
+    }
+
+    /// Ask the selected device for its config
+    fn read_config(&mut self) {
+        let Some(handle) = self.device else {
+            return;
+        };
+        let Some(device) = self.devices.get_mut(&handle) else {
+            return;
+        };
+
+        self.settings.last_device = Some(handle.identity());
+        let was_connected = device.is_connected();
+        let mut result = device
+            .spawn_event_loop()
+            .and_then(|_| device.send_event(device::SendEvent::ReadConfig));
+        // a device that just connected starts reading sensors on init
+        if was_connected {
+            result = result.and_then(|_| device.send_event(device::SendEvent::ReadSensorsBegin));
+        }
+        self.consume_error(&i18n::tr!("config-read-error"), result);
+    }
+
+    /// Select a device and read its config
+    fn select_device(&mut self, handle: DeviceHandle) {
+        let previous = self.device.replace(handle);
+        if previous != Some(handle) {
+            self.selected_key = None;
+            self.stop_sensors(previous);
+        }
+        self.read_config();
+    }
+
+    /// Stop reading the sensors of a device that is not shown anymore
+    fn stop_sensors(&mut self, handle: Option<DeviceHandle>) {
+        let Some(handle) = handle.filter(|handle| !self.rpc.is_subscribed(handle)) else {
+            return;
+        };
+        let Some(device) = self.devices.get_mut(&handle) else {
+            return;
+        };
+        if device.is_connected() {
+            let result = device.send_event(device::SendEvent::ReadSensorsEnd);
+            self.consume_error(&i18n::tr!("sensors-stop-error"), result);
+        }
+    }
+
+    /// Send the polling rate from the settings to the selected device
+    fn set_polling_rate(&mut self) {
+        let Some(device) = self.device.and_then(|handle| self.devices.get_mut(&handle)) else {
+            return;
         };
+        let result = device.send_event(device::SendEvent::SetPollingRate(
+            self.settings.polling_rate,
+        ));
+        self.consume_error(&i18n::tr!("polling-rate-error"), result);
     }
 }
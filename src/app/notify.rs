@@ -0,0 +1,262 @@
+use std::time::{Duration, Instant};
+
+use egui::{Color32, RichText};
+
+use super::{accessibility::labeled, i18n::tr};
+
+/// how long a toast stays up before it fades out
+const TOAST_DURATION: Duration = Duration::from_secs(5);
+/// errors stay up longer so the details can be read
+const ERROR_TOAST_DURATION: Duration = Duration::from_secs(10);
+const TOAST_WIDTH: f32 = 280.;
+const MAX_LOG_LEN: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl Level {
+    fn color(self, visuals: &egui::Visuals) -> Color32 {
+        match self {
+            Level::Info => visuals.text_color(),
+            Level::Success => Color32::from_rgb(0x29, 0xd3, 0x98),
+            Level::Warning => visuals.warn_fg_color,
+            Level::Error => visuals.error_fg_color,
+        }
+    }
+
+    fn icon(self) -> &'static str {
+        match self {
+            Level::Info => "ℹ",
+            Level::Success => "✔",
+            Level::Warning => "⚠",
+            Level::Error => "✖",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Notification {
+    /// unique for every notification, keeps the state of its widgets apart
+    pub id: u64,
+    pub level: Level,
+    pub message: String,
+    /// shown when the notification is expanded
+    pub details: Option<String>,
+    pub time: Instant,
+}
+
+#[derive(Debug)]
+struct Toast {
+    notification: Notification,
+    shown_since: Instant,
+}
+
+/// Toast notifications and the log of everything that was reported
+#[derive(Debug, Default)]
+pub struct Notifications {
+    toasts: Vec<Toast>,
+    log: Vec<Notification>,
+    /// errors that have not been looked at in the log window yet
+    unread_errors: usize,
+    pub show_log: bool,
+    next_id: u64,
+}
+
+impl Notifications {
+    pub fn push(&mut self, level: Level, message: impl Into<String>, details: Option<String>) {
+        self.next_id += 1;
+        let notification = Notification {
+            id: self.next_id,
+            level,
+            message: message.into(),
+            details,
+            time: Instant::now(),
+        };
+        match level {
+            Level::Error => log::error!("{}", notification.message),
+            Level::Warning => log::warn!("{}", notification.message),
+            Level::Info | Level::Success => log::info!("{}", notification.message),
+        }
+        if level == Level::Error && !self.show_log {
+            self.unread_errors += 1;
+        }
+
+        if self.log.len() == MAX_LOG_LEN {
+            self.log.remove(0);
+        }
+        self.log.push(notification.clone());
+        self.toasts.push(Toast {
+            notification,
+            shown_since: Instant::now(),
+        });
+    }
+
+    pub fn info(&mut self, message: impl Into<String>) {
+        self.push(Level::Info, message, None);
+    }
+
+    pub fn success(&mut self, message: impl Into<String>) {
+        self.push(Level::Success, message, None);
+    }
+
+    pub fn warning(&mut self, message: impl Into<String>) {
+        self.push(Level::Warning, message, None);
+    }
+
+    /// Report an error, the error and its sources end up in the details
+    pub fn error(&mut self, context: impl Into<String>, error: &dyn std::error::Error) {
+        let mut details = error.to_string();
+        let mut source = error.source();
+        while let Some(e) = source {
+            details += "\n";
+            details += &tr!("notify-caused-by", error = e.to_string());
+            source = e.source();
+        }
+        self.push(Level::Error, context, Some(details));
+    }
+
+    pub fn unread_errors(&self) -> usize {
+        self.unread_errors
+    }
+
+    pub fn toggle_log(&mut self) {
+        self.show_log = !self.show_log;
+        self.unread_errors = 0;
+    }
+
+    /// Draw the toasts in the bottom right corner
+    pub fn show_toasts(&mut self, ctx: &egui::Context) {
+        self.toasts.retain(|toast| {
+            let duration = if toast.notification.level == Level::Error {
+                ERROR_TOAST_DURATION
+            } else {
+                TOAST_DURATION
+            };
+            toast.shown_since.elapsed() < duration
+        });
+        if self.toasts.is_empty() {
+            return;
+        }
+        ctx.request_repaint_after(Duration::from_millis(250));
+
+        let mut dismissed = None;
+        egui::Area::new(egui::Id::new("toasts"))
+            .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-10., -10.))
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                ui.set_max_width(TOAST_WIDTH);
+                for (i, toast) in self.toasts.iter_mut().enumerate() {
+                    let response = egui::Frame::popup(ui.style())
+                        .show(ui, |ui| {
+                            ui.set_width(TOAST_WIDTH);
+                            ui.horizontal(|ui| {
+                                notification_label(ui, &toast.notification);
+                                ui.with_layout(
+                                    egui::Layout::right_to_left(egui::Align::Min),
+                                    |ui| {
+                                        let dismiss = ui.small_button("✖");
+                                        if labeled(dismiss, &tr!("notify-dismiss")).clicked() {
+                                            dismissed = Some(i);
+                                        }
+                                    },
+                                );
+                            });
+                            if let Some(details) = &toast.notification.details {
+                                egui::CollapsingHeader::new(tr!("notify-details"))
+                                    .id_source(("toast details", toast.notification.id))
+                                    .show(ui, |ui| {
+                                        ui.label(details);
+                                    });
+                            }
+                        })
+                        .response;
+
+                    // keep the toast up while it is being read
+                    if response.hovered() {
+                        toast.shown_since = Instant::now();
+                    }
+                }
+            });
+
+        if let Some(i) = dismissed {
+            self.toasts.remove(i);
+        }
+    }
+
+    /// Window listing every notification
+    pub fn show_log(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_log;
+        egui::Window::new(tr!("notify-log"))
+            .open(&mut open)
+            .default_width(400.)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if ui.button(tr!("notify-clear")).clicked() {
+                        self.log.clear();
+                    }
+                    if ui.button(tr!("notify-copy")).clicked() {
+                        let text = self
+                            .log
+                            .iter()
+                            .map(|n| {
+                                let mut line = format!("[{:?}] {}", n.level, n.message);
+                                if let Some(details) = &n.details {
+                                    line += &format!(": {details}");
+                                }
+                                line
+                            })
+                            .collect::<Vec<_>>()
+                            .join("\n");
+                        ui.output_mut(|o| o.copied_text = text);
+                    }
+                });
+                ui.separator();
+
+                egui::ScrollArea::vertical()
+                    .stick_to_bottom(true)
+                    .show(ui, |ui| {
+                        if self.log.is_empty() {
+                            ui.weak(tr!("notify-empty"));
+                        }
+                        for notification in &self.log {
+                            ui.horizontal(|ui| {
+                                ui.weak(format_age(notification.time.elapsed()));
+                                notification_label(ui, notification);
+                            });
+                            if let Some(details) = &notification.details {
+                                ui.indent(notification.id, |ui| {
+                                    egui::CollapsingHeader::new(tr!("notify-details"))
+                                        .id_source(("log details", notification.id))
+                                        .show(ui, |ui| {
+                                            ui.label(details);
+                                        });
+                                });
+                            }
+                        }
+                    });
+            });
+        self.show_log = open;
+    }
+}
+
+fn notification_label(ui: &mut egui::Ui, notification: &Notification) {
+    let color = notification.level.color(ui.visuals());
+    ui.label(RichText::new(notification.level.icon()).color(color));
+    ui.add(egui::Label::new(RichText::new(&notification.message).color(color)).wrap(true));
+}
+
+pub fn format_age(age: Duration) -> String {
+    let secs = age.as_secs();
+    if secs < 60 {
+        tr!("age-seconds", count = secs)
+    } else if secs < 60 * 60 {
+        tr!("age-minutes", count = secs / 60)
+    } else {
+        tr!("age-hours", count = secs / (60 * 60))
+    }
+}
@@ -0,0 +1,76 @@
+use crate::overlay::{Frame, KeyState, Server};
+
+use super::{dashboard, i18n::tr, Crustility};
+
+/// State of the key overlay server
+#[derive(Default)]
+pub struct Overlay {
+    server: Option<Server>,
+    /// port the server could not start on, don't retry every frame
+    failed: Option<u16>,
+}
+
+impl Overlay {
+    /// Connected overlays, `None` when the server is not running
+    pub fn clients(&self) -> Option<usize> {
+        self.server.as_ref().map(Server::clients)
+    }
+}
+
+impl Crustility {
+    /// Start or stop the overlay server as the settings say and send it the key states
+    pub fn poll_overlay(&mut self, ctx: &egui::Context) {
+        let port = self.settings.overlay_port;
+        let state = &mut self.overlay;
+        if !self.settings.overlay_server || state.server.as_ref().is_some_and(|s| s.port() != port)
+        {
+            *state = Overlay::default();
+            if !self.settings.overlay_server {
+                return;
+            }
+        }
+        if state.server.is_none() && state.failed != Some(port) {
+            match Server::start(port, self.settings.overlay_rate) {
+                Ok(server) => state.server = Some(server),
+                Err(e) => {
+                    state.failed = Some(port);
+                    self.notifications
+                        .error(tr!("overlay-start-error", port = port), &e);
+                    return;
+                }
+            }
+        }
+        let Some(server) = &mut state.server else {
+            return;
+        };
+        server.set_rate(self.settings.overlay_rate);
+
+        let Some((handle, device)) = self
+            .device
+            .and_then(|handle| Some((handle, self.devices.get(&handle)?)))
+        else {
+            return;
+        };
+        let Some(config) = device.config() else {
+            return;
+        };
+        let frame = Frame {
+            device: dashboard::device_name(&handle, device),
+            travel: config.capabilities.travel_distance.as_mm(),
+            keys: config
+                .hkeys
+                .iter()
+                .map(|key| KeyState {
+                    label: key.keycode.to_string(),
+                    position: key.target_position.as_mm(),
+                    pressed: key.trigger.pressed,
+                    actuation: key.hysterisis.lower.as_mm(),
+                    release: key.hysterisis.upper.as_mm(),
+                })
+                .collect(),
+        };
+        server.publish(&frame);
+        // keep sending while the window is in the background
+        ctx.request_repaint_after(std::time::Duration::from_secs(1) / self.settings.overlay_rate);
+    }
+}
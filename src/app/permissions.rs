@@ -0,0 +1,131 @@
+#[cfg(target_os = "linux")]
+use std::sync::mpsc::Receiver;
+
+use crustility_core::device::DeviceHandle;
+#[cfg(target_os = "linux")]
+use crustility_core::udev;
+
+use super::{i18n::tr, Crustility};
+
+/// Shown when the serial port of a device could not be opened for lack of permissions
+pub struct PermissionPrompt {
+    handle: DeviceHandle,
+    port_name: String,
+    /// group owning the port and whether the user is in it
+    #[cfg(target_os = "linux")]
+    group: Option<(String, bool)>,
+    #[cfg(target_os = "linux")]
+    install: Option<Receiver<Result<(), udev::Error>>>,
+    install_error: Option<String>,
+}
+
+impl PermissionPrompt {
+    pub fn new(handle: DeviceHandle, port_name: String) -> Self {
+        Self {
+            handle,
+            #[cfg(target_os = "linux")]
+            group: udev::port_group(&port_name).map(|group| {
+                let in_group = udev::user_groups()
+                    .map(|groups| groups.contains(&group))
+                    .unwrap_or(false);
+                (group, in_group)
+            }),
+            port_name,
+            #[cfg(target_os = "linux")]
+            install: None,
+            install_error: None,
+        }
+    }
+}
+
+impl Crustility {
+    pub fn permission_window(&mut self, ctx: &egui::Context) {
+        let Some(prompt) = &mut self.permission_prompt else {
+            return;
+        };
+        let mut open = true;
+        let mut retry = false;
+
+        egui::Window::new(tr!("permission-title"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0., 0.))
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.label(tr!("permission-denied", port = prompt.port_name.as_str()));
+
+                #[cfg(target_os = "linux")]
+                {
+                    ui.label(tr!("permission-groups"));
+                    match &prompt.group {
+                        Some((group, true)) => {
+                            ui.label(tr!("permission-in-group", group = group.as_str()));
+                        }
+                        Some((group, false)) => {
+                            ui.label(tr!("permission-join-group", group = group.as_str()));
+                            ui.code(format!("sudo usermod -aG {group} $USER"));
+                        }
+                        None => (),
+                    }
+                    ui.separator();
+
+                    let group = prompt
+                        .group
+                        .as_ref()
+                        .map_or("dialout", |(group, _)| group.as_str());
+                    let rule = udev::rule(&prompt.handle, group);
+                    ui.label(tr!("permission-udev"));
+                    ui.collapsing(tr!("permission-rule"), |ui| {
+                        ui.code(&rule);
+                        ui.label(udev::rule_path(&prompt.handle).display().to_string());
+                    });
+
+                    if let Some(install) = &prompt.install {
+                        match install.try_recv() {
+                            Ok(Ok(())) => {
+                                prompt.install = None;
+                                retry = true;
+                            }
+                            Ok(Err(e)) => {
+                                prompt.install = None;
+                                prompt.install_error = Some(e.to_string());
+                            }
+                            Err(_) => {
+                                ui.horizontal(|ui| {
+                                    ui.spinner();
+                                    ui.label(tr!("permission-waiting"));
+                                });
+                            }
+                        }
+                    } else {
+                        ui.horizontal(|ui| {
+                            if ui.button(tr!("permission-install")).clicked() {
+                                prompt.install_error = None;
+                                prompt.install = Some(udev::install_rule(&prompt.handle, rule));
+                            }
+                            if udev::rule_installed(&prompt.handle)
+                                && ui.button(tr!("permission-retry")).clicked()
+                            {
+                                retry = true;
+                            }
+                        });
+                    }
+                }
+
+                if let Some(e) = &prompt.install_error {
+                    ui.colored_label(ui.visuals().error_fg_color, e);
+                }
+            });
+
+        if retry {
+            let handle = prompt.handle;
+            self.permission_prompt = None;
+            if let Some(device) = self.devices.get_mut(&handle) {
+                device.restart_event_loop();
+                self.select_device(handle);
+            }
+        } else if !open {
+            self.permission_prompt = None;
+        }
+    }
+}
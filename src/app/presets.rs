@@ -0,0 +1,260 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crustility_core::{
+    config::HKey,
+    device::DeviceHandle,
+    preset::{self, Kind, Params, Preset},
+};
+
+use super::{i18n::tr, layout::setting_slider, Crustility};
+
+/// Applying a preset from the catalog to some keys
+pub struct Presets {
+    catalog: Vec<Preset>,
+    /// index into the catalog, the window is shown while one is picked
+    picked: Option<usize>,
+    params: Option<Params>,
+    keys: BTreeSet<usize>,
+    preview: bool,
+    /// keys from before the preview, put back when it ends or they get unchecked
+    original: Option<(DeviceHandle, BTreeMap<usize, HKey>)>,
+}
+
+impl Default for Presets {
+    fn default() -> Self {
+        Self {
+            catalog: preset::catalog(),
+            picked: None,
+            params: None,
+            keys: BTreeSet::new(),
+            preview: true,
+            original: None,
+        }
+    }
+}
+
+impl Crustility {
+    pub fn preset_menu(&mut self, ui: &mut egui::Ui) {
+        let mut picked = None;
+        ui.menu_button(tr!("menu-presets"), |ui| {
+            for (i, preset) in self.presets.catalog.iter().enumerate() {
+                if ui
+                    .selectable_label(self.presets.picked == Some(i), preset_name(preset.kind))
+                    .on_hover_text(preset_description(preset.kind))
+                    .clicked()
+                {
+                    picked = Some(i);
+                    ui.close_menu();
+                }
+            }
+        });
+
+        if let Some(i) = picked {
+            self.end_preview();
+            let presets = &mut self.presets;
+            presets.picked = Some(i);
+            presets.params = Some(presets.catalog[i].params());
+            presets.keys = match self.selected_key {
+                Some(key) => BTreeSet::from([key]),
+                None => self
+                    .device
+                    .and_then(|handle| self.devices.get(&handle))
+                    .and_then(|device| device.config())
+                    .map(|config| (0..config.hkeys.len()).collect())
+                    .unwrap_or_default(),
+            };
+        }
+    }
+
+    /// Parameters of the picked preset and the keys it goes to
+    ///
+    /// While previewing the preset is applied to the edited config so the
+    /// visualizer shows it against the live key positions
+    pub fn preset_window(&mut self, ctx: &egui::Context) {
+        let (Some(i), Some(mut params)) = (self.presets.picked, self.presets.params) else {
+            return;
+        };
+        let Some(handle) = self.device else {
+            self.close_preset();
+            return;
+        };
+        if self
+            .presets
+            .original
+            .as_ref()
+            .is_some_and(|(h, _)| *h != handle)
+        {
+            // the device changed, its config never had the preview
+            self.presets.original = None;
+        }
+        let Some(config) = self.devices.get(&handle).and_then(|d| d.config()) else {
+            return;
+        };
+        let key_count = config.hkeys.len();
+        let travel = f32::from(config.capabilities.travel_distance);
+
+        let mut open = true;
+        let mut apply = false;
+        let mut cancel = false;
+        let presets = &mut self.presets;
+        let preset = presets.catalog[i].clone();
+        egui::Window::new(tr!("preset-title", name = preset_name(preset.kind)))
+            .open(&mut open)
+            .default_width(320.)
+            .show(ctx, |ui| {
+                ui.label(preset_description(preset.kind));
+                ui.separator();
+                setting_slider(
+                    ui,
+                    &mut params.actuation,
+                    travel,
+                    &tr!("preset-actuation"),
+                    None,
+                );
+                if preset.rapid_trigger.is_some() {
+                    setting_slider(
+                        ui,
+                        &mut params.sensitivity,
+                        travel,
+                        &tr!("preset-sensitivity"),
+                        None,
+                    );
+                }
+                ui.separator();
+
+                ui.label(tr!("preset-keys"));
+                ui.horizontal_wrapped(|ui| {
+                    for key in 0..key_count {
+                        let mut checked = presets.keys.contains(&key);
+                        if ui.checkbox(&mut checked, (key + 1).to_string()).changed() {
+                            if checked {
+                                presets.keys.insert(key);
+                            } else {
+                                presets.keys.remove(&key);
+                            }
+                        }
+                    }
+                });
+                ui.horizontal(|ui| {
+                    if ui.small_button(tr!("preset-all")).clicked() {
+                        presets.keys = (0..key_count).collect();
+                    }
+                    if ui.small_button(tr!("preset-none")).clicked() {
+                        presets.keys.clear();
+                    }
+                });
+                ui.separator();
+
+                ui.checkbox(&mut presets.preview, tr!("preset-preview"));
+                ui.horizontal(|ui| {
+                    apply = ui
+                        .add_enabled(
+                            !presets.keys.is_empty(),
+                            egui::Button::new(tr!("preset-apply", count = presets.keys.len())),
+                        )
+                        .clicked();
+                    cancel = ui.button(tr!("preset-cancel")).clicked();
+                });
+            });
+        self.presets.params = Some(params);
+
+        if apply {
+            self.end_preview();
+            let keys = self.presets.keys.clone();
+            if let Some(config) = self.devices.get_mut(&handle).and_then(|d| d.config_mut()) {
+                let capabilities = config.capabilities;
+                for i in &keys {
+                    if let Some(key) = config.hkeys.get_mut(*i) {
+                        preset.apply(&params, &capabilities, key);
+                    }
+                }
+            }
+            self.notifications.info(tr!(
+                "preset-applied",
+                count = keys.len(),
+                name = preset_name(preset.kind)
+            ));
+            self.close_preset();
+        } else if cancel || !open {
+            self.end_preview();
+            self.close_preset();
+        } else if self.presets.preview {
+            self.show_preview(handle, &preset, &params);
+        } else {
+            self.end_preview();
+        }
+    }
+
+    /// Apply the preset to the checked keys, remembering what they were before
+    fn show_preview(&mut self, handle: DeviceHandle, preset: &Preset, params: &Params) {
+        let Some(config) = self.devices.get_mut(&handle).and_then(|d| d.config_mut()) else {
+            return;
+        };
+        let keys = &self.presets.keys;
+        let original = &mut self
+            .presets
+            .original
+            .get_or_insert_with(|| (handle, BTreeMap::new()))
+            .1;
+        original.retain(|i, before| {
+            let checked = keys.contains(i);
+            if let Some(key) = config.hkeys.get_mut(*i).filter(|_| !checked) {
+                restore(key, before);
+            }
+            checked
+        });
+
+        let capabilities = config.capabilities;
+        for i in keys {
+            if let Some(key) = config.hkeys.get_mut(*i) {
+                original.entry(*i).or_insert_with(|| key.clone());
+                preset.apply(params, &capabilities, key);
+            }
+        }
+    }
+
+    /// Put back the settings the preview replaced
+    fn end_preview(&mut self) {
+        let Some((handle, original)) = self.presets.original.take() else {
+            return;
+        };
+        if let Some(config) = self.devices.get_mut(&handle).and_then(|d| d.config_mut()) {
+            for (i, before) in &original {
+                if let Some(key) = config.hkeys.get_mut(*i) {
+                    restore(key, before);
+                }
+            }
+        }
+    }
+
+    fn close_preset(&mut self) {
+        self.presets.picked = None;
+        self.presets.params = None;
+    }
+}
+
+/// Settings a preset changes, the live state of the key stays
+fn preset_name(kind: Kind) -> String {
+    match kind {
+        Kind::OsuAggressive => tr!("preset-osu-aggressive"),
+        Kind::OsuBalanced => tr!("preset-osu-balanced"),
+        Kind::Gaming => tr!("preset-gaming"),
+        Kind::Typing => tr!("preset-typing"),
+        Kind::AntiChatter => tr!("preset-anti-chatter"),
+    }
+}
+
+fn preset_description(kind: Kind) -> String {
+    match kind {
+        Kind::OsuAggressive => tr!("preset-osu-aggressive-description"),
+        Kind::OsuBalanced => tr!("preset-osu-balanced-description"),
+        Kind::Gaming => tr!("preset-gaming-description"),
+        Kind::Typing => tr!("preset-typing-description"),
+        Kind::AntiChatter => tr!("preset-anti-chatter-description"),
+    }
+}
+
+fn restore(key: &mut HKey, before: &HKey) {
+    key.rt = before.rt.clone();
+    key.hysterisis = before.hysterisis.clone();
+}
@@ -0,0 +1,149 @@
+use crustility_core::profile;
+
+use super::{accessibility::labeled, i18n::tr, Confirm, Crustility};
+
+enum ProfileAction {
+    Load(String),
+    Save,
+    Delete(String),
+}
+
+impl Crustility {
+    /// Named profiles the edited config can be saved to and loaded from
+    pub fn profile_menu(&mut self, ui: &mut egui::Ui) {
+        let id = ui.make_persistent_id("profile name");
+        let mut name = ui
+            .ctx()
+            .data_mut(|d| d.get_temp::<String>(id))
+            .or_else(|| self.settings.last_profile.clone())
+            .unwrap_or_default();
+        let mut action = None;
+
+        let title = match &self.settings.last_profile {
+            Some(profile) => tr!("profile-named", name = profile.as_str()),
+            None => tr!("profile"),
+        };
+        ui.menu_button(title, |ui| {
+            match profile::list() {
+                Ok(names) if names.is_empty() => {
+                    ui.weak(tr!("no-profiles"));
+                }
+                Ok(names) => {
+                    for profile in names {
+                        ui.horizontal(|ui| {
+                            let current = self.settings.last_profile.as_ref() == Some(&profile);
+                            if ui.selectable_label(current, &profile).clicked() {
+                                action = Some(ProfileAction::Load(profile.clone()));
+                                ui.close_menu();
+                            }
+                            let delete = ui.small_button("🗑").on_hover_text(tr!("delete"));
+                            if labeled(delete, &tr!("delete-profile", name = profile.as_str()))
+                                .clicked()
+                            {
+                                action = Some(ProfileAction::Delete(profile));
+                            }
+                        });
+                    }
+                }
+                Err(e) => {
+                    ui.colored_label(ui.visuals().error_fg_color, e.to_string());
+                }
+            }
+            ui.separator();
+            ui.horizontal(|ui| {
+                ui.add(
+                    egui::TextEdit::singleline(&mut name)
+                        .hint_text(tr!("profile-name"))
+                        .desired_width(120.),
+                );
+                if ui
+                    .add_enabled(!name.trim().is_empty(), egui::Button::new(tr!("save")))
+                    .clicked()
+                {
+                    action = Some(ProfileAction::Save);
+                }
+            });
+        });
+
+        match action {
+            Some(ProfileAction::Load(profile)) => {
+                self.load_profile(&profile);
+            }
+            Some(ProfileAction::Save) => self.save_profile(name.trim()),
+            Some(ProfileAction::Delete(profile)) => {
+                if self.settings.confirm_delete_profile {
+                    self.confirm = Some(Confirm::DeleteProfile(profile));
+                } else {
+                    self.delete_profile(&profile);
+                }
+            }
+            None => (),
+        }
+        ui.ctx().data_mut(|d| d.insert_temp(id, name));
+    }
+
+    /// Load a profile into the config of the selected device, returns if it was loaded
+    pub fn load_profile(&mut self, name: &str) -> bool {
+        let Some(device) = self.device.and_then(|handle| self.devices.get_mut(&handle)) else {
+            return false;
+        };
+        let Some(config) = device.config_mut() else {
+            return false;
+        };
+        let profile = match profile::load(name) {
+            Ok(profile) => profile,
+            Err(e) => {
+                self.notifications
+                    .error(tr!("profile-load-error", name = name), &e);
+                return false;
+            }
+        };
+
+        let copied = config.apply_settings(&profile);
+        if profile.hkeys.len() != config.hkeys.len() {
+            self.notifications.warning(tr!(
+                "profile-key-count",
+                name = name,
+                profile = profile.hkeys.len(),
+                device = config.hkeys.len()
+            ));
+        }
+        self.notifications
+            .info(tr!("profile-loaded", name = name, count = copied));
+        self.settings.last_profile = Some(name.to_string());
+        true
+    }
+
+    pub fn save_profile(&mut self, name: &str) {
+        let Some(config) = self
+            .device
+            .and_then(|handle| self.devices.get(&handle))
+            .and_then(|device| device.config())
+        else {
+            return;
+        };
+        match profile::save(name, config) {
+            Ok(()) => {
+                self.notifications
+                    .success(tr!("profile-saved", name = name));
+                self.settings.last_profile = Some(name.to_string());
+            }
+            Err(e) => self
+                .notifications
+                .error(tr!("profile-save-error", name = name), &e),
+        }
+    }
+
+    pub fn delete_profile(&mut self, name: &str) {
+        match profile::delete(name) {
+            Ok(()) => {
+                if self.settings.last_profile.as_deref() == Some(name) {
+                    self.settings.last_profile = None;
+                }
+            }
+            Err(e) => self
+                .notifications
+                .error(tr!("profile-delete-error", name = name), &e),
+        }
+    }
+}
@@ -0,0 +1,230 @@
+use std::time::{Duration, Instant};
+
+use crustility_core::{
+    compare,
+    config::Config,
+    device::{self, DeviceHandle},
+};
+
+use super::{
+    compare::{setting_name, value_text},
+    i18n::tr,
+    Crustility,
+};
+
+/// seconds between checks when they are turned on
+pub const DEFAULT_INTERVAL: u32 = 10;
+pub const MIN_INTERVAL: u32 = 2;
+pub const MAX_INTERVAL: u32 = 600;
+
+/// Looking for changes made to the device by other tools
+#[derive(Default)]
+pub struct Resync {
+    last_check: Option<Instant>,
+    /// if the window had focus last frame
+    focused: bool,
+    /// config the device reported that differs from the known one
+    changed: Option<(DeviceHandle, Config)>,
+}
+
+impl Crustility {
+    /// Ask the selected device for its config when the settings say it is time
+    pub fn poll_resync(&mut self, ctx: &egui::Context) {
+        let focused = ctx.input(|i| i.focused);
+        let gained_focus = focused && !self.resync.focused;
+        self.resync.focused = focused;
+
+        let Some(handle) = self.device else {
+            return;
+        };
+        if self.resync.changed.is_some() {
+            return;
+        }
+        let interval = self
+            .settings
+            .resync_interval
+            .map(|seconds| Duration::from_secs(seconds.into()));
+        let due = interval.is_some_and(|interval| {
+            self.resync
+                .last_check
+                .is_none_or(|last| last.elapsed() >= interval)
+        });
+        if let Some(interval) = interval {
+            ctx.request_repaint_after(interval);
+        }
+        let focus_check = gained_focus && self.settings.resync_on_focus;
+        if !due && !focus_check {
+            return;
+        }
+        self.resync.last_check = Some(Instant::now());
+
+        let Some(device) = self.devices.get_mut(&handle) else {
+            return;
+        };
+        // nothing to compare against before the first read
+        if !device.is_connected() || device.synced_config().is_none() {
+            return;
+        }
+        let result = device.send_event(device::SendEvent::CheckConfig);
+        self.consume_error(&tr!("resync-error"), result);
+    }
+
+    /// Compare a config read in the background with what the device is known to have
+    pub fn config_checked(&mut self, handle: DeviceHandle, checked: Config) {
+        let Some(device) = self.devices.get_mut(&handle) else {
+            return;
+        };
+        let unchanged = device
+            .synced_config()
+            .is_none_or(|synced| compare::diff(synced, &checked).is_empty());
+        // edits that happen to match the device need no prompt either
+        let matches_edits = device
+            .config()
+            .is_some_and(|config| compare::diff(config, &checked).is_empty());
+        if unchanged || matches_edits || self.device != Some(handle) {
+            device.set_synced_config(checked);
+            return;
+        }
+        self.resync.changed = Some((handle, checked));
+    }
+
+    /// Ask what to do about changes made outside of crustility
+    pub fn resync_window(&mut self, ctx: &egui::Context) {
+        let Some((handle, checked)) = &self.resync.changed else {
+            return;
+        };
+        let handle = *handle;
+        let Some(device) = self.devices.get(&handle) else {
+            self.resync.changed = None;
+            return;
+        };
+        let (Some(synced), Some(config)) = (device.synced_config(), device.config()) else {
+            self.resync.changed = None;
+            return;
+        };
+
+        let changes = compare::diff(synced, checked);
+        let edits = compare::diff(synced, config);
+        let mut open = true;
+        let mut action = None;
+        egui::Window::new(tr!("resync-title", device = device.name().as_str()))
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(420.)
+            .show(ctx, |ui| {
+                ui.label(tr!("resync-description"));
+                if !edits.is_empty() {
+                    ui.colored_label(
+                        ui.visuals().warn_fg_color,
+                        tr!("resync-edited", count = edits.len()),
+                    );
+                }
+
+                egui::ScrollArea::vertical()
+                    .max_height(320.)
+                    .show(ui, |ui| {
+                        egui::Grid::new("resync changes")
+                            .num_columns(5)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                ui.strong(tr!("resync-key"));
+                                ui.strong(tr!("resync-setting"));
+                                ui.strong(tr!("resync-known"));
+                                ui.strong(tr!("resync-device"));
+                                ui.strong(tr!("resync-yours"));
+                                ui.end_row();
+                                for change in &changes {
+                                    let edit = edits.iter().find(|edit| {
+                                        edit.key == change.key && edit.setting == change.setting
+                                    });
+                                    ui.label((change.key + 1).to_string());
+                                    ui.label(setting_name(change.setting));
+                                    ui.label(value_text(change.left.as_ref()));
+                                    ui.label(value_text(change.right.as_ref()));
+                                    match edit {
+                                        Some(edit) => ui.colored_label(
+                                            ui.visuals().warn_fg_color,
+                                            value_text(edit.right.as_ref()),
+                                        ),
+                                        None => ui.weak("—"),
+                                    };
+                                    ui.end_row();
+                                }
+                            });
+                    });
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui
+                        .button(tr!("resync-reload"))
+                        .on_hover_text(tr!("resync-reload-hint"))
+                        .clicked()
+                    {
+                        action = Some(Action::Reload);
+                    }
+                    if ui
+                        .add_enabled(!edits.is_empty(), egui::Button::new(tr!("resync-merge")))
+                        .on_hover_text(tr!("resync-merge-hint"))
+                        .clicked()
+                    {
+                        action = Some(Action::Merge);
+                    }
+                    if ui
+                        .button(tr!("resync-overwrite"))
+                        .on_hover_text(tr!("resync-overwrite-hint"))
+                        .clicked()
+                    {
+                        action = Some(Action::Overwrite);
+                    }
+                });
+            });
+
+        if action.is_none() && open {
+            return;
+        }
+        let Some((handle, checked)) = self.resync.changed.take() else {
+            return;
+        };
+        let Some(device) = self.devices.get_mut(&handle) else {
+            return;
+        };
+        let name = device.name().clone();
+        match action {
+            Some(Action::Reload) => {
+                device.set_config(checked);
+                self.notifications
+                    .info(tr!("resync-reloaded", device = name.as_str()));
+            }
+            Some(Action::Merge) => {
+                let (Some(synced), Some(config)) =
+                    (device.synced_config().cloned(), device.config_mut())
+                else {
+                    return;
+                };
+                let merge = compare::merge(&synced, &checked, config);
+                device.set_synced_config(checked);
+                self.notifications.info(tr!(
+                    "resync-merged",
+                    count = merge.copied,
+                    device = name.as_str()
+                ));
+                if !merge.conflicts.is_empty() {
+                    self.notifications
+                        .warning(tr!("resync-conflicts", count = merge.conflicts.len()));
+                }
+            }
+            Some(Action::Overwrite) => {
+                device.set_synced_config(checked);
+                self.request_apply();
+            }
+            // closed, don't ask again about the same changes
+            None => device.set_synced_config(checked),
+        }
+    }
+}
+
+enum Action {
+    Reload,
+    Merge,
+    Overwrite,
+}
@@ -0,0 +1,322 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::rpc::{self, Connection, Request, RpcError, Server};
+use crustility_core::{
+    config::Config,
+    device::{self, DeviceHandle, SensorData},
+    profile,
+};
+
+use super::{
+    dashboard::{self, PushResult},
+    i18n::tr,
+    Crustility,
+};
+
+/// how often the ui wakes up to answer requests while the window is idle
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// State of the control server
+#[derive(Default)]
+pub struct Rpc {
+    server: Option<Server>,
+    /// endpoint the server could not start on, don't retry every frame
+    failed: Option<rpc::Endpoint>,
+    /// requests waiting for a device to report its config
+    waiting: Vec<(DeviceHandle, Request)>,
+    /// connections streaming the sensor readings of a device
+    subscribers: Vec<(DeviceHandle, Connection)>,
+}
+
+impl Rpc {
+    pub fn is_subscribed(&self, handle: &DeviceHandle) -> bool {
+        self.subscribers.iter().any(|(h, _)| h == handle)
+    }
+
+    /// Forward a sensor reading to the connections subscribed to the device
+    pub fn publish_sensor(
+        &mut self,
+        handle: DeviceHandle,
+        data: &SensorData,
+        pressed: Option<bool>,
+    ) {
+        if !self.is_subscribed(&handle) {
+            return;
+        }
+        let params = json!({
+            "device": device_id(&handle),
+            "key": data.key,
+            "raw": data.raw,
+            "position": data.mapped.as_mm(),
+            "pressed": pressed,
+        });
+        self.subscribers
+            .retain(|(h, connection)| *h != handle || connection.notify("sensor", params.clone()));
+    }
+
+    /// Fail the requests waiting for a device and end its sensor streams
+    pub fn connection_lost(&mut self, handle: &DeviceHandle) {
+        let (lost, waiting) = std::mem::take(&mut self.waiting)
+            .into_iter()
+            .partition(|(h, _)| h == handle);
+        self.waiting = waiting;
+        for (_, request) in lost {
+            request.reply(Err(RpcError::new(
+                rpc::SERVER_ERROR,
+                "lost the connection to the device",
+            )));
+        }
+        self.subscribers.retain(|(h, connection)| {
+            if h == handle {
+                connection.notify("sensors.stopped", json!({ "device": device_id(h) }));
+            }
+            h != handle
+        });
+    }
+}
+
+/// Answer of a method
+enum Reply {
+    Value(Value),
+    /// ask again once the device reported its config
+    Wait(DeviceHandle),
+}
+
+#[derive(Deserialize)]
+struct DeviceParams {
+    /// id from `devices.list`, the selected device when missing
+    #[serde(default)]
+    device: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct SetConfigParams {
+    #[serde(default)]
+    device: Option<String>,
+    config: Config,
+}
+
+#[derive(Deserialize)]
+struct ApplyProfileParams {
+    #[serde(default)]
+    device: Option<String>,
+    profile: String,
+}
+
+/// Id of a device in requests, stays the same while it is connected
+fn device_id(handle: &DeviceHandle) -> String {
+    format!("{}#{}", handle.identity(), handle.instance())
+}
+
+fn server_error(message: impl Into<String>) -> RpcError {
+    RpcError::new(rpc::SERVER_ERROR, message)
+}
+
+impl Crustility {
+    /// Start or stop the server as the settings say and answer its requests
+    pub fn poll_rpc(&mut self, ctx: &egui::Context) {
+        if !self.settings.rpc_server {
+            self.rpc = rpc_stopped(std::mem::take(&mut self.rpc));
+            return;
+        }
+        let endpoint = self.settings.rpc_endpoint();
+        if self
+            .rpc
+            .server
+            .as_ref()
+            .is_some_and(|server| *server.endpoint() != endpoint)
+        {
+            self.rpc = rpc_stopped(std::mem::take(&mut self.rpc));
+        }
+        if self.rpc.server.is_none() && self.rpc.failed.as_ref() != Some(&endpoint) {
+            match Server::start(endpoint.clone()) {
+                Ok(server) => self.rpc.server = Some(server),
+                Err(e) => {
+                    self.rpc.failed = Some(endpoint.clone());
+                    self.notifications.error(
+                        tr!(
+                            "control-server-start-error",
+                            endpoint = endpoint.to_string()
+                        ),
+                        &e,
+                    );
+                }
+            }
+        }
+
+        while let Some(request) = self.rpc.server.as_ref().and_then(|s| s.try_recv()) {
+            self.handle_request(request);
+        }
+        ctx.request_repaint_after(POLL_INTERVAL);
+    }
+
+    /// Answer the requests that waited for the config of a device
+    pub fn resume_rpc(&mut self, handle: DeviceHandle) {
+        let (ready, waiting) = std::mem::take(&mut self.rpc.waiting)
+            .into_iter()
+            .partition::<Vec<_>, _>(|(h, _)| *h == handle);
+        self.rpc.waiting = waiting;
+        for (_, request) in ready {
+            self.handle_request(request);
+        }
+    }
+
+    fn handle_request(&mut self, request: Request) {
+        log::debug!("rpc request {}", request.method);
+        match self.call(&request) {
+            Ok(Reply::Value(value)) => request.reply(Ok(value)),
+            Ok(Reply::Wait(handle)) => self.rpc.waiting.push((handle, request)),
+            Err(e) => request.reply(Err(e)),
+        }
+    }
+
+    /// Methods of the control server
+    ///
+    /// - `devices.list` connected devices and their ids
+    /// - `config.get {device?}` config of a device
+    /// - `config.set {device?, config}` write the key settings of a config to a device
+    /// - `profiles.list` names of the stored profiles
+    /// - `profile.apply {device?, profile}` write a profile to a device
+    /// - `sensors.subscribe {device?}` stream `sensor` notifications of a device
+    /// - `sensors.unsubscribe {device?}` stop streaming, from every device when missing
+    fn call(&mut self, request: &Request) -> Result<Reply, RpcError> {
+        let value = match request.method.as_str() {
+            "devices.list" => self.rpc_devices(),
+            "config.get" => {
+                let params: DeviceParams = request.params()?;
+                let handle = self.rpc_device(params.device.as_deref())?;
+                let device = self
+                    .devices
+                    .get_mut(&handle)
+                    .ok_or_else(|| server_error("the device is disconnected"))?;
+                match device.config() {
+                    Some(config) => json!(config),
+                    None => {
+                        device
+                            .spawn_event_loop()
+                            .and_then(|_| device.send_event(device::SendEvent::ReadConfig))
+                            .map_err(|e| server_error(e.to_string()))?;
+                        return Ok(Reply::Wait(handle));
+                    }
+                }
+            }
+            "config.set" => {
+                let params: SetConfigParams = request.params()?;
+                let handle = self.rpc_device(params.device.as_deref())?;
+                return self.rpc_push(handle, &params.config);
+            }
+            "profiles.list" => json!(profile::list().map_err(|e| server_error(e.to_string()))?),
+            "profile.apply" => {
+                let params: ApplyProfileParams = request.params()?;
+                let handle = self.rpc_device(params.device.as_deref())?;
+                let config =
+                    profile::load(&params.profile).map_err(|e| server_error(e.to_string()))?;
+                let reply = self.rpc_push(handle, &config)?;
+                if matches!(reply, Reply::Value(_)) && self.device == Some(handle) {
+                    self.settings.last_profile = Some(params.profile);
+                }
+                return Ok(reply);
+            }
+            "sensors.subscribe" => {
+                let params: DeviceParams = request.params()?;
+                let handle = self.rpc_device(params.device.as_deref())?;
+                let device = self
+                    .devices
+                    .get_mut(&handle)
+                    .ok_or_else(|| server_error("the device is disconnected"))?;
+                device
+                    .spawn_event_loop()
+                    .and_then(|_| device.send_event(device::SendEvent::ReadSensorsBegin))
+                    .map_err(|e| server_error(e.to_string()))?;
+                let subscriber = (handle, request.connection.clone());
+                if !self.rpc.subscribers.contains(&subscriber) {
+                    self.rpc.subscribers.push(subscriber);
+                }
+                json!({ "device": device_id(&handle) })
+            }
+            "sensors.unsubscribe" => {
+                let params: DeviceParams = request.params()?;
+                let handle = match params.device {
+                    Some(id) => Some(self.rpc_device(Some(&id))?),
+                    None => None,
+                };
+                self.rpc.subscribers.retain(|(h, connection)| {
+                    *connection != request.connection || handle.is_some_and(|handle| handle != *h)
+                });
+                Value::Null
+            }
+            method => {
+                return Err(RpcError::new(
+                    rpc::METHOD_NOT_FOUND,
+                    format!("there is no method {method}"),
+                ))
+            }
+        };
+        Ok(Reply::Value(value))
+    }
+
+    fn rpc_devices(&self) -> Value {
+        let devices = self
+            .devices
+            .iter()
+            .filter_map(|handle| {
+                let device = self.devices.get(handle)?;
+                Some(json!({
+                    "id": device_id(handle),
+                    "name": dashboard::device_name(handle, device),
+                    "port": device.port_name(),
+                    "vid": handle.vid(),
+                    "pid": handle.pid(),
+                    "serial_number": device.serial_number(),
+                    "selected": self.device == Some(*handle),
+                    "connected": device.is_connected(),
+                    "hkeys": device.config().map(|config| config.hkeys.len()),
+                    "firmware": device.config().map(|config| &config.firmware),
+                }))
+            })
+            .collect::<Vec<_>>();
+        json!(devices)
+    }
+
+    /// Device a request is about
+    fn rpc_device(&self, id: Option<&str>) -> Result<DeviceHandle, RpcError> {
+        match id {
+            Some(id) => self
+                .devices
+                .iter()
+                .find(|handle| device_id(handle) == id)
+                .copied()
+                .ok_or_else(|| server_error(format!("there is no device {id}"))),
+            None => self
+                .device
+                .ok_or_else(|| server_error("no device is selected")),
+        }
+    }
+
+    fn rpc_push(&mut self, handle: DeviceHandle, config: &Config) -> Result<Reply, RpcError> {
+        let device = self
+            .devices
+            .get_mut(&handle)
+            .ok_or_else(|| server_error("the device is disconnected"))?;
+        self.backups.refresh();
+        match dashboard::push(&handle, device, config) {
+            PushResult::Pending => Ok(Reply::Wait(handle)),
+            PushResult::Applied { keys, device_keys } => Ok(Reply::Value(json!({
+                "keys": keys,
+                "device_keys": device_keys,
+            }))),
+            PushResult::Failed(reason) => Err(server_error(reason)),
+        }
+    }
+}
+
+/// Drop the server, answering what still waits on it
+fn rpc_stopped(mut state: Rpc) -> Rpc {
+    for (_, request) in state.waiting.drain(..) {
+        request.reply(Err(server_error("the control server was stopped")));
+    }
+    Rpc::default()
+}
@@ -0,0 +1,333 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app::{
+        accessibility::labeled,
+        i18n::{self, tr},
+        resync, theme, Crustility,
+    },
+    overlay, rpc,
+};
+use crustility_core::{device, storage};
+
+const SETTINGS_FILE: &str = "settings.toml";
+pub const MIN_UI_SCALE: f32 = 0.75;
+pub const MAX_UI_SCALE: f32 = 3.;
+const UI_SCALE_STEP: f32 = 0.25;
+
+/// Preferences kept between runs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// name of the selected theme
+    pub theme: String,
+    /// code of the ui language
+    pub language: String,
+    /// pixels per point of the whole ui
+    pub ui_scale: f32,
+    pub remember_window: bool,
+    pub window: Option<WindowGeometry>,
+    /// [`device::DeviceHandle::identity`] of the device selected last
+    pub last_device: Option<String>,
+    pub last_profile: Option<String>,
+    /// target sensor sample rate in Hz
+    pub polling_rate: u32,
+    pub confirm_apply: bool,
+    pub confirm_delete_profile: bool,
+    /// seconds between checks for changes made to the device elsewhere, off when `None`
+    pub resync_interval: Option<u32>,
+    /// check for changes when the window gets focus
+    pub resync_on_focus: bool,
+    /// run the json-rpc control server
+    pub rpc_server: bool,
+    /// localhost port for the control server, the platform default when `None`
+    pub rpc_port: Option<u16>,
+    /// serve the key overlay for streaming
+    pub overlay_server: bool,
+    pub overlay_port: u16,
+    /// overlay updates per second
+    pub overlay_rate: u32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            theme: theme::DEFAULT_THEME.to_string(),
+            language: i18n::DEFAULT_LANGUAGE.to_string(),
+            ui_scale: 1.5,
+            remember_window: true,
+            window: None,
+            last_device: None,
+            last_profile: None,
+            polling_rate: device::poll::DEFAULT_RATE,
+            confirm_apply: false,
+            confirm_delete_profile: true,
+            resync_interval: None,
+            resync_on_focus: true,
+            rpc_server: false,
+            rpc_port: None,
+            overlay_server: false,
+            overlay_port: overlay::DEFAULT_PORT,
+            overlay_rate: overlay::DEFAULT_RATE,
+        }
+    }
+}
+
+/// Outer position and inner size of the window in logical pixels
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub maximized: bool,
+}
+
+impl Settings {
+    pub fn load() -> Self {
+        let mut settings: Self = storage::load_or_default(SETTINGS_FILE);
+        settings.ui_scale = settings.ui_scale.clamp(MIN_UI_SCALE, MAX_UI_SCALE);
+        settings.polling_rate = settings
+            .polling_rate
+            .clamp(device::poll::MIN_RATE, device::poll::MAX_RATE);
+        settings.resync_interval = settings
+            .resync_interval
+            .map(|seconds| seconds.clamp(resync::MIN_INTERVAL, resync::MAX_INTERVAL));
+        settings.overlay_rate = settings
+            .overlay_rate
+            .clamp(overlay::MIN_RATE, overlay::MAX_RATE);
+        settings
+    }
+
+    /// Where the control server listens
+    pub fn rpc_endpoint(&self) -> rpc::Endpoint {
+        match self.rpc_port {
+            Some(port) => rpc::Endpoint::Tcp(port),
+            None => rpc::Endpoint::default_for_platform(),
+        }
+    }
+
+    pub fn save(&self) -> Result<(), storage::Error> {
+        storage::save(SETTINGS_FILE, self)
+    }
+
+    /// Apply the stored window geometry to the options the window is created with
+    pub fn window_options(&self, options: &mut eframe::NativeOptions) {
+        let Some(window) = self.window.filter(|_| self.remember_window) else {
+            return;
+        };
+        options.initial_window_pos = Some(egui::pos2(window.x, window.y));
+        options.initial_window_size = Some(egui::vec2(window.width, window.height));
+        options.maximized = window.maximized;
+    }
+}
+
+impl Crustility {
+    pub fn save_settings(&mut self) {
+        if let Err(e) = self.settings.save() {
+            self.notifications.error(tr!("settings-save-error"), &e);
+        }
+    }
+
+    /// Keep track of the window geometry so it can be restored on the next start
+    pub fn record_window(&mut self, window: &eframe::WindowInfo) {
+        if !self.settings.remember_window || window.minimized || window.fullscreen {
+            return;
+        }
+        let Some(position) = window.position else {
+            return;
+        };
+        let geometry = self.settings.window.get_or_insert(WindowGeometry {
+            x: position.x,
+            y: position.y,
+            width: window.size.x,
+            height: window.size.y,
+            maximized: window.maximized,
+        });
+        geometry.maximized = window.maximized;
+        // keep the size to go back to when the window gets unmaximized
+        if !window.maximized {
+            geometry.x = position.x;
+            geometry.y = position.y;
+            geometry.width = window.size.x;
+            geometry.height = window.size.y;
+        }
+    }
+
+    pub fn settings_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_settings;
+        let mut settings = self.settings.clone();
+
+        egui::Window::new(tr!("settings"))
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                egui::Grid::new("settings")
+                    .num_columns(2)
+                    .spacing([20., 8.])
+                    .show(ui, |ui| {
+                        ui.label(tr!("language"));
+                        egui::ComboBox::from_id_source("settings language")
+                            .selected_text(i18n::language_name(&settings.language))
+                            .show_ui(ui, |ui| {
+                                for language in &i18n::LANGUAGES {
+                                    ui.selectable_value(
+                                        &mut settings.language,
+                                        language.code.to_string(),
+                                        language.name,
+                                    );
+                                }
+                            });
+                        ui.end_row();
+
+                        // buttons instead of a drag value, the ui would move under the pointer
+                        ui.label(tr!("ui-scale"));
+                        ui.horizontal(|ui| {
+                            if labeled(ui.small_button("-"), &tr!("smaller")).clicked() {
+                                settings.ui_scale -= UI_SCALE_STEP;
+                            }
+                            ui.label(format!("{:.0}%", settings.ui_scale * 100.));
+                            if labeled(ui.small_button("+"), &tr!("larger")).clicked() {
+                                settings.ui_scale += UI_SCALE_STEP;
+                            }
+                        });
+                        settings.ui_scale = settings.ui_scale.clamp(MIN_UI_SCALE, MAX_UI_SCALE);
+                        ui.end_row();
+
+                        ui.label(tr!("theme"));
+                        egui::ComboBox::from_id_source("settings theme")
+                            .selected_text(&settings.theme)
+                            .show_ui(ui, |ui| {
+                                for theme in &self.themes {
+                                    ui.selectable_value(
+                                        &mut settings.theme,
+                                        theme.name.clone(),
+                                        &theme.name,
+                                    );
+                                }
+                            });
+                        ui.end_row();
+
+                        ui.label(tr!("polling-rate"));
+                        ui.add(
+                            egui::DragValue::new(&mut settings.polling_rate)
+                                .clamp_range(device::poll::MIN_RATE..=device::poll::MAX_RATE)
+                                .suffix(" Hz"),
+                        );
+                        ui.end_row();
+
+                        ui.label(tr!("window"));
+                        ui.checkbox(&mut settings.remember_window, tr!("remember-window"));
+                        ui.end_row();
+
+                        ui.label(tr!("device-changes"));
+                        ui.vertical(|ui| {
+                            ui.horizontal(|ui| {
+                                let mut periodic = settings.resync_interval.is_some();
+                                if ui.checkbox(&mut periodic, tr!("resync-every")).changed() {
+                                    settings.resync_interval =
+                                        periodic.then_some(resync::DEFAULT_INTERVAL);
+                                }
+                                if let Some(interval) = &mut settings.resync_interval {
+                                    ui.add(
+                                        egui::DragValue::new(interval)
+                                            .clamp_range(
+                                                resync::MIN_INTERVAL..=resync::MAX_INTERVAL,
+                                            )
+                                            .suffix(" s"),
+                                    );
+                                }
+                            });
+                            ui.checkbox(&mut settings.resync_on_focus, tr!("resync-on-focus"));
+                        });
+                        ui.end_row();
+
+                        ui.label(tr!("confirm-before"));
+                        ui.vertical(|ui| {
+                            ui.checkbox(&mut settings.confirm_apply, tr!("confirm-before-apply"));
+                            ui.checkbox(
+                                &mut settings.confirm_delete_profile,
+                                tr!("confirm-before-delete"),
+                            );
+                        });
+                        ui.end_row();
+
+                        ui.label(tr!("control-server"));
+                        ui.vertical(|ui| {
+                            ui.checkbox(&mut settings.rpc_server, tr!("control-server-enable"))
+                                .on_hover_text(tr!("control-server-hint"));
+                            ui.horizontal(|ui| {
+                                let mut tcp = settings.rpc_port.is_some();
+                                if ui.checkbox(&mut tcp, tr!("localhost-port")).changed() {
+                                    settings.rpc_port = tcp.then_some(rpc::DEFAULT_PORT);
+                                }
+                                if let Some(port) = &mut settings.rpc_port {
+                                    ui.add(egui::DragValue::new(port).clamp_range(1024..=u16::MAX));
+                                }
+                            });
+                            ui.weak(settings.rpc_endpoint().to_string());
+                            if settings.rpc_port.is_some() {
+                                if let Ok(path) = rpc::token_path() {
+                                    ui.weak(tr!(
+                                        "control-server-token",
+                                        path = path.display().to_string()
+                                    ));
+                                }
+                            }
+                        });
+                        ui.end_row();
+
+                        ui.label(tr!("key-overlay"));
+                        ui.vertical(|ui| {
+                            ui.checkbox(&mut settings.overlay_server, tr!("key-overlay-enable"));
+                            ui.horizontal(|ui| {
+                                ui.label(tr!("port"));
+                                ui.add(
+                                    egui::DragValue::new(&mut settings.overlay_port)
+                                        .clamp_range(1024..=u16::MAX),
+                                );
+                                ui.label(tr!("rate"));
+                                ui.add(
+                                    egui::DragValue::new(&mut settings.overlay_rate)
+                                        .clamp_range(overlay::MIN_RATE..=overlay::MAX_RATE)
+                                        .suffix(" Hz"),
+                                );
+                            });
+                            if let Some(viewers) = self.overlay.clients() {
+                                ui.horizontal(|ui| {
+                                    let url =
+                                        format!("http://localhost:{}/", settings.overlay_port);
+                                    ui.hyperlink(&url);
+                                    ui.weak(tr!("overlay-viewers", count = viewers));
+                                });
+                            }
+                        });
+                        ui.end_row();
+                    });
+
+                ui.separator();
+                if ui.button(tr!("reset-defaults")).clicked() {
+                    settings = Settings {
+                        window: settings.window,
+                        last_device: settings.last_device.clone(),
+                        last_profile: settings.last_profile.clone(),
+                        ..Settings::default()
+                    };
+                }
+            });
+        self.show_settings = open;
+
+        if settings == self.settings {
+            return;
+        }
+        let polling_rate_changed = settings.polling_rate != self.settings.polling_rate;
+        i18n::set_language(&settings.language);
+        let theme = settings.theme.clone();
+        self.settings = settings;
+        if polling_rate_changed {
+            self.set_polling_rate();
+        }
+        // saves the settings as well
+        self.set_theme(theme);
+    }
+}
@@ -0,0 +1,170 @@
+use crustility_core::{compare, config::Config, share, validate};
+
+use super::{
+    compare::{setting_name, value_text},
+    i18n::tr,
+    Crustility,
+};
+
+/// Share strings copied from and pasted into the menu bar
+#[derive(Default)]
+pub struct Share {
+    /// text pasted into the menu
+    pasted: String,
+    /// decoded config waiting to be applied
+    preview: Option<Config>,
+}
+
+impl Crustility {
+    pub fn share_menu(&mut self, ui: &mut egui::Ui) {
+        let mut preview = false;
+        ui.menu_button(tr!("menu-share"), |ui| {
+            let config = self
+                .device
+                .and_then(|handle| self.devices.get(&handle))
+                .and_then(|device| device.config());
+            if ui
+                .add_enabled(config.is_some(), egui::Button::new(tr!("share-copy")))
+                .on_hover_text(tr!("share-copy-hint"))
+                .clicked()
+            {
+                match config.map(share::encode) {
+                    Some(Ok(text)) => {
+                        ui.output_mut(|o| o.copied_text = text);
+                        self.notifications.success(tr!("share-copied"));
+                    }
+                    Some(Err(e)) => self.notifications.error(tr!("share-encode-error"), &e),
+                    None => (),
+                }
+                ui.close_menu();
+            }
+            ui.separator();
+            ui.horizontal(|ui| {
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.share.pasted)
+                        .hint_text(share::PREFIX)
+                        .desired_width(160.),
+                );
+                if ui
+                    .add_enabled(
+                        !self.share.pasted.trim().is_empty(),
+                        egui::Button::new(tr!("share-preview")),
+                    )
+                    .clicked()
+                {
+                    preview = true;
+                    ui.close_menu();
+                }
+            });
+        });
+
+        if preview {
+            match share::decode(&self.share.pasted) {
+                Ok(config) => {
+                    self.share.preview = Some(config);
+                    self.share.pasted.clear();
+                }
+                Err(e) => self.notifications.error(tr!("share-decode-error"), &e),
+            }
+        }
+    }
+
+    /// Differences between the shared config and the selected device, applied on request
+    pub fn share_window(&mut self, ctx: &egui::Context) {
+        let Some(shared) = &self.share.preview else {
+            return;
+        };
+        let Some(config) = self
+            .device
+            .and_then(|handle| self.devices.get(&handle))
+            .and_then(|device| device.config())
+        else {
+            return;
+        };
+
+        let mut open = true;
+        let mut apply = false;
+        let mut load = false;
+        let matches = shared.hkeys.len() == config.hkeys.len();
+        let report = validate::validate(shared);
+        egui::Window::new(tr!("share-title"))
+            .open(&mut open)
+            .default_width(420.)
+            .show(ctx, |ui| {
+                if !matches {
+                    ui.colored_label(
+                        ui.visuals().error_fg_color,
+                        tr!(
+                            "share-key-count",
+                            shared = shared.hkeys.len(),
+                            device = config.hkeys.len()
+                        ),
+                    );
+                }
+                if !report.is_valid() {
+                    ui.colored_label(
+                        ui.visuals().warn_fg_color,
+                        tr!("share-invalid", count = report.len()),
+                    );
+                }
+
+                let differences = compare::diff(config, shared);
+                if differences.is_empty() {
+                    ui.label(tr!("share-same"));
+                } else {
+                    egui::ScrollArea::vertical()
+                        .max_height(320.)
+                        .show(ui, |ui| {
+                            egui::Grid::new("shared differences")
+                                .num_columns(4)
+                                .striped(true)
+                                .show(ui, |ui| {
+                                    ui.strong(tr!("share-key"));
+                                    ui.strong(tr!("share-setting"));
+                                    ui.strong(tr!("share-device"));
+                                    ui.strong(tr!("share-shared"));
+                                    ui.end_row();
+                                    for difference in &differences {
+                                        ui.label((difference.key + 1).to_string());
+                                        ui.label(setting_name(difference.setting));
+                                        ui.label(value_text(difference.left.as_ref()));
+                                        ui.label(value_text(difference.right.as_ref()));
+                                        ui.end_row();
+                                    }
+                                });
+                        });
+                }
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    let valid = matches && report.is_valid();
+                    apply = ui
+                        .add_enabled(valid, egui::Button::new(tr!("share-apply")))
+                        .on_hover_text(tr!("share-apply-hint"))
+                        .clicked();
+                    load = ui
+                        .add_enabled(matches, egui::Button::new(tr!("share-load")))
+                        .on_hover_text(tr!("share-load-hint"))
+                        .clicked();
+                });
+            });
+
+        if apply || load {
+            if let Some(config) = self
+                .device
+                .and_then(|handle| self.devices.get_mut(&handle))
+                .and_then(|device| device.config_mut())
+            {
+                if let Some(shared) = self.share.preview.take() {
+                    let copied = config.apply_settings(&shared);
+                    self.notifications.info(tr!("share-loaded", count = copied));
+                }
+            }
+            if apply {
+                self.request_apply();
+            }
+        } else if !open {
+            self.share.preview = None;
+        }
+    }
+}
@@ -1,103 +1,300 @@
-pub fn horizon_dark() -> egui::Visuals {
-    let dark_pink = color(0xe95378);
-    let orange = color(0xe4a88a);
-    let text = color(0xd5d8da);
-    let warn = color(0xe4a88a);
-    let error = color(0xe95378);
-    let url = color(0xe95378);
-    let selection = color(0x353747);
-    let background = color(0x1c1e26);
-    let background_light = color(0x353747);
-    let background_dark = color(0x16161c);
-    let stroke = egui::Stroke::new(0., egui::Color32::TRANSPARENT);
-    let stroke_light = egui::Stroke::new(1., color(0x2f3138));
-    let stroke_orange = egui::Stroke::new(1., orange);
-    let _stroke_dark = egui::Stroke::new(1., background_dark);
-    let rounding = egui::Rounding::default();
-    let shadow = egui::epaint::Shadow {
-        extrusion: 5.,
-        color: background_dark,
+use std::path::PathBuf;
+
+use egui::Color32;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use thiserror::Error;
+
+use crustility_core::storage;
+
+/// Colors a theme is built from
+///
+/// User themes are toml files in the `themes` config directory with a `name` and these fields,
+/// colors are written as `"#rrggbb"`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Palette {
+    pub dark_mode: bool,
+    /// active widgets, like the pressed part of a key
+    pub accent: Hex,
+    /// text of interactive widgets
+    pub highlight: Hex,
+    pub text: Hex,
+    pub warn: Hex,
+    pub error: Hex,
+    pub link: Hex,
+    pub selection: Hex,
+    pub background: Hex,
+    pub background_light: Hex,
+    pub background_dark: Hex,
+    pub border: Hex,
+    #[serde(default = "default_stroke_width")]
+    pub stroke_width: f32,
+}
+
+fn default_stroke_width() -> f32 {
+    1.
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Theme {
+    pub name: String,
+    #[serde(flatten)]
+    pub palette: Palette,
+}
+
+/// Color written as `"#rrggbb"` in theme files
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hex(pub Color32);
+
+impl Serialize for Hex {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let [r, g, b, _] = self.0.to_array();
+        serializer.serialize_str(&format!("#{r:02x}{g:02x}{b:02x}"))
+    }
+}
+
+impl<'de> Deserialize<'de> for Hex {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = String::deserialize(deserializer)?;
+        let digits = raw.strip_prefix('#').unwrap_or(&raw);
+        match u32::from_str_radix(digits, 16) {
+            Ok(c) if digits.len() == 6 => Ok(Self(color(c))),
+            _ => Err(serde::de::Error::custom(format!(
+                "expected a color like \"#1c1e26\", got \"{raw}\""
+            ))),
+        }
+    }
+}
+
+pub const DEFAULT_THEME: &str = "Horizon Dark";
+
+/// Themes shipped with crustility
+pub fn built_in() -> Vec<Theme> {
+    vec![
+        Theme {
+            name: DEFAULT_THEME.to_string(),
+            palette: horizon_dark(),
+        },
+        Theme {
+            name: "Horizon Light".to_string(),
+            palette: horizon_light(),
+        },
+        Theme {
+            name: "High Contrast".to_string(),
+            palette: high_contrast(),
+        },
+    ]
+}
+
+/// Directory user themes are loaded from
+pub fn themes_dir() -> Result<PathBuf, Error> {
+    Ok(storage::config_dir()?.join("themes"))
+}
+
+/// Built in themes followed by the user themes, along with the files that could not be loaded
+pub fn load_all() -> (Vec<Theme>, Vec<(PathBuf, Error)>) {
+    let mut themes = built_in();
+    let mut errors = Vec::new();
+
+    // without a config directory there can't be any user themes either
+    let Ok(dir) = themes_dir() else {
+        return (themes, errors);
+    };
+    let entries = match std::fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return (themes, errors),
+        Err(e) => {
+            errors.push((dir, e.into()));
+            return (themes, errors);
+        }
     };
 
-    egui::Visuals {
-        dark_mode: true,
-        override_text_color: Some(text),
-        widgets: egui::style::Widgets {
-            noninteractive: egui::style::WidgetVisuals {
-                bg_fill: background_dark,
-                weak_bg_fill: background_light,
-                bg_stroke: stroke_light,
-                rounding,
-                fg_stroke: stroke,
-                expansion: 1.,
+    let mut paths = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+        .collect::<Vec<_>>();
+    paths.sort();
+    for path in paths {
+        let theme = std::fs::read_to_string(&path)
+            .map_err(Error::from)
+            .and_then(|raw| Ok(toml::from_str::<Theme>(&raw)?));
+        match theme {
+            // user themes replace built in ones with the same name
+            Ok(theme) => match themes.iter_mut().find(|t| t.name == theme.name) {
+                Some(existing) => *existing = theme,
+                None => themes.push(theme),
             },
-            inactive: egui::style::WidgetVisuals {
-                bg_fill: background_dark,
-                weak_bg_fill: background_light,
-                bg_stroke: stroke,
-                rounding,
-                fg_stroke: stroke_orange,
-                expansion: 1.,
-            },
-            hovered: egui::style::WidgetVisuals {
-                bg_fill: background_light,
-                weak_bg_fill: background_dark,
-                bg_stroke: stroke_light,
-                rounding,
-                fg_stroke: stroke_orange,
-                expansion: 1.,
-            },
-            active: egui::style::WidgetVisuals {
-                bg_fill: background,
-                weak_bg_fill: dark_pink,
-                bg_stroke: stroke,
-                rounding,
-                fg_stroke: stroke_orange,
-                expansion: 1.,
+            Err(e) => errors.push((path, e)),
+        }
+    }
+    (themes, errors)
+}
+
+pub fn horizon_dark() -> Palette {
+    Palette {
+        dark_mode: true,
+        accent: Hex(color(0xe95378)),
+        highlight: Hex(color(0xe4a88a)),
+        text: Hex(color(0xd5d8da)),
+        warn: Hex(color(0xe4a88a)),
+        error: Hex(color(0xe95378)),
+        link: Hex(color(0xe95378)),
+        selection: Hex(color(0x353747)),
+        background: Hex(color(0x1c1e26)),
+        background_light: Hex(color(0x353747)),
+        background_dark: Hex(color(0x16161c)),
+        border: Hex(color(0x2f3138)),
+        stroke_width: 1.,
+    }
+}
+
+pub fn horizon_light() -> Palette {
+    Palette {
+        dark_mode: false,
+        accent: Hex(color(0xf7939b)),
+        highlight: Hex(color(0xda103f)),
+        text: Hex(color(0x1c1e26)),
+        warn: Hex(color(0xdc3318)),
+        error: Hex(color(0xe95378)),
+        link: Hex(color(0x1d8991)),
+        selection: Hex(color(0xf9cbbe)),
+        background: Hex(color(0xfdf0ed)),
+        background_light: Hex(color(0xf9cec3)),
+        background_dark: Hex(color(0xfadad1)),
+        border: Hex(color(0xf0c4b8)),
+        stroke_width: 1.,
+    }
+}
+
+pub fn high_contrast() -> Palette {
+    Palette {
+        dark_mode: true,
+        accent: Hex(color(0xffd700)),
+        highlight: Hex(color(0xffffff)),
+        text: Hex(color(0xffffff)),
+        warn: Hex(color(0xffd700)),
+        error: Hex(color(0xff4040)),
+        link: Hex(color(0x00e5ff)),
+        selection: Hex(color(0x0050a0)),
+        background: Hex(color(0x000000)),
+        background_light: Hex(color(0x404040)),
+        background_dark: Hex(color(0x000000)),
+        border: Hex(color(0xffffff)),
+        stroke_width: 2.,
+    }
+}
+
+impl Palette {
+    pub fn visuals(&self) -> egui::Visuals {
+        let Hex(accent) = self.accent;
+        let Hex(highlight) = self.highlight;
+        let Hex(text) = self.text;
+        let Hex(warn) = self.warn;
+        let Hex(error) = self.error;
+        let Hex(url) = self.link;
+        let Hex(selection) = self.selection;
+        let Hex(background) = self.background;
+        let Hex(background_light) = self.background_light;
+        let Hex(background_dark) = self.background_dark;
+        let Hex(border) = self.border;
+        let stroke = egui::Stroke::new(0., egui::Color32::TRANSPARENT);
+        let stroke_light = egui::Stroke::new(self.stroke_width, border);
+        let stroke_orange = egui::Stroke::new(self.stroke_width, highlight);
+        let rounding = egui::Rounding::default();
+        let shadow = egui::epaint::Shadow {
+            extrusion: 5.,
+            color: background_dark,
+        };
+
+        egui::Visuals {
+            dark_mode: self.dark_mode,
+            override_text_color: Some(text),
+            widgets: egui::style::Widgets {
+                noninteractive: egui::style::WidgetVisuals {
+                    bg_fill: background_dark,
+                    weak_bg_fill: background_light,
+                    bg_stroke: stroke_light,
+                    rounding,
+                    fg_stroke: stroke,
+                    expansion: 1.,
+                },
+                inactive: egui::style::WidgetVisuals {
+                    bg_fill: background_dark,
+                    weak_bg_fill: background_light,
+                    bg_stroke: stroke,
+                    rounding,
+                    fg_stroke: stroke_orange,
+                    expansion: 1.,
+                },
+                hovered: egui::style::WidgetVisuals {
+                    bg_fill: background_light,
+                    weak_bg_fill: background_dark,
+                    bg_stroke: stroke_light,
+                    rounding,
+                    fg_stroke: stroke_orange,
+                    expansion: 1.,
+                },
+                active: egui::style::WidgetVisuals {
+                    bg_fill: background,
+                    weak_bg_fill: accent,
+                    bg_stroke: stroke,
+                    rounding,
+                    fg_stroke: stroke_orange,
+                    expansion: 1.,
+                },
+                open: egui::style::WidgetVisuals {
+                    bg_fill: background,
+                    weak_bg_fill: background_dark,
+                    bg_stroke: stroke,
+                    rounding,
+                    fg_stroke: stroke,
+                    expansion: 1.,
+                },
             },
-            open: egui::style::WidgetVisuals {
-                bg_fill: background,
-                weak_bg_fill: background_dark,
-                bg_stroke: stroke,
-                rounding,
-                fg_stroke: stroke,
-                expansion: 1.,
+            selection: egui::style::Selection {
+                bg_fill: selection,
+                stroke,
             },
-        },
-        selection: egui::style::Selection {
-            bg_fill: selection,
-            stroke,
-        },
-        hyperlink_color: url,
-        faint_bg_color: background_dark,
-        extreme_bg_color: background_dark,
-        code_bg_color: background,
-        warn_fg_color: warn,
-        error_fg_color: error,
-        window_rounding: rounding,
-        window_shadow: shadow,
-        window_fill: background,
-        window_stroke: stroke,
-        menu_rounding: rounding,
-        panel_fill: background,
-        popup_shadow: shadow,
-        resize_corner_size: 3.,
-        text_cursor: stroke,
-        text_cursor_preview: false,
-        clip_rect_margin: 5.,
-        button_frame: true,
-        collapsing_header_frame: false,
-        indent_has_left_vline: true,
-        striped: true,
-        slider_trailing_fill: false,
-        interact_cursor: None,
+            hyperlink_color: url,
+            faint_bg_color: background_dark,
+            extreme_bg_color: background_dark,
+            code_bg_color: background,
+            warn_fg_color: warn,
+            error_fg_color: error,
+            window_rounding: rounding,
+            window_shadow: shadow,
+            window_fill: background,
+            window_stroke: stroke,
+            menu_rounding: rounding,
+            panel_fill: background,
+            popup_shadow: shadow,
+            resize_corner_size: 3.,
+            text_cursor: stroke,
+            text_cursor_preview: false,
+            clip_rect_margin: 5.,
+            button_frame: true,
+            collapsing_header_frame: false,
+            indent_has_left_vline: true,
+            striped: true,
+            slider_trailing_fill: false,
+            interact_cursor: None,
+        }
     }
 }
 
-fn color(c: u32) -> egui::Color32 {
-    egui::Color32::from_rgb(
+fn color(c: u32) -> Color32 {
+    Color32::from_rgb(
         ((c & 0xFF0000) >> 16) as u8,
         ((c & 0x00FF00) >> 8) as u8,
         (c & 0x0000FF) as u8,
     )
 }
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Storage(#[from] storage::Error),
+    #[error("theme file io")]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Deserialize(#[from] toml::de::Error),
+}
@@ -0,0 +1,315 @@
+use std::process::Command;
+use std::sync::mpsc::{Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use thiserror::Error;
+
+use crate::rules::Rules;
+
+/// how often the focused application is checked
+pub const POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// errors in a row before the watcher gives up
+const MAX_FAILURES: u32 = 5;
+/// longest wait between polls after errors
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
+
+/// Application that has the keyboard focus
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Focus {
+    /// executable name, or the window class when the process is unknown
+    pub process: String,
+    pub title: String,
+    pub pid: Option<u32>,
+}
+
+/// Source of the focused application, one per windowing system
+pub trait FocusDetector: Send {
+    fn name(&self) -> &'static str;
+    /// `None` when no window is focused
+    fn focused(&mut self) -> Result<Option<Focus>, Error>;
+}
+
+/// Detector for the session crustility is running in
+pub fn detect() -> Option<Box<dyn FocusDetector>> {
+    let env = |name| std::env::var_os(name).is_some();
+    if env("HYPRLAND_INSTANCE_SIGNATURE") {
+        Some(Box::new(Hyprland))
+    } else if env("SWAYSOCK") {
+        Some(Box::new(Sway))
+    } else if env("DISPLAY") {
+        // also works for xwayland windows on other compositors
+        Some(Box::new(X11))
+    } else {
+        None
+    }
+}
+
+fn run(program: &str, args: &[&str]) -> Result<String, Error> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| Error::Command(program.to_string(), e))?;
+    if !output.status.success() {
+        return Err(Error::Failed(
+            program.to_string(),
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Name of the executable of a process
+fn process_name(pid: u32) -> Option<String> {
+    std::fs::read_to_string(format!("/proc/{pid}/comm"))
+        .ok()
+        .map(|comm| comm.trim().to_string())
+}
+
+fn focus(class: &str, title: &str, pid: Option<u32>) -> Focus {
+    Focus {
+        process: pid
+            .and_then(process_name)
+            .unwrap_or_else(|| class.to_string()),
+        title: title.to_string(),
+        pid,
+    }
+}
+
+/// Active window through `xprop`
+pub struct X11;
+
+impl FocusDetector for X11 {
+    fn name(&self) -> &'static str {
+        "X11"
+    }
+
+    fn focused(&mut self) -> Result<Option<Focus>, Error> {
+        // _NET_ACTIVE_WINDOW(WINDOW): window id # 0x3a00007
+        let root = run("xprop", &["-root", "_NET_ACTIVE_WINDOW"])?;
+        let Some(id) = root.split_whitespace().last() else {
+            return Ok(None);
+        };
+        if id == "0x0" {
+            return Ok(None);
+        }
+
+        let window = run(
+            "xprop",
+            &["-id", id, "WM_CLASS", "_NET_WM_NAME", "_NET_WM_PID"],
+        )?;
+        let mut class = "";
+        let mut title = "";
+        let mut pid = None;
+        for line in window.lines() {
+            let Some((property, value)) = line.split_once(" = ") else {
+                continue;
+            };
+            if property.starts_with("WM_CLASS") {
+                // instance and class, the class is the more readable one
+                class = value.rsplit('"').nth(1).unwrap_or_default();
+            } else if property.starts_with("_NET_WM_NAME") {
+                title = value.trim_matches('"');
+            } else if property.starts_with("_NET_WM_PID") {
+                pid = value.trim().parse().ok();
+            }
+        }
+        Ok(Some(focus(class, title, pid)))
+    }
+}
+
+/// Active window through `hyprctl`
+pub struct Hyprland;
+
+impl FocusDetector for Hyprland {
+    fn name(&self) -> &'static str {
+        "Hyprland"
+    }
+
+    fn focused(&mut self) -> Result<Option<Focus>, Error> {
+        let window: serde_json::Value =
+            serde_json::from_str(&run("hyprctl", &["activewindow", "-j"])?)?;
+        let Some(class) = window.get("class").and_then(|v| v.as_str()) else {
+            return Ok(None);
+        };
+        let title = window["title"].as_str().unwrap_or_default();
+        let pid = window["pid"].as_u64().map(|pid| pid as u32);
+        Ok(Some(focus(class, title, pid)))
+    }
+}
+
+/// Focused node of the `swaymsg` tree
+pub struct Sway;
+
+impl FocusDetector for Sway {
+    fn name(&self) -> &'static str {
+        "Sway"
+    }
+
+    fn focused(&mut self) -> Result<Option<Focus>, Error> {
+        fn find(node: &serde_json::Value) -> Option<&serde_json::Value> {
+            if node["focused"].as_bool() == Some(true) {
+                return Some(node);
+            }
+            ["nodes", "floating_nodes"]
+                .iter()
+                .filter_map(|children| node[children].as_array())
+                .flatten()
+                .find_map(find)
+        }
+
+        let tree: serde_json::Value = serde_json::from_str(&run("swaymsg", &["-t", "get_tree"])?)?;
+        let Some(node) = find(&tree).filter(|node| node["pid"].is_u64()) else {
+            return Ok(None);
+        };
+        let class = node["app_id"]
+            .as_str()
+            .or_else(|| node["window_properties"]["class"].as_str())
+            .unwrap_or_default();
+        let title = node["name"].as_str().unwrap_or_default();
+        let pid = node["pid"].as_u64().map(|pid| pid as u32);
+        Ok(Some(focus(class, title, pid)))
+    }
+}
+
+pub enum FocusEvent {
+    /// the focus changed, with the profile the rules pick for the new focus
+    Changed {
+        focus: Option<Focus>,
+        profile: Option<String>,
+    },
+    Error(Error),
+}
+
+/// Polls a detector and picks profiles on a background thread, reporting focus changes
+///
+/// The ui is woken up for every event, so switches happen while it is minimized too.
+/// The thread stops once the watcher is dropped
+pub struct FocusWatcher {
+    detector_name: &'static str,
+    receiver: Receiver<FocusEvent>,
+    rules: Arc<Mutex<Rules>>,
+}
+
+impl FocusWatcher {
+    pub fn spawn(mut detector: Box<dyn FocusDetector>, rules: Rules, ctx: egui::Context) -> Self {
+        let detector_name = detector.name();
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let rules = Arc::new(Mutex::new(rules));
+        let thread_rules = rules.clone();
+        std::thread::spawn(move || Self::watch(detector.as_mut(), &thread_rules, &sender, &ctx));
+        Self {
+            detector_name,
+            receiver,
+            rules,
+        }
+    }
+
+    fn watch(
+        detector: &mut dyn FocusDetector,
+        rules: &Mutex<Rules>,
+        sender: &Sender<FocusEvent>,
+        ctx: &egui::Context,
+    ) {
+        let own_pid = std::process::id();
+        let mut last = None;
+        let mut failures = 0;
+        loop {
+            let focused = detector.focused();
+            failures = if focused.is_ok() { 0 } else { failures + 1 };
+            let event = match focused {
+                // switching to crustility itself should not change anything
+                Ok(Some(focus)) if focus.pid == Some(own_pid) => None,
+                Ok(focus) if focus != last => {
+                    last = focus.clone();
+                    let profile = lock(rules).profile_for(focus.as_ref()).map(str::to_string);
+                    Some(FocusEvent::Changed { focus, profile })
+                }
+                Ok(_) => None,
+                Err(e) if gives_up(&e, failures) => Some(FocusEvent::Error(e)),
+                // windows closing between two queries make them fail now and then
+                Err(e) => {
+                    log::warn!("could not detect the focused application: {e}");
+                    None
+                }
+            };
+            let stop = matches!(event, Some(FocusEvent::Error(_)));
+            if let Some(event) = event {
+                if sender.send(event).is_err() {
+                    return;
+                }
+                ctx.request_repaint();
+            }
+            if stop {
+                return;
+            }
+            std::thread::sleep(retry_delay(failures));
+        }
+    }
+
+    pub fn detector_name(&self) -> &'static str {
+        self.detector_name
+    }
+
+    /// Pick profiles with new rules from the next focus change on
+    pub fn set_rules(&self, rules: Rules) {
+        *lock(&self.rules) = rules;
+    }
+
+    pub fn try_recv(&self) -> Option<FocusEvent> {
+        self.receiver.try_recv().ok()
+    }
+}
+
+/// If the watcher should stop after `failures` errors in a row, the last one being `error`
+fn gives_up(error: &Error, failures: u32) -> bool {
+    let missing = matches!(error, Error::Command(_, e) if e.kind() == std::io::ErrorKind::NotFound);
+    missing || failures >= MAX_FAILURES
+}
+
+/// Wait before the next poll, doubling with every error in a row
+fn retry_delay(failures: u32) -> Duration {
+    (POLL_INTERVAL * 2u32.saturating_pow(failures.min(16))).min(MAX_RETRY_DELAY)
+}
+
+fn lock(rules: &Mutex<Rules>) -> std::sync::MutexGuard<'_, Rules> {
+    // the rules are replaced as a whole, a panic can't leave them half written
+    rules.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("could not run {0}")]
+    Command(String, #[source] std::io::Error),
+    #[error("{0} failed: {1}")]
+    Failed(String, String),
+    #[error("unexpected output")]
+    Json(#[from] serde_json::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retries_with_backoff() {
+        assert_eq!(retry_delay(0), POLL_INTERVAL);
+        assert_eq!(retry_delay(1), POLL_INTERVAL * 2);
+        assert_eq!(retry_delay(3), POLL_INTERVAL * 8);
+        assert_eq!(retry_delay(u32::MAX), MAX_RETRY_DELAY);
+    }
+
+    #[test]
+    fn gives_up_after_repeated_failures() {
+        let failed = || Error::Failed("xprop".to_string(), "BadWindow".to_string());
+        assert!(!gives_up(&failed(), 1));
+        assert!(!gives_up(&failed(), MAX_FAILURES - 1));
+        assert!(gives_up(&failed(), MAX_FAILURES));
+
+        let missing = Error::Command(
+            "xprop".to_string(),
+            std::io::Error::from(std::io::ErrorKind::NotFound),
+        );
+        assert!(gives_up(&missing, 1));
+    }
+}
@@ -0,0 +1,468 @@
+# Tastenoptionen
+select-key = Taste auswählen
+key-heading = Taste { $key }
+rapid-trigger = Rapid Trigger
+enable = Aktivieren
+continuous = Durchgehend
+up-sensitivity = mm Empfindlichkeit nach oben
+down-sensitivity = mm Empfindlichkeit nach unten
+hysteresis = Hysterese
+hysteresis-upper = mm oben
+hysteresis-lower = mm unten
+hid = HID
+
+# Menüleiste
+apply-config = Konfiguration anwenden
+apply-config-hint = Die Konfiguration auf das Gerät schreiben
+invalid-settings = { $count ->
+    [one] { $count } ungültige Einstellung, vor dem Anwenden korrigieren
+   *[other] { $count } ungültige Einstellungen, vor dem Anwenden korrigieren
+}
+menu-devices = Geräte
+menu-compare = Vergleichen
+menu-import-export = Import / Export
+menu-auto-profiles = Automatische Profile
+menu-settings = Einstellungen
+menu-share = Teilen
+menu-presets = Vorlagen
+menu-log = Protokoll
+menu-log-unread = Protokoll ({ $count })
+polling-throttled = das Gerät kommt nicht hinterher, die Abfragerate wurde gedrosselt
+polling-rate-error = Die Abfragerate konnte nicht geändert werden
+
+# Bestätigung
+confirm = Bestätigen
+confirm-apply = Die Konfiguration auf das Gerät schreiben?
+confirm-delete-profile = Das Profil { $name } löschen?
+yes = Ja
+no = Nein
+
+# Farbschema
+menu-theme = Farbschema
+reload-themes = Farbschemata neu laden
+reload-themes-hint = lädt die toml-Paletten in { $dir }
+
+# serieller Anschluss
+menu-port = Anschluss
+baud-rate = Baudrate
+flow-control = Flusskontrolle
+parity = Parität
+data-bits = Datenbits
+stop-bits = Stoppbits
+flow-control-none = Keine
+flow-control-software = Software
+flow-control-hardware = Hardware
+parity-none = Keine
+parity-odd = Ungerade
+parity-even = Gerade
+timeout = Zeitlimit
+reset-defaults = Auf Standardwerte zurücksetzen
+serial-settings-error = Die seriellen Einstellungen konnten nicht geändert werden
+
+# Geräte
+all-devices = Alle Geräte
+select-device = <Gerät auswählen>
+disconnected = <Getrennt>
+
+# Visualisierung
+actuation = Auslösung
+release = Freigabe
+threshold-keyboard-hint = { $name } { $value }, mit den Pfeiltasten verschieben
+key-pressed = gedrückt
+key-released = losgelassen
+key-description = Taste { $key }, { $state }, Auslösung { $actuation }, Freigabe { $release }
+threshold-description = { $name } in mm
+
+# Layout
+menu-layout = Layout
+file = Datei
+load = Laden
+save = Speichern
+use-default-layout = Standardlayout verwenden
+reset-view = Ansicht zurücksetzen
+layout-store-error = Das Layout konnte nicht gespeichert werden
+layout-load-error = Das Layout konnte nicht geladen werden
+layout-save-error = Das Layout konnte nicht gespeichert werden
+layout-saved = Layout in { $path } gespeichert
+
+# Profile
+profile = Profil
+profile-named = Profil: { $name }
+no-profiles = Keine gespeicherten Profile
+delete = löschen
+delete-profile = Das Profil { $name } löschen
+profile-name = Name
+profile-load-error = Das Profil { $name } konnte nicht geladen werden
+profile-key-count = Das Profil { $name } hat { $profile } Tasten, das Gerät { $device }
+profile-loaded = Das Profil { $name } wurde in { $count } Tasten geladen
+profile-saved = Das Profil { $name } wurde gespeichert
+profile-save-error = Das Profil { $name } konnte nicht gespeichert werden
+profile-delete-error = Das Profil { $name } konnte nicht gelöscht werden
+
+# Einstellungen
+settings = Einstellungen
+settings-save-error = Die Einstellungen konnten nicht gespeichert werden
+language = Sprache
+ui-scale = Skalierung
+smaller = Kleiner
+larger = Größer
+theme = Farbschema
+polling-rate = Abfragerate
+window = Fenster
+remember-window = Größe und Position merken
+confirm-before = Nachfragen
+confirm-before-apply = Vor dem Anwenden einer Konfiguration
+confirm-before-delete = Vor dem Löschen eines Profils
+control-server = Steuerserver
+control-server-enable = Anderen Programmen die Steuerung erlauben
+control-server-hint = json-rpc, eine Nachricht pro Zeile
+control-server-token = Clients müssen zuerst auth mit dem Token aus { $path } aufrufen
+localhost-port = Localhost-Port
+key-overlay = Tasten-Overlay
+key-overlay-enable = Ein Overlay für Streams bereitstellen
+port = Port
+rate = Rate
+overlay-viewers = { $count } verbunden
+device-changes = Änderungen am Gerät
+resync-every = Prüfen alle
+resync-on-focus = Wenn das Fenster den Fokus bekommt
+
+# Änderungen außerhalb von crustility
+resync-title = { $device } wurde geändert
+resync-description = Die Einstellungen auf dem Gerät sind nicht die, die crustility zuletzt gelesen oder geschrieben hat, ein anderes Programm oder ein Firmware-Befehl hat sie geändert.
+resync-edited = { $count ->
+    [one] Du hast { $count } Änderung, die nicht angewendet wurde.
+   *[other] Du hast { $count } Änderungen, die nicht angewendet wurden.
+}
+resync-key = Taste
+resync-setting = Einstellung
+resync-known = Bekannt
+resync-device = Gerät
+resync-yours = Deine
+resync-reload = Neu laden
+resync-reload-hint = die Einstellungen des Geräts verwenden, deine Änderungen gehen verloren
+resync-merge = Zusammenführen
+resync-merge-hint = die Änderungen des Geräts übernehmen, deine Änderungen behalten, wo beide geändert wurden
+resync-overwrite = Überschreiben
+resync-overwrite-hint = deine Konfiguration erneut auf das Gerät schreiben
+resync-reloaded = Die Konfiguration von { $device } wurde neu geladen
+resync-merged = { $count ->
+    [one] { $count } Einstellung von { $device } übernommen
+   *[other] { $count } Einstellungen von { $device } übernommen
+}
+resync-conflicts = { $count ->
+    [one] { $count } Einstellung wurde auf beiden Seiten geändert, deine Änderung wurde behalten
+   *[other] { $count } Einstellungen wurden auf beiden Seiten geändert, deine Änderungen wurden behalten
+}
+resync-error = Das Gerät konnte nicht auf Änderungen geprüft werden
+
+# Sicherungen und Zurücksetzen
+menu-backups = Sicherungen
+backups-browse = Sicherung wiederherstellen…
+factory-reset = Auf Standard zurücksetzen…
+factory-reset-hint = die Standardwerte der Firmware wiederherstellen, vorher wird eine Sicherung erstellt
+confirm-reset = Das Gerät auf die Standardwerte der Firmware zurücksetzen? Eine Sicherung der Einstellungen wird behalten.
+factory-reset-done = { $device } wurde auf die Standardwerte zurückgesetzt
+factory-reset-error = Das Gerät konnte nicht zurückgesetzt werden
+backup-error = Die Konfiguration konnte nicht gesichert werden, es wurde nichts geändert
+backups = Sicherungen von { $device }
+no-backups = Noch keine Sicherungen, vor jedem Anwenden und Zurücksetzen wird eine erstellt
+backup-taken = Erstellt
+backup-reason = Vor
+backup-keys = Tasten
+backup-reason-apply = Anwenden
+backup-reason-reset = Zurücksetzen
+backup-load = Laden
+backup-load-hint = die Einstellungen laden, um sie vor dem Anwenden zu bearbeiten
+backup-restore = Wiederherstellen
+backup-restore-hint = die Einstellungen laden und auf das Gerät schreiben
+delete-backup = Die Sicherung vom { $time } löschen
+backup-loaded = Die Sicherung vom { $time } wurde in { $count } Tasten geladen
+backup-key-count = Die Sicherung hat { $backup } Tasten, das Gerät { $device }
+backup-delete-error = Die Sicherung konnte nicht gelöscht werden
+backups-list-error = Die Sicherungen konnten nicht gelesen werden
+
+# Konfigurationen anwenden und lesen
+apply-invalid = Die Konfiguration hat ungültige Einstellungen und wurde nicht angewendet
+apply-error = Die Konfiguration konnte nicht angewendet werden
+config-applied = { $count ->
+    [one] Konfiguration auf { $count } Taste angewendet
+   *[other] Konfiguration auf { $count } Tasten angewendet
+}
+config-read = { $count ->
+    [one] Konfiguration von { $count } Taste aus { $device } gelesen
+   *[other] Konfiguration von { $count } Tasten aus { $device } gelesen
+}
+config-read-error = Die Konfiguration konnte nicht gelesen werden
+device-read-error = Die Konfiguration von { $device } konnte nicht gelesen werden
+device-connect-error = Verbindung zu { $device } fehlgeschlagen
+device-connection-lost = Verbindung zu { $device } verloren
+device-throttled = { $device } schafft keine { $rate } Hz, die Abfragerate wurde gedrosselt
+sensors-start-error = Das Lesen der Sensoren konnte nicht gestartet werden
+sensors-stop-error = Das Lesen der Sensoren konnte nicht beendet werden
+profile-save-first = Speichere zuerst ein Profil über das Profilmenü
+theme-load-error = Das Farbschema { $path } konnte nicht geladen werden
+theme-missing = Das Farbschema { $name } gibt es nicht mehr, { $default } wird verwendet
+overlay-start-error = Der Overlay-Server konnte auf Port { $port } nicht gestartet werden
+control-server-start-error = Der Steuerserver konnte auf { $endpoint } nicht gestartet werden
+
+# Namen der Einstellungen
+setting-key = Taste
+setting-rapid-trigger = Rapid Trigger
+setting-continuous = Kontinuierlich
+setting-up-sensitivity = Empfindlichkeit hoch
+setting-down-sensitivity = Empfindlichkeit runter
+setting-hysteresis-lower = Untere Hysterese
+setting-hysteresis-upper = Obere Hysterese
+setting-key-code = Tastencode
+setting-hid = HID
+value-on = an
+value-off = aus
+value-present = vorhanden
+
+# invalid settings
+problem-out-of-range = muss zwischen { $min } und { $max } liegen
+problem-too-close = muss mindestens { $tolerance } über der unteren Hysterese liegen
+
+# Tastenbelegung
+keybind-bind = Belegen
+keybind-capturing = Drücke eine Taste…
+keybind-bind-hint = die nächste gedrückte Taste belegen
+keybind-search = Suchen
+keycode-unbound = Nicht belegt
+keycode-characters = Zeichen (US-Layout)
+keycode-modifiers = Modifikatoren
+keycode-usages = Tasten (unabhängig vom Layout)
+
+# Benachrichtigungen
+notify-dismiss = Schließen
+notify-details = Details
+notify-log = Protokoll
+notify-clear = Leeren
+notify-copy = Kopieren
+notify-empty = Nichts zu berichten
+notify-caused-by = verursacht durch: { $error }
+age-seconds = vor { $count } s
+age-minutes = vor { $count } min
+age-hours = vor { $count } h
+
+# Teilen
+share-copy = Teilen-Text kopieren
+share-copy-hint = die Tasteneinstellungen als Text kopieren, der im Chat eingefügt werden kann
+share-copied = Der Teilen-Text wurde in die Zwischenablage kopiert
+share-encode-error = Der Teilen-Text konnte nicht erstellt werden
+share-decode-error = Der Teilen-Text konnte nicht gelesen werden
+share-preview = Vorschau
+share-title = Geteilte Konfiguration
+share-key-count = Die geteilte Konfiguration ist für { $shared } Tasten, dieses Gerät hat { $device }
+share-invalid = { $count ->
+    [one] { $count } Einstellung ist ungültig
+   *[other] { $count } Einstellungen sind ungültig
+}
+share-same = Die geteilte Konfiguration ist die gleiche wie die des Geräts
+share-key = Taste
+share-setting = Einstellung
+share-device = Gerät
+share-shared = Geteilt
+share-apply = Anwenden
+share-apply-hint = die Einstellungen laden und auf das Gerät schreiben
+share-load = Laden
+share-load-hint = die Einstellungen laden, um sie vor dem Anwenden zu bearbeiten
+share-loaded = { $count ->
+    [one] Die geteilten Einstellungen von { $count } Taste wurden geladen
+   *[other] Die geteilten Einstellungen von { $count } Tasten wurden geladen
+}
+
+# Vorlagen
+preset-title = Vorlage: { $name }
+preset-osu-aggressive = osu! aggressiv
+preset-osu-aggressive-description = Kontinuierlicher Rapid Trigger mit flachem Auslösepunkt für schnelle Streams
+preset-osu-balanced = osu! ausgewogen
+preset-osu-balanced-description = Rapid Trigger, der oberhalb des Auslösepunkts zurücksetzt, nachsichtig, wenn deine Finger auf den Tasten ruhen
+preset-gaming = Gaming
+preset-gaming-description = Rapid Trigger mit mittlerem Auslösepunkt, für Bewegungstasten
+preset-typing = Tippen
+preset-typing-description = Kein Rapid Trigger und ein tiefer Auslösepunkt, wie ein normaler Schalter
+preset-anti-chatter = Vorsichtig gegen Prellen
+preset-anti-chatter-description = Kein Rapid Trigger und ein großer Abstand zum Loslassen, für verrauschte Sensoren oder Tasten, die doppelt auslösen
+preset-actuation = mm Auslösung
+preset-sensitivity = mm Rapid-Trigger-Empfindlichkeit
+preset-keys = Tasten
+preset-all = Alle
+preset-none = Keine
+preset-preview = Vorschau auf den Tasten
+preset-apply = { $count ->
+    [one] Auf { $count } Taste anwenden
+   *[other] Auf { $count } Tasten anwenden
+}
+preset-cancel = Abbrechen
+preset-applied = { $count ->
+    [one] { $count } Taste auf die Vorlage { $name } gesetzt, wende sie an, um sie auf das Gerät zu schreiben
+   *[other] { $count } Tasten auf die Vorlage { $name } gesetzt, wende sie an, um sie auf das Gerät zu schreiben
+}
+
+# Vergleichen
+compare-title = Vergleichen
+compare-profile = Profil { $name }
+compare-disconnected = das Gerät ist nicht verbunden
+compare-reading = die Konfiguration wird gelesen…
+compare-swap = Die Konfigurationen tauschen
+compare-swap-hint = tauschen
+compare-pick = Wähle zwei Konfigurationen zum Vergleichen
+compare-same = Die Konfigurationen sind gleich
+compare-key = Taste
+compare-setting = Einstellung
+compare-left = Links
+compare-right = Rechts
+compare-select = <Auswählen>
+compare-select-all = Alle auswählen
+compare-copy = { $count } links → rechts kopieren
+compare-copied-device = { $count ->
+    [one] { $count } Einstellung nach { $target } kopiert, wende sie an, um sie auf das Gerät zu schreiben
+   *[other] { $count } Einstellungen nach { $target } kopiert, wende sie an, um sie auf das Gerät zu schreiben
+}
+compare-copied-profile = { $count ->
+    [one] { $count } Einstellung in das Profil { $name } kopiert
+   *[other] { $count } Einstellungen in das Profil { $name } kopiert
+}
+compare-skipped = { $count ->
+    [one] { $count } Einstellung wurde nicht kopiert, ihre Taste fehlt oder hat Rapid Trigger aus
+   *[other] { $count } Einstellungen wurden nicht kopiert, ihre Taste fehlt oder hat Rapid Trigger aus
+}
+
+# Gerätefenster
+dashboard-title = Geräte
+dashboard-rescan = Neu suchen
+dashboard-read-all = Alle Konfigurationen lesen
+dashboard-read-all-hint = mit jedem Gerät verbinden, um seine Tasten und Firmware anzuzeigen
+dashboard-device = Gerät
+dashboard-port = Anschluss
+dashboard-status = Status
+dashboard-keys = Tasten
+dashboard-firmware = Firmware
+dashboard-target-hint = an dieses Gerät übertragen
+dashboard-usb = usb { $id }
+dashboard-manufacturer = von { $manufacturer }
+dashboard-serial-number = Seriennummer { $serial_number }
+dashboard-error = Fehler
+dashboard-polling = Abfrage mit { $rate } Hz
+dashboard-connected = Verbunden
+dashboard-not-connected = Nicht verbunden
+dashboard-keys-hint = Hall-Effekt- + digitale Tasten
+dashboard-firmware-unknown = unbekannt
+dashboard-push = Übertragen
+dashboard-push-selected = Konfiguration des ausgewählten Geräts
+dashboard-push-profile = Profil { $name }
+dashboard-push-targets = { $count ->
+    [one] an { $count } Gerät
+   *[other] an { $count } Geräte
+}
+dashboard-last-push = Letzte Übertragung
+dashboard-push-pending = die Konfiguration wird gelesen
+dashboard-push-applied = { $count ->
+    [one] auf { $count } Taste angewendet
+   *[other] auf { $count } Tasten angewendet
+}
+dashboard-push-applied-partly = auf { $keys } von { $count } Tasten angewendet
+push-connection-lost = die Verbindung wurde getrennt
+push-invalid-for-device = die Einstellungen sind für dieses Gerät ungültig
+push-backup-error = die Konfiguration konnte nicht gesichert werden: { $error }
+push-no-config = Das ausgewählte Gerät hat keine Konfiguration zum Übertragen
+push-invalid = Die Konfiguration hat ungültige Einstellungen und wurde nicht übertragen
+push-done = { $count ->
+    [one] Die Konfiguration wurde an { $count } Gerät übertragen
+   *[other] Die Konfiguration wurde an { $count } Geräte übertragen
+}
+push-waiting = Die Konfiguration wurde an { $applied } von { $count } Geräten übertragen, warte auf den Rest
+push-partial = Die Konfiguration wurde an { $applied } von { $count } Geräten übertragen
+
+# automatische Profile
+autoswitch-title = Automatische Profile
+autoswitch-enable = Profile nach der fokussierten Anwendung wechseln
+autoswitch-unsupported = Automatischer Profilwechsel wird auf diesem Desktop nicht unterstützt
+autoswitch-focus-error = Die fokussierte Anwendung konnte nicht ermittelt werden
+autoswitch-save-error = Die Regeln für den Profilwechsel konnten nicht gespeichert werden
+autoswitch-detector = Erkennung mit { $detector }
+autoswitch-undetectable = Die fokussierte Anwendung kann nicht erkannt werden
+autoswitch-focused = Fokussiert: { $process } ({ $title })
+autoswitch-focused-nothing = Fokussiert: nichts
+autoswitch-nothing = nichts
+autoswitch-override = Manuell festlegen
+autoswitch-override-hint = das aktuelle Profil behalten, egal was fokussiert ist
+autoswitch-match = Vergleich
+autoswitch-contains = Enthält
+autoswitch-profile = Profil
+autoswitch-match-process = Prozess
+autoswitch-match-title = Titel
+autoswitch-remove = Die Regel entfernen
+autoswitch-remove-hint = entfernen
+autoswitch-add-rule = Regel hinzufügen
+autoswitch-add-focused = { $process } hinzufügen
+autoswitch-fallback = Sonst
+autoswitch-keep-profile = Profil behalten
+autoswitch-log = Wechsel
+autoswitch-log-empty = Noch keine Wechsel
+autoswitch-not-applied = das Profil konnte nicht angewendet werden
+
+# Import und Export
+interop-title = Import / Export
+interop-format = Format
+interop-format-commands = Serielle Befehle
+interop-format-json = Crustility JSON
+interop-open = Öffnen
+interop-save = Speichern
+interop-text-hint = Öffne eine Datei oder füge ihren Inhalt ein
+interop-export = Gerät exportieren
+interop-export-hint = die Einstellungen des ausgewählten Geräts schreiben
+interop-import-device = In das Gerät importieren
+interop-import-device-hint = die Einstellungen in das ausgewählte Gerät laden, wende sie an, um sie zu schreiben
+interop-profile-name = Profilname
+interop-import-profile = Als Profil importieren
+interop-saved = { $path } gespeichert
+interop-save-error = { $path } konnte nicht gespeichert werden
+interop-open-error = { $path } konnte nicht geöffnet werden
+interop-exported = { $count ->
+    [one] Ohne { $count } Einstellung exportiert, die sich nicht übertragen lässt
+   *[other] Ohne { $count } Einstellungen exportiert, die sich nicht übertragen lassen
+}
+interop-imported = { $count ->
+    [one] Ohne { $count } Einstellung importiert, die sich nicht übertragen lässt
+   *[other] Ohne { $count } Einstellungen importiert, die sich nicht übertragen lassen
+}
+interop-export-report = Nicht exportiert
+interop-import-report = Nicht importiert
+interop-imported-profile = Das Profil { $name } wurde importiert
+interop-import-error = { $format } konnte nicht importiert werden
+interop-key-count = Der Import hat { $import } Tasten, das Gerät { $device }
+interop-imported-device = { $count ->
+    [one] Die Einstellungen von { $count } Taste wurden importiert, wende sie an, um sie auf das Gerät zu schreiben
+   *[other] Die Einstellungen von { $count } Tasten wurden importiert, wende sie an, um sie auf das Gerät zu schreiben
+}
+interop-all-translated = Alle Einstellungen wurden übertragen
+interop-setting = Einstellung
+interop-value = Wert
+interop-reason = Grund
+interop-reason-digital-keys = digitale Tasten haben noch keine Einstellungen
+interop-reason-out-of-range = außerhalb des Bereichs, den die Firmware annimmt
+interop-reason-not-a-setting = keine Einstellung
+interop-reason-not-a-key-setting = keine Tasteneinstellung
+interop-reason-past-key-count = { $count ->
+    [one] laut Datei gibt es nur { $count } Taste
+   *[other] laut Datei gibt es nur { $count } Tasten
+}
+interop-reason-unknown-setting = unbekannte Einstellung
+interop-reason-invalid-value = kein gültiger Wert
+interop-reason-calibration = die Kalibrierung gehört zum Schalter und bleibt auf dem Gerät
+interop-reason-rapid-trigger-off = Rapid Trigger ist aus
+
+# Berechtigungen
+permission-title = Zugriff verweigert
+permission-denied = crustility darf { $port } nicht öffnen.
+permission-groups = Serielle Anschlüsse gehören einer Gruppe, nur ihre Mitglieder oder der angemeldete Benutzer mit einer passenden udev-Regel können sie öffnen.
+permission-in-group = Du bist bereits in der Gruppe { $group }, melde dich ab und wieder an, falls du erst kürzlich hinzugefügt wurdest.
+permission-join-group = Du kannst dich zur Gruppe { $group } hinzufügen und dich neu anmelden:
+permission-udev = Oder installiere eine udev-Regel, die dir Zugriff auf dieses Minipad gibt:
+permission-rule = Regel
+permission-waiting = Warte auf Authentifizierung
+permission-install = udev-Regel installieren
+permission-retry = Erneut versuchen
@@ -0,0 +1,468 @@
+# key options
+select-key = Select Key
+key-heading = Key { $key }
+rapid-trigger = Rapid Trigger
+enable = Enable
+continuous = Continuous
+up-sensitivity = mm up sensitivity
+down-sensitivity = mm down sensitivity
+hysteresis = Hysteresis
+hysteresis-upper = mm upper
+hysteresis-lower = mm lower
+hid = HID
+
+# menu bar
+apply-config = Apply Config
+apply-config-hint = Write the config to the device
+invalid-settings = { $count ->
+    [one] { $count } invalid setting, fix it before applying
+   *[other] { $count } invalid settings, fix them before applying
+}
+menu-devices = Devices
+menu-compare = Compare
+menu-import-export = Import / Export
+menu-auto-profiles = Auto Profiles
+menu-settings = Settings
+menu-share = Share
+menu-presets = Presets
+menu-log = Log
+menu-log-unread = Log ({ $count })
+polling-throttled = the device can't keep up, polling was throttled
+polling-rate-error = Could not change the polling rate
+
+# confirmation
+confirm = Confirm
+confirm-apply = Write the config to the device?
+confirm-delete-profile = Delete the profile { $name }?
+yes = Yes
+no = No
+
+# theme menu
+menu-theme = Theme
+reload-themes = Reload themes
+reload-themes-hint = load the toml palettes in { $dir }
+
+# serial port menu
+menu-port = Port
+baud-rate = Baud rate
+flow-control = Flow control
+parity = Parity
+data-bits = Data bits
+stop-bits = Stop bits
+flow-control-none = None
+flow-control-software = Software
+flow-control-hardware = Hardware
+parity-none = None
+parity-odd = Odd
+parity-even = Even
+timeout = Timeout
+reset-defaults = Reset to defaults
+serial-settings-error = Could not change the serial settings
+
+# devices
+all-devices = All devices
+select-device = <Select Device>
+disconnected = <Disconnected>
+
+# visualizer
+actuation = Actuation
+release = Release
+threshold-keyboard-hint = { $name } { $value }, arrow keys to move
+key-pressed = pressed
+key-released = released
+key-description = Key { $key }, { $state }, actuation { $actuation }, release { $release }
+threshold-description = { $name } in mm
+
+# layout menu
+menu-layout = Layout
+file = File
+load = Load
+save = Save
+use-default-layout = Use default layout
+reset-view = Reset view
+layout-store-error = Could not store the layout
+layout-load-error = Could not load the layout
+layout-save-error = Could not save the layout
+layout-saved = Layout saved to { $path }
+
+# profiles
+profile = Profile
+profile-named = Profile: { $name }
+no-profiles = No saved profiles
+delete = delete
+delete-profile = Delete the profile { $name }
+profile-name = Name
+profile-load-error = Could not load the profile { $name }
+profile-key-count = The profile { $name } has { $profile } keys, the device { $device }
+profile-loaded = Loaded the profile { $name } into { $count } keys
+profile-saved = Saved the profile { $name }
+profile-save-error = Could not save the profile { $name }
+profile-delete-error = Could not delete the profile { $name }
+
+# settings
+settings = Settings
+settings-save-error = Could not save the settings
+language = Language
+ui-scale = UI scale
+smaller = Smaller
+larger = Larger
+theme = Theme
+polling-rate = Polling rate
+window = Window
+remember-window = Remember size and position
+confirm-before = Confirm
+confirm-before-apply = Before applying a config
+confirm-before-delete = Before deleting a profile
+control-server = Control server
+control-server-enable = Let other tools control crustility
+control-server-hint = json-rpc, one message per line
+control-server-token = Clients have to call auth with the token in { $path } first
+localhost-port = Localhost port
+key-overlay = Key overlay
+key-overlay-enable = Serve an overlay for streaming
+port = Port
+rate = Rate
+overlay-viewers = { $count } connected
+device-changes = Device changes
+resync-every = Check every
+resync-on-focus = When the window gets focus
+
+# changes made outside of crustility
+resync-title = { $device } was changed
+resync-description = The settings on the device are not the ones crustility last read or wrote, another tool or a firmware command changed them.
+resync-edited = { $count ->
+    [one] You have { $count } edit that was not applied.
+   *[other] You have { $count } edits that were not applied.
+}
+resync-key = Key
+resync-setting = Setting
+resync-known = Known
+resync-device = Device
+resync-yours = Yours
+resync-reload = Reload
+resync-reload-hint = use the settings of the device, your edits are lost
+resync-merge = Merge
+resync-merge-hint = take the changes of the device, keep your edits where both changed
+resync-overwrite = Overwrite
+resync-overwrite-hint = write your config to the device again
+resync-reloaded = Reloaded the config of { $device }
+resync-merged = { $count ->
+    [one] Merged { $count } setting from { $device }
+   *[other] Merged { $count } settings from { $device }
+}
+resync-conflicts = { $count ->
+    [one] { $count } setting was changed on both sides, your edit was kept
+   *[other] { $count } settings were changed on both sides, your edits were kept
+}
+resync-error = Could not check the device for changes
+
+# backups and factory reset
+menu-backups = Backups
+backups-browse = Restore a backup…
+factory-reset = Reset to defaults…
+factory-reset-hint = restore the firmware defaults, a backup is taken first
+confirm-reset = Reset the device to the firmware defaults? A backup of its settings is kept.
+factory-reset-done = { $device } was reset to its defaults
+factory-reset-error = Could not reset the device
+backup-error = Could not back up the config, nothing was changed
+backups = Backups of { $device }
+no-backups = No backups yet, one is taken before every apply and reset
+backup-taken = Taken
+backup-reason = Before
+backup-keys = Keys
+backup-reason-apply = apply
+backup-reason-reset = reset
+backup-load = Load
+backup-load-hint = load the settings to edit them before applying
+backup-restore = Restore
+backup-restore-hint = load the settings and write them to the device
+delete-backup = Delete the backup from { $time }
+backup-loaded = Loaded the backup from { $time } into { $count } keys
+backup-key-count = The backup has { $backup } keys, the device { $device }
+backup-delete-error = Could not delete the backup
+backups-list-error = Could not read the backups
+
+# applying and reading configs
+apply-invalid = The config has invalid settings and was not applied
+apply-error = Could not apply the config
+config-applied = { $count ->
+    [one] Config applied to { $count } key
+   *[other] Config applied to { $count } keys
+}
+config-read = { $count ->
+    [one] Read config of { $count } key from { $device }
+   *[other] Read config of { $count } keys from { $device }
+}
+config-read-error = Could not read the config
+device-read-error = Could not read the config of { $device }
+device-connect-error = Could not connect to { $device }
+device-connection-lost = Lost connection to { $device }
+device-throttled = { $device } can't keep up with { $rate } Hz, polling was throttled
+sensors-start-error = Could not start reading sensors
+sensors-stop-error = Could not stop reading sensors
+profile-save-first = Save a profile from the profile menu first
+theme-load-error = Could not load the theme { $path }
+theme-missing = The theme { $name } does not exist anymore, using { $default }
+overlay-start-error = Could not start the overlay server on port { $port }
+control-server-start-error = Could not start the control server on { $endpoint }
+
+# setting names
+setting-key = Key
+setting-rapid-trigger = Rapid trigger
+setting-continuous = Continuous
+setting-up-sensitivity = Up sensitivity
+setting-down-sensitivity = Down sensitivity
+setting-hysteresis-lower = Lower hysteresis
+setting-hysteresis-upper = Upper hysteresis
+setting-key-code = Key code
+setting-hid = HID
+value-on = on
+value-off = off
+value-present = present
+
+# invalid settings
+problem-out-of-range = must be between { $min } and { $max }
+problem-too-close = must be at least { $tolerance } above the lower hysteresis
+
+# key binding
+keybind-bind = Bind
+keybind-capturing = Press a key…
+keybind-bind-hint = bind the next key you press
+keybind-search = Search
+keycode-unbound = Unbound
+keycode-characters = Characters (us layout)
+keycode-modifiers = Modifiers
+keycode-usages = Keys (layout independent)
+
+# notifications
+notify-dismiss = Dismiss
+notify-details = Details
+notify-log = Log
+notify-clear = Clear
+notify-copy = Copy
+notify-empty = Nothing to report
+notify-caused-by = caused by: { $error }
+age-seconds = { $count }s ago
+age-minutes = { $count }m ago
+age-hours = { $count }h ago
+
+# share strings
+share-copy = Copy share string
+share-copy-hint = copy the key settings as text that can be pasted in chat
+share-copied = Copied the share string to the clipboard
+share-encode-error = Could not create the share string
+share-decode-error = Could not read the share string
+share-preview = Preview
+share-title = Shared config
+share-key-count = The shared config is for { $shared } keys, this device has { $device }
+share-invalid = { $count ->
+    [one] { $count } setting is invalid
+   *[other] { $count } settings are invalid
+}
+share-same = The shared config is the same as the device's
+share-key = Key
+share-setting = Setting
+share-device = Device
+share-shared = Shared
+share-apply = Apply
+share-apply-hint = load the settings and write them to the device
+share-load = Load
+share-load-hint = load the settings to edit them before applying
+share-loaded = { $count ->
+    [one] Loaded the shared settings of { $count } key
+   *[other] Loaded the shared settings of { $count } keys
+}
+
+# presets
+preset-title = Preset: { $name }
+preset-osu-aggressive = osu! aggressive
+preset-osu-aggressive-description = Continuous rapid trigger with a shallow actuation for fast streams
+preset-osu-balanced = osu! balanced
+preset-osu-balanced-description = Rapid trigger that resets above the actuation point, forgiving when resting fingers on the keys
+preset-gaming = Gaming
+preset-gaming-description = Rapid trigger with a medium actuation, for movement keys
+preset-typing = Typing
+preset-typing-description = No rapid trigger and a deep actuation, like a regular switch
+preset-anti-chatter = Conservative anti-chatter
+preset-anti-chatter-description = No rapid trigger and a wide release gap, for noisy sensors or keys that double press
+preset-actuation = mm actuation
+preset-sensitivity = mm rapid trigger sensitivity
+preset-keys = Keys
+preset-all = All
+preset-none = None
+preset-preview = Preview on the keys
+preset-apply = { $count ->
+    [one] Apply to { $count } key
+   *[other] Apply to { $count } keys
+}
+preset-cancel = Cancel
+preset-applied = { $count ->
+    [one] Set { $count } key to the preset { $name }, apply to write it to the device
+   *[other] Set { $count } keys to the preset { $name }, apply to write them to the device
+}
+
+# compare
+compare-title = Compare
+compare-profile = profile { $name }
+compare-disconnected = the device is disconnected
+compare-reading = reading the config…
+compare-swap = Swap the configs
+compare-swap-hint = swap
+compare-pick = Pick two configs to compare
+compare-same = The configs are the same
+compare-key = Key
+compare-setting = Setting
+compare-left = Left
+compare-right = Right
+compare-select = <Select>
+compare-select-all = Select all
+compare-copy = Copy { $count } left → right
+compare-copied-device = { $count ->
+    [one] Copied { $count } setting to { $target }, apply to write it to the device
+   *[other] Copied { $count } settings to { $target }, apply to write them to the device
+}
+compare-copied-profile = { $count ->
+    [one] Copied { $count } setting to the profile { $name }
+   *[other] Copied { $count } settings to the profile { $name }
+}
+compare-skipped = { $count ->
+    [one] { $count } setting was not copied, its key is missing or has rapid trigger off
+   *[other] { $count } settings were not copied, their key is missing or has rapid trigger off
+}
+
+# devices window
+dashboard-title = Devices
+dashboard-rescan = Rescan
+dashboard-read-all = Read all configs
+dashboard-read-all-hint = connect to every device to show its keys and firmware
+dashboard-device = Device
+dashboard-port = Port
+dashboard-status = Status
+dashboard-keys = Keys
+dashboard-firmware = Firmware
+dashboard-target-hint = push to this device
+dashboard-usb = usb { $id }
+dashboard-manufacturer = by { $manufacturer }
+dashboard-serial-number = serial number { $serial_number }
+dashboard-error = Error
+dashboard-polling = Polling at { $rate } Hz
+dashboard-connected = Connected
+dashboard-not-connected = Not connected
+dashboard-keys-hint = hall effect + digital keys
+dashboard-firmware-unknown = unknown
+dashboard-push = Push
+dashboard-push-selected = config of the selected device
+dashboard-push-profile = profile { $name }
+dashboard-push-targets = { $count ->
+    [one] to { $count } device
+   *[other] to { $count } devices
+}
+dashboard-last-push = Last push
+dashboard-push-pending = reading the config
+dashboard-push-applied = { $count ->
+    [one] applied to { $count } key
+   *[other] applied to { $count } keys
+}
+dashboard-push-applied-partly = applied to { $keys } of { $count } keys
+push-connection-lost = lost the connection
+push-invalid-for-device = the settings are invalid for this device
+push-backup-error = could not back up the config: { $error }
+push-no-config = The selected device has no config to push
+push-invalid = The config has invalid settings and was not pushed
+push-done = { $count ->
+    [one] Pushed the config to { $count } device
+   *[other] Pushed the config to { $count } devices
+}
+push-waiting = Pushed the config to { $applied } of { $count } devices, waiting for the rest
+push-partial = Pushed the config to { $applied } of { $count } devices
+
+# automatic profiles
+autoswitch-title = Automatic profiles
+autoswitch-enable = Switch profiles by the focused application
+autoswitch-unsupported = Automatic profile switching is not supported on this desktop
+autoswitch-focus-error = Could not find out which application is focused
+autoswitch-save-error = Could not save the profile switching rules
+autoswitch-detector = Detecting with { $detector }
+autoswitch-undetectable = The focused application can't be detected
+autoswitch-focused = Focused: { $process } ({ $title })
+autoswitch-focused-nothing = Focused: nothing
+autoswitch-nothing = nothing
+autoswitch-override = Manual override
+autoswitch-override-hint = keep the current profile whatever is focused
+autoswitch-match = Match
+autoswitch-contains = Contains
+autoswitch-profile = Profile
+autoswitch-match-process = Process
+autoswitch-match-title = Title
+autoswitch-remove = Remove the rule
+autoswitch-remove-hint = remove
+autoswitch-add-rule = Add rule
+autoswitch-add-focused = Add { $process }
+autoswitch-fallback = Otherwise
+autoswitch-keep-profile = keep the profile
+autoswitch-log = Switches
+autoswitch-log-empty = No switches yet
+autoswitch-not-applied = the profile could not be applied
+
+# import and export
+interop-title = Import / Export
+interop-format = Format
+interop-format-commands = Serial commands
+interop-format-json = Crustility JSON
+interop-open = Open
+interop-save = Save
+interop-text-hint = Open a file or paste its content
+interop-export = Export device
+interop-export-hint = write the settings of the selected device
+interop-import-device = Import to device
+interop-import-device-hint = load the settings into the selected device, apply to write them
+interop-profile-name = Profile name
+interop-import-profile = Import as profile
+interop-saved = Saved { $path }
+interop-save-error = Could not save { $path }
+interop-open-error = Could not open { $path }
+interop-exported = { $count ->
+    [one] Exported without { $count } setting that doesn't translate
+   *[other] Exported without { $count } settings that don't translate
+}
+interop-imported = { $count ->
+    [one] Imported without { $count } setting that doesn't translate
+   *[other] Imported without { $count } settings that don't translate
+}
+interop-export-report = Left out of the export
+interop-import-report = Left out of the import
+interop-imported-profile = Imported the profile { $name }
+interop-import-error = Could not import the { $format }
+interop-key-count = The import has { $import } keys, the device { $device }
+interop-imported-device = { $count ->
+    [one] Imported the settings of { $count } key, apply to write them to the device
+   *[other] Imported the settings of { $count } keys, apply to write them to the device
+}
+interop-all-translated = Every setting was translated
+interop-setting = Setting
+interop-value = Value
+interop-reason = Reason
+interop-reason-digital-keys = digital keys have no settings yet
+interop-reason-out-of-range = out of the range the firmware takes
+interop-reason-not-a-setting = not a setting
+interop-reason-not-a-key-setting = not a key setting
+interop-reason-past-key-count = { $count ->
+    [one] the file says there is only { $count } key
+   *[other] the file says there are only { $count } keys
+}
+interop-reason-unknown-setting = unknown setting
+interop-reason-invalid-value = not a valid value
+interop-reason-calibration = calibration belongs to the switch and stays on the device
+interop-reason-rapid-trigger-off = rapid trigger is off
+
+# permissions
+permission-title = Permission denied
+permission-denied = crustility is not allowed to open { $port }.
+permission-groups = Serial ports belong to a group and only its members or the logged in user with a matching udev rule can open them.
+permission-in-group = You are in the { $group } group already, log out and back in if you were added recently.
+permission-join-group = You can add yourself to the { $group } group and log in again:
+permission-udev = Or install a udev rule that gives you access to this minipad:
+permission-rule = Rule
+permission-waiting = Waiting for authentication
+permission-install = Install udev rule
+permission-retry = Retry
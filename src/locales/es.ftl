@@ -0,0 +1,468 @@
+# opciones de tecla
+select-key = Selecciona una tecla
+key-heading = Tecla { $key }
+rapid-trigger = Rapid Trigger
+enable = Activar
+continuous = Continuo
+up-sensitivity = mm sensibilidad al subir
+down-sensitivity = mm sensibilidad al bajar
+hysteresis = Histéresis
+hysteresis-upper = mm superior
+hysteresis-lower = mm inferior
+hid = HID
+
+# barra de menú
+apply-config = Aplicar configuración
+apply-config-hint = Escribir la configuración en el dispositivo
+invalid-settings = { $count ->
+    [one] { $count } ajuste no válido, corrígelo antes de aplicar
+   *[other] { $count } ajustes no válidos, corrígelos antes de aplicar
+}
+menu-devices = Dispositivos
+menu-compare = Comparar
+menu-import-export = Importar / Exportar
+menu-auto-profiles = Perfiles automáticos
+menu-settings = Ajustes
+menu-share = Compartir
+menu-presets = Plantillas
+menu-log = Registro
+menu-log-unread = Registro ({ $count })
+polling-throttled = el dispositivo no da abasto, se redujo la frecuencia de sondeo
+polling-rate-error = No se pudo cambiar la frecuencia de sondeo
+
+# confirmación
+confirm = Confirmar
+confirm-apply = ¿Escribir la configuración en el dispositivo?
+confirm-delete-profile = ¿Eliminar el perfil { $name }?
+yes = Sí
+no = No
+
+# tema
+menu-theme = Tema
+reload-themes = Recargar temas
+reload-themes-hint = carga las paletas toml de { $dir }
+
+# puerto serie
+menu-port = Puerto
+baud-rate = Baudios
+flow-control = Control de flujo
+parity = Paridad
+data-bits = Bits de datos
+stop-bits = Bits de parada
+flow-control-none = Ninguno
+flow-control-software = Software
+flow-control-hardware = Hardware
+parity-none = Ninguna
+parity-odd = Impar
+parity-even = Par
+timeout = Tiempo de espera
+reset-defaults = Restablecer valores predeterminados
+serial-settings-error = No se pudieron cambiar los ajustes del puerto serie
+
+# dispositivos
+all-devices = Todos los dispositivos
+select-device = <Selecciona un dispositivo>
+disconnected = <Desconectado>
+
+# visualizador
+actuation = Activación
+release = Liberación
+threshold-keyboard-hint = { $name } { $value }, muévelo con las flechas
+key-pressed = pulsada
+key-released = suelta
+key-description = Tecla { $key }, { $state }, activación { $actuation }, liberación { $release }
+threshold-description = { $name } en mm
+
+# disposición
+menu-layout = Disposición
+file = Archivo
+load = Cargar
+save = Guardar
+use-default-layout = Usar la disposición predeterminada
+reset-view = Restablecer vista
+layout-store-error = No se pudo guardar la disposición
+layout-load-error = No se pudo cargar la disposición
+layout-save-error = No se pudo guardar la disposición
+layout-saved = Disposición guardada en { $path }
+
+# perfiles
+profile = Perfil
+profile-named = Perfil: { $name }
+no-profiles = No hay perfiles guardados
+delete = eliminar
+delete-profile = Eliminar el perfil { $name }
+profile-name = Nombre
+profile-load-error = No se pudo cargar el perfil { $name }
+profile-key-count = El perfil { $name } tiene { $profile } teclas, el dispositivo { $device }
+profile-loaded = Perfil { $name } cargado en { $count } teclas
+profile-saved = Perfil { $name } guardado
+profile-save-error = No se pudo guardar el perfil { $name }
+profile-delete-error = No se pudo eliminar el perfil { $name }
+
+# ajustes
+settings = Ajustes
+settings-save-error = No se pudieron guardar los ajustes
+language = Idioma
+ui-scale = Escala de la interfaz
+smaller = Más pequeño
+larger = Más grande
+theme = Tema
+polling-rate = Frecuencia de sondeo
+window = Ventana
+remember-window = Recordar tamaño y posición
+confirm-before = Confirmar
+confirm-before-apply = Antes de aplicar una configuración
+confirm-before-delete = Antes de eliminar un perfil
+control-server = Servidor de control
+control-server-enable = Permitir que otras herramientas controlen crustility
+control-server-hint = json-rpc, un mensaje por línea
+control-server-token = Los clientes deben llamar primero a auth con el token de { $path }
+localhost-port = Puerto en localhost
+key-overlay = Overlay de teclas
+key-overlay-enable = Servir un overlay para streaming
+port = Puerto
+rate = Frecuencia
+overlay-viewers = { $count } conectados
+device-changes = Cambios en el dispositivo
+resync-every = Comprobar cada
+resync-on-focus = Cuando la ventana recibe el foco
+
+# cambios hechos fuera de crustility
+resync-title = { $device } ha cambiado
+resync-description = Los ajustes del dispositivo no son los que crustility leyó o escribió por última vez, otro programa o un comando del firmware los cambió.
+resync-edited = { $count ->
+    [one] Tienes { $count } cambio sin aplicar.
+   *[other] Tienes { $count } cambios sin aplicar.
+}
+resync-key = Tecla
+resync-setting = Ajuste
+resync-known = Conocido
+resync-device = Dispositivo
+resync-yours = Tuyo
+resync-reload = Recargar
+resync-reload-hint = usar los ajustes del dispositivo, tus cambios se pierden
+resync-merge = Combinar
+resync-merge-hint = tomar los cambios del dispositivo, mantener tus cambios donde ambos cambiaron
+resync-overwrite = Sobrescribir
+resync-overwrite-hint = escribir tu configuración en el dispositivo de nuevo
+resync-reloaded = Se recargó la configuración de { $device }
+resync-merged = { $count ->
+    [one] Se combinó { $count } ajuste de { $device }
+   *[other] Se combinaron { $count } ajustes de { $device }
+}
+resync-conflicts = { $count ->
+    [one] { $count } ajuste cambió en ambos lados, se mantuvo tu cambio
+   *[other] { $count } ajustes cambiaron en ambos lados, se mantuvieron tus cambios
+}
+resync-error = No se pudo comprobar si el dispositivo cambió
+
+# copias de seguridad y restablecimiento
+menu-backups = Copias de seguridad
+backups-browse = Restaurar una copia…
+factory-reset = Restablecer valores predeterminados…
+factory-reset-hint = restaurar los valores del firmware, antes se hace una copia de seguridad
+confirm-reset = ¿Restablecer el dispositivo a los valores del firmware? Se guarda una copia de sus ajustes.
+factory-reset-done = { $device } se restableció a sus valores predeterminados
+factory-reset-error = No se pudo restablecer el dispositivo
+backup-error = No se pudo hacer una copia de la configuración, no se cambió nada
+backups = Copias de seguridad de { $device }
+no-backups = Todavía no hay copias, se hace una antes de cada aplicación y restablecimiento
+backup-taken = Hecha
+backup-reason = Antes de
+backup-keys = Teclas
+backup-reason-apply = aplicar
+backup-reason-reset = restablecer
+backup-load = Cargar
+backup-load-hint = cargar los ajustes para editarlos antes de aplicarlos
+backup-restore = Restaurar
+backup-restore-hint = cargar los ajustes y escribirlos en el dispositivo
+delete-backup = Eliminar la copia del { $time }
+backup-loaded = Se cargó la copia del { $time } en { $count } teclas
+backup-key-count = La copia tiene { $backup } teclas, el dispositivo { $device }
+backup-delete-error = No se pudo eliminar la copia
+backups-list-error = No se pudieron leer las copias de seguridad
+
+# aplicar y leer configuraciones
+apply-invalid = La configuración tiene ajustes no válidos y no se aplicó
+apply-error = No se pudo aplicar la configuración
+config-applied = { $count ->
+    [one] Configuración aplicada a { $count } tecla
+   *[other] Configuración aplicada a { $count } teclas
+}
+config-read = { $count ->
+    [one] Configuración de { $count } tecla leída de { $device }
+   *[other] Configuración de { $count } teclas leída de { $device }
+}
+config-read-error = No se pudo leer la configuración
+device-read-error = No se pudo leer la configuración de { $device }
+device-connect-error = No se pudo conectar con { $device }
+device-connection-lost = Se perdió la conexión con { $device }
+device-throttled = { $device } no alcanza { $rate } Hz, se redujo la frecuencia de sondeo
+sensors-start-error = No se pudo empezar a leer los sensores
+sensors-stop-error = No se pudo dejar de leer los sensores
+profile-save-first = Guarda primero un perfil desde el menú de perfiles
+theme-load-error = No se pudo cargar el tema { $path }
+theme-missing = El tema { $name } ya no existe, se usa { $default }
+overlay-start-error = No se pudo iniciar el servidor de overlay en el puerto { $port }
+control-server-start-error = No se pudo iniciar el servidor de control en { $endpoint }
+
+# nombres de los ajustes
+setting-key = Tecla
+setting-rapid-trigger = Rapid Trigger
+setting-continuous = Continuo
+setting-up-sensitivity = Sensibilidad al subir
+setting-down-sensitivity = Sensibilidad al bajar
+setting-hysteresis-lower = Histéresis inferior
+setting-hysteresis-upper = Histéresis superior
+setting-key-code = Código de tecla
+setting-hid = HID
+value-on = activado
+value-off = desactivado
+value-present = presente
+
+# invalid settings
+problem-out-of-range = debe estar entre { $min } y { $max }
+problem-too-close = debe estar al menos { $tolerance } por encima de la histéresis inferior
+
+# asignación de teclas
+keybind-bind = Asignar
+keybind-capturing = Pulsa una tecla…
+keybind-bind-hint = asignar la siguiente tecla que pulses
+keybind-search = Buscar
+keycode-unbound = Sin asignar
+keycode-characters = Caracteres (distribución us)
+keycode-modifiers = Modificadores
+keycode-usages = Teclas (independientes de la distribución)
+
+# notificaciones
+notify-dismiss = Descartar
+notify-details = Detalles
+notify-log = Registro
+notify-clear = Vaciar
+notify-copy = Copiar
+notify-empty = Nada que informar
+notify-caused-by = causado por: { $error }
+age-seconds = hace { $count } s
+age-minutes = hace { $count } min
+age-hours = hace { $count } h
+
+# compartir
+share-copy = Copiar texto para compartir
+share-copy-hint = copiar los ajustes de las teclas como texto que se puede pegar en un chat
+share-copied = Texto para compartir copiado al portapapeles
+share-encode-error = No se pudo crear el texto para compartir
+share-decode-error = No se pudo leer el texto para compartir
+share-preview = Vista previa
+share-title = Configuración compartida
+share-key-count = La configuración compartida es para { $shared } teclas, este dispositivo tiene { $device }
+share-invalid = { $count ->
+    [one] { $count } ajuste no es válido
+   *[other] { $count } ajustes no son válidos
+}
+share-same = La configuración compartida es la misma que la del dispositivo
+share-key = Tecla
+share-setting = Ajuste
+share-device = Dispositivo
+share-shared = Compartido
+share-apply = Aplicar
+share-apply-hint = cargar los ajustes y escribirlos en el dispositivo
+share-load = Cargar
+share-load-hint = cargar los ajustes para editarlos antes de aplicarlos
+share-loaded = { $count ->
+    [one] Ajustes compartidos de { $count } tecla cargados
+   *[other] Ajustes compartidos de { $count } teclas cargados
+}
+
+# preajustes
+preset-title = Preajuste: { $name }
+preset-osu-aggressive = osu! agresivo
+preset-osu-aggressive-description = Rapid Trigger continuo con una activación poco profunda para streams rápidos
+preset-osu-balanced = osu! equilibrado
+preset-osu-balanced-description = Rapid Trigger que se reinicia por encima del punto de activación, tolerante si apoyas los dedos en las teclas
+preset-gaming = Juegos
+preset-gaming-description = Rapid Trigger con una activación media, para las teclas de movimiento
+preset-typing = Escritura
+preset-typing-description = Sin Rapid Trigger y con una activación profunda, como un interruptor normal
+preset-anti-chatter = Anti rebote conservador
+preset-anti-chatter-description = Sin Rapid Trigger y con una separación de liberación amplia, para sensores con ruido o teclas que se pulsan dos veces
+preset-actuation = mm de activación
+preset-sensitivity = mm de sensibilidad de rapid trigger
+preset-keys = Teclas
+preset-all = Todas
+preset-none = Ninguna
+preset-preview = Vista previa en las teclas
+preset-apply = { $count ->
+    [one] Aplicar a { $count } tecla
+   *[other] Aplicar a { $count } teclas
+}
+preset-cancel = Cancelar
+preset-applied = { $count ->
+    [one] { $count } tecla con el preajuste { $name }, aplica para escribirlo en el dispositivo
+   *[other] { $count } teclas con el preajuste { $name }, aplica para escribirlo en el dispositivo
+}
+
+# comparar
+compare-title = Comparar
+compare-profile = perfil { $name }
+compare-disconnected = el dispositivo está desconectado
+compare-reading = leyendo la configuración…
+compare-swap = Intercambiar las configuraciones
+compare-swap-hint = intercambiar
+compare-pick = Elige dos configuraciones para comparar
+compare-same = Las configuraciones son iguales
+compare-key = Tecla
+compare-setting = Ajuste
+compare-left = Izquierda
+compare-right = Derecha
+compare-select = <Seleccionar>
+compare-select-all = Seleccionar todo
+compare-copy = Copiar { $count } izquierda → derecha
+compare-copied-device = { $count ->
+    [one] { $count } ajuste copiado a { $target }, aplica para escribirlo en el dispositivo
+   *[other] { $count } ajustes copiados a { $target }, aplica para escribirlos en el dispositivo
+}
+compare-copied-profile = { $count ->
+    [one] { $count } ajuste copiado al perfil { $name }
+   *[other] { $count } ajustes copiados al perfil { $name }
+}
+compare-skipped = { $count ->
+    [one] { $count } ajuste no se copió, su tecla falta o tiene rapid trigger desactivado
+   *[other] { $count } ajustes no se copiaron, su tecla falta o tiene rapid trigger desactivado
+}
+
+# ventana de dispositivos
+dashboard-title = Dispositivos
+dashboard-rescan = Volver a buscar
+dashboard-read-all = Leer todas las configuraciones
+dashboard-read-all-hint = conectar con cada dispositivo para mostrar sus teclas y firmware
+dashboard-device = Dispositivo
+dashboard-port = Puerto
+dashboard-status = Estado
+dashboard-keys = Teclas
+dashboard-firmware = Firmware
+dashboard-target-hint = enviar a este dispositivo
+dashboard-usb = usb { $id }
+dashboard-manufacturer = de { $manufacturer }
+dashboard-serial-number = número de serie { $serial_number }
+dashboard-error = Error
+dashboard-polling = Sondeo a { $rate } Hz
+dashboard-connected = Conectado
+dashboard-not-connected = No conectado
+dashboard-keys-hint = teclas de efecto hall + digitales
+dashboard-firmware-unknown = desconocido
+dashboard-push = Enviar
+dashboard-push-selected = configuración del dispositivo seleccionado
+dashboard-push-profile = perfil { $name }
+dashboard-push-targets = { $count ->
+    [one] a { $count } dispositivo
+   *[other] a { $count } dispositivos
+}
+dashboard-last-push = Último envío
+dashboard-push-pending = leyendo la configuración
+dashboard-push-applied = { $count ->
+    [one] aplicado a { $count } tecla
+   *[other] aplicado a { $count } teclas
+}
+dashboard-push-applied-partly = aplicado a { $keys } de { $count } teclas
+push-connection-lost = se perdió la conexión
+push-invalid-for-device = los ajustes no son válidos para este dispositivo
+push-backup-error = no se pudo hacer una copia de seguridad de la configuración: { $error }
+push-no-config = El dispositivo seleccionado no tiene configuración para enviar
+push-invalid = La configuración tiene ajustes no válidos y no se envió
+push-done = { $count ->
+    [one] Configuración enviada a { $count } dispositivo
+   *[other] Configuración enviada a { $count } dispositivos
+}
+push-waiting = Configuración enviada a { $applied } de { $count } dispositivos, esperando al resto
+push-partial = Configuración enviada a { $applied } de { $count } dispositivos
+
+# perfiles automáticos
+autoswitch-title = Perfiles automáticos
+autoswitch-enable = Cambiar de perfil según la aplicación enfocada
+autoswitch-unsupported = El cambio automático de perfil no es compatible con este escritorio
+autoswitch-focus-error = No se pudo averiguar qué aplicación está enfocada
+autoswitch-save-error = No se pudieron guardar las reglas de cambio de perfil
+autoswitch-detector = Detectando con { $detector }
+autoswitch-undetectable = No se puede detectar la aplicación enfocada
+autoswitch-focused = Enfocada: { $process } ({ $title })
+autoswitch-focused-nothing = Enfocada: nada
+autoswitch-nothing = nada
+autoswitch-override = Control manual
+autoswitch-override-hint = mantener el perfil actual sin importar lo que esté enfocado
+autoswitch-match = Comparar
+autoswitch-contains = Contiene
+autoswitch-profile = Perfil
+autoswitch-match-process = Proceso
+autoswitch-match-title = Título
+autoswitch-remove = Quitar la regla
+autoswitch-remove-hint = quitar
+autoswitch-add-rule = Añadir regla
+autoswitch-add-focused = Añadir { $process }
+autoswitch-fallback = Si no
+autoswitch-keep-profile = mantener el perfil
+autoswitch-log = Cambios
+autoswitch-log-empty = Aún no hay cambios
+autoswitch-not-applied = no se pudo aplicar el perfil
+
+# importar y exportar
+interop-title = Importar / Exportar
+interop-format = Formato
+interop-format-commands = Comandos serie
+interop-format-json = JSON de Crustility
+interop-open = Abrir
+interop-save = Guardar
+interop-text-hint = Abre un archivo o pega su contenido
+interop-export = Exportar dispositivo
+interop-export-hint = escribir los ajustes del dispositivo seleccionado
+interop-import-device = Importar al dispositivo
+interop-import-device-hint = cargar los ajustes en el dispositivo seleccionado, aplica para escribirlos
+interop-profile-name = Nombre del perfil
+interop-import-profile = Importar como perfil
+interop-saved = { $path } guardado
+interop-save-error = No se pudo guardar { $path }
+interop-open-error = No se pudo abrir { $path }
+interop-exported = { $count ->
+    [one] Exportado sin { $count } ajuste que no se puede traducir
+   *[other] Exportado sin { $count } ajustes que no se pueden traducir
+}
+interop-imported = { $count ->
+    [one] Importado sin { $count } ajuste que no se puede traducir
+   *[other] Importado sin { $count } ajustes que no se pueden traducir
+}
+interop-export-report = Excluido de la exportación
+interop-import-report = Excluido de la importación
+interop-imported-profile = Perfil { $name } importado
+interop-import-error = No se pudo importar { $format }
+interop-key-count = La importación tiene { $import } teclas, el dispositivo { $device }
+interop-imported-device = { $count ->
+    [one] Ajustes de { $count } tecla importados, aplica para escribirlos en el dispositivo
+   *[other] Ajustes de { $count } teclas importados, aplica para escribirlos en el dispositivo
+}
+interop-all-translated = Todos los ajustes se tradujeron
+interop-setting = Ajuste
+interop-value = Valor
+interop-reason = Motivo
+interop-reason-digital-keys = las teclas digitales aún no tienen ajustes
+interop-reason-out-of-range = fuera del rango que admite el firmware
+interop-reason-not-a-setting = no es un ajuste
+interop-reason-not-a-key-setting = no es un ajuste de tecla
+interop-reason-past-key-count = { $count ->
+    [one] el archivo dice que solo hay { $count } tecla
+   *[other] el archivo dice que solo hay { $count } teclas
+}
+interop-reason-unknown-setting = ajuste desconocido
+interop-reason-invalid-value = no es un valor válido
+interop-reason-calibration = la calibración pertenece al interruptor y se queda en el dispositivo
+interop-reason-rapid-trigger-off = el Rapid Trigger está desactivado
+
+# permisos
+permission-title = Permiso denegado
+permission-denied = crustility no tiene permiso para abrir { $port }.
+permission-groups = Los puertos serie pertenecen a un grupo y solo sus miembros o el usuario con sesión iniciada y una regla udev adecuada pueden abrirlos.
+permission-in-group = Ya estás en el grupo { $group }, cierra sesión y vuelve a entrar si te añadieron hace poco.
+permission-join-group = Puedes añadirte al grupo { $group } y volver a iniciar sesión:
+permission-udev = O instala una regla udev que te dé acceso a este minipad:
+permission-rule = Regla
+permission-waiting = Esperando la autenticación
+permission-install = Instalar regla udev
+permission-retry = Reintentar
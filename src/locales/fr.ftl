@@ -0,0 +1,468 @@
+# options de touche
+select-key = Choisir une touche
+key-heading = Touche { $key }
+rapid-trigger = Rapid Trigger
+enable = Activer
+continuous = Continu
+up-sensitivity = mm sensibilité en montée
+down-sensitivity = mm sensibilité en descente
+hysteresis = Hystérésis
+hysteresis-upper = mm haut
+hysteresis-lower = mm bas
+hid = HID
+
+# barre de menu
+apply-config = Appliquer la configuration
+apply-config-hint = Écrire la configuration sur l'appareil
+invalid-settings = { $count ->
+    [one] { $count } réglage invalide, corrigez-le avant d'appliquer
+   *[other] { $count } réglages invalides, corrigez-les avant d'appliquer
+}
+menu-devices = Appareils
+menu-compare = Comparer
+menu-import-export = Importer / Exporter
+menu-auto-profiles = Profils automatiques
+menu-settings = Paramètres
+menu-share = Partager
+menu-presets = Modèles
+menu-log = Journal
+menu-log-unread = Journal ({ $count })
+polling-throttled = l'appareil ne suit pas, la fréquence d'interrogation a été réduite
+polling-rate-error = Impossible de changer la fréquence d'interrogation
+
+# confirmation
+confirm = Confirmer
+confirm-apply = Écrire la configuration sur l'appareil ?
+confirm-delete-profile = Supprimer le profil { $name } ?
+yes = Oui
+no = Non
+
+# thème
+menu-theme = Thème
+reload-themes = Recharger les thèmes
+reload-themes-hint = charge les palettes toml de { $dir }
+
+# port série
+menu-port = Port
+baud-rate = Débit en bauds
+flow-control = Contrôle de flux
+parity = Parité
+data-bits = Bits de données
+stop-bits = Bits d'arrêt
+flow-control-none = Aucun
+flow-control-software = Logiciel
+flow-control-hardware = Matériel
+parity-none = Aucune
+parity-odd = Impaire
+parity-even = Paire
+timeout = Délai
+reset-defaults = Rétablir les valeurs par défaut
+serial-settings-error = Impossible de changer les réglages du port série
+
+# appareils
+all-devices = Tous les appareils
+select-device = <Choisir un appareil>
+disconnected = <Déconnecté>
+
+# visualisation
+actuation = Activation
+release = Relâchement
+threshold-keyboard-hint = { $name } { $value }, déplacer avec les flèches
+key-pressed = enfoncée
+key-released = relâchée
+key-description = Touche { $key }, { $state }, activation { $actuation }, relâchement { $release }
+threshold-description = { $name } en mm
+
+# disposition
+menu-layout = Disposition
+file = Fichier
+load = Charger
+save = Enregistrer
+use-default-layout = Utiliser la disposition par défaut
+reset-view = Réinitialiser la vue
+layout-store-error = Impossible d'enregistrer la disposition
+layout-load-error = Impossible de charger la disposition
+layout-save-error = Impossible d'enregistrer la disposition
+layout-saved = Disposition enregistrée dans { $path }
+
+# profils
+profile = Profil
+profile-named = Profil : { $name }
+no-profiles = Aucun profil enregistré
+delete = supprimer
+delete-profile = Supprimer le profil { $name }
+profile-name = Nom
+profile-load-error = Impossible de charger le profil { $name }
+profile-key-count = Le profil { $name } a { $profile } touches, l'appareil { $device }
+profile-loaded = Profil { $name } chargé dans { $count } touches
+profile-saved = Profil { $name } enregistré
+profile-save-error = Impossible d'enregistrer le profil { $name }
+profile-delete-error = Impossible de supprimer le profil { $name }
+
+# paramètres
+settings = Paramètres
+settings-save-error = Impossible d'enregistrer les paramètres
+language = Langue
+ui-scale = Échelle de l'interface
+smaller = Plus petit
+larger = Plus grand
+theme = Thème
+polling-rate = Fréquence d'interrogation
+window = Fenêtre
+remember-window = Mémoriser la taille et la position
+confirm-before = Confirmer
+confirm-before-apply = Avant d'appliquer une configuration
+confirm-before-delete = Avant de supprimer un profil
+control-server = Serveur de contrôle
+control-server-enable = Permettre à d'autres outils de contrôler crustility
+control-server-hint = json-rpc, un message par ligne
+control-server-token = Les clients doivent d'abord appeler auth avec le jeton de { $path }
+localhost-port = Port localhost
+key-overlay = Overlay des touches
+key-overlay-enable = Servir un overlay pour le streaming
+port = Port
+rate = Fréquence
+overlay-viewers = { $count } connectés
+device-changes = Modifications de l'appareil
+resync-every = Vérifier toutes les
+resync-on-focus = Quand la fenêtre reçoit le focus
+
+# modifications faites en dehors de crustility
+resync-title = { $device } a été modifié
+resync-description = Les réglages de l'appareil ne sont pas ceux que crustility a lus ou écrits en dernier, un autre outil ou une commande du firmware les a modifiés.
+resync-edited = { $count ->
+    [one] Vous avez { $count } modification non appliquée.
+   *[other] Vous avez { $count } modifications non appliquées.
+}
+resync-key = Touche
+resync-setting = Réglage
+resync-known = Connu
+resync-device = Appareil
+resync-yours = Les vôtres
+resync-reload = Recharger
+resync-reload-hint = utiliser les réglages de l'appareil, vos modifications sont perdues
+resync-merge = Fusionner
+resync-merge-hint = reprendre les modifications de l'appareil, garder les vôtres là où les deux ont changé
+resync-overwrite = Écraser
+resync-overwrite-hint = écrire à nouveau votre configuration sur l'appareil
+resync-reloaded = La configuration de { $device } a été rechargée
+resync-merged = { $count ->
+    [one] { $count } réglage fusionné depuis { $device }
+   *[other] { $count } réglages fusionnés depuis { $device }
+}
+resync-conflicts = { $count ->
+    [one] { $count } réglage a été modifié des deux côtés, votre modification a été gardée
+   *[other] { $count } réglages ont été modifiés des deux côtés, vos modifications ont été gardées
+}
+resync-error = Impossible de vérifier les modifications de l'appareil
+
+# sauvegardes et réinitialisation
+menu-backups = Sauvegardes
+backups-browse = Restaurer une sauvegarde…
+factory-reset = Réinitialiser…
+factory-reset-hint = restaurer les valeurs du firmware, une sauvegarde est faite avant
+confirm-reset = Réinitialiser l'appareil aux valeurs du firmware ? Une sauvegarde de ses réglages est conservée.
+factory-reset-done = { $device } a été réinitialisé
+factory-reset-error = Impossible de réinitialiser l'appareil
+backup-error = Impossible de sauvegarder la configuration, rien n'a été modifié
+backups = Sauvegardes de { $device }
+no-backups = Pas encore de sauvegarde, une est faite avant chaque application et réinitialisation
+backup-taken = Faite le
+backup-reason = Avant
+backup-keys = Touches
+backup-reason-apply = application
+backup-reason-reset = réinitialisation
+backup-load = Charger
+backup-load-hint = charger les réglages pour les modifier avant de les appliquer
+backup-restore = Restaurer
+backup-restore-hint = charger les réglages et les écrire sur l'appareil
+delete-backup = Supprimer la sauvegarde du { $time }
+backup-loaded = La sauvegarde du { $time } a été chargée dans { $count } touches
+backup-key-count = La sauvegarde a { $backup } touches, l'appareil { $device }
+backup-delete-error = Impossible de supprimer la sauvegarde
+backups-list-error = Impossible de lire les sauvegardes
+
+# appliquer et lire les configurations
+apply-invalid = La configuration a des réglages invalides et n'a pas été appliquée
+apply-error = Impossible d'appliquer la configuration
+config-applied = { $count ->
+    [one] Configuration appliquée à { $count } touche
+   *[other] Configuration appliquée à { $count } touches
+}
+config-read = { $count ->
+    [one] Configuration de { $count } touche lue depuis { $device }
+   *[other] Configuration de { $count } touches lue depuis { $device }
+}
+config-read-error = Impossible de lire la configuration
+device-read-error = Impossible de lire la configuration de { $device }
+device-connect-error = Impossible de se connecter à { $device }
+device-connection-lost = Connexion perdue avec { $device }
+device-throttled = { $device } ne suit pas à { $rate } Hz, la fréquence d'interrogation a été réduite
+sensors-start-error = Impossible de commencer la lecture des capteurs
+sensors-stop-error = Impossible d'arrêter la lecture des capteurs
+profile-save-first = Enregistrez d'abord un profil depuis le menu des profils
+theme-load-error = Impossible de charger le thème { $path }
+theme-missing = Le thème { $name } n'existe plus, { $default } est utilisé
+overlay-start-error = Impossible de démarrer le serveur d'overlay sur le port { $port }
+control-server-start-error = Impossible de démarrer le serveur de contrôle sur { $endpoint }
+
+# noms des réglages
+setting-key = Touche
+setting-rapid-trigger = Rapid Trigger
+setting-continuous = Continu
+setting-up-sensitivity = Sensibilité en montée
+setting-down-sensitivity = Sensibilité en descente
+setting-hysteresis-lower = Hystérésis basse
+setting-hysteresis-upper = Hystérésis haute
+setting-key-code = Code de touche
+setting-hid = HID
+value-on = activé
+value-off = désactivé
+value-present = présente
+
+# invalid settings
+problem-out-of-range = doit être entre { $min } et { $max }
+problem-too-close = doit être au moins { $tolerance } au-dessus de l'hystérésis basse
+
+# assignation des touches
+keybind-bind = Assigner
+keybind-capturing = Appuyez sur une touche…
+keybind-bind-hint = assigner la prochaine touche appuyée
+keybind-search = Rechercher
+keycode-unbound = Non assignée
+keycode-characters = Caractères (disposition us)
+keycode-modifiers = Modificateurs
+keycode-usages = Touches (indépendantes de la disposition)
+
+# notifications
+notify-dismiss = Fermer
+notify-details = Détails
+notify-log = Journal
+notify-clear = Vider
+notify-copy = Copier
+notify-empty = Rien à signaler
+notify-caused-by = causé par : { $error }
+age-seconds = il y a { $count } s
+age-minutes = il y a { $count } min
+age-hours = il y a { $count } h
+
+# partage
+share-copy = Copier le texte de partage
+share-copy-hint = copier les réglages des touches en texte à coller dans un chat
+share-copied = Texte de partage copié dans le presse-papiers
+share-encode-error = Impossible de créer le texte de partage
+share-decode-error = Impossible de lire le texte de partage
+share-preview = Aperçu
+share-title = Configuration partagée
+share-key-count = La configuration partagée est pour { $shared } touches, cet appareil en a { $device }
+share-invalid = { $count ->
+    [one] { $count } réglage est invalide
+   *[other] { $count } réglages sont invalides
+}
+share-same = La configuration partagée est la même que celle de l'appareil
+share-key = Touche
+share-setting = Réglage
+share-device = Appareil
+share-shared = Partagé
+share-apply = Appliquer
+share-apply-hint = charger les réglages et les écrire sur l'appareil
+share-load = Charger
+share-load-hint = charger les réglages pour les modifier avant de les appliquer
+share-loaded = { $count ->
+    [one] Réglages partagés de { $count } touche chargés
+   *[other] Réglages partagés de { $count } touches chargés
+}
+
+# préréglages
+preset-title = Préréglage : { $name }
+preset-osu-aggressive = osu! agressif
+preset-osu-aggressive-description = Rapid Trigger continu avec une activation peu profonde pour les streams rapides
+preset-osu-balanced = osu! équilibré
+preset-osu-balanced-description = Rapid Trigger qui se réinitialise au-dessus du point d'activation, tolérant quand vous posez les doigts sur les touches
+preset-gaming = Jeu
+preset-gaming-description = Rapid Trigger avec une activation moyenne, pour les touches de déplacement
+preset-typing = Frappe
+preset-typing-description = Sans Rapid Trigger et avec une activation profonde, comme un interrupteur classique
+preset-anti-chatter = Anti-rebond prudent
+preset-anti-chatter-description = Sans Rapid Trigger et avec un large écart de relâchement, pour les capteurs bruités ou les touches qui s'activent deux fois
+preset-actuation = mm d'activation
+preset-sensitivity = mm de sensibilité rapid trigger
+preset-keys = Touches
+preset-all = Toutes
+preset-none = Aucune
+preset-preview = Aperçu sur les touches
+preset-apply = { $count ->
+    [one] Appliquer à { $count } touche
+   *[other] Appliquer à { $count } touches
+}
+preset-cancel = Annuler
+preset-applied = { $count ->
+    [one] { $count } touche réglée sur le préréglage { $name }, appliquez pour l'écrire sur l'appareil
+   *[other] { $count } touches réglées sur le préréglage { $name }, appliquez pour les écrire sur l'appareil
+}
+
+# comparer
+compare-title = Comparer
+compare-profile = profil { $name }
+compare-disconnected = l'appareil est déconnecté
+compare-reading = lecture de la configuration…
+compare-swap = Échanger les configurations
+compare-swap-hint = échanger
+compare-pick = Choisissez deux configurations à comparer
+compare-same = Les configurations sont identiques
+compare-key = Touche
+compare-setting = Réglage
+compare-left = Gauche
+compare-right = Droite
+compare-select = <Choisir>
+compare-select-all = Tout sélectionner
+compare-copy = Copier { $count } gauche → droite
+compare-copied-device = { $count ->
+    [one] { $count } réglage copié vers { $target }, appliquez pour l'écrire sur l'appareil
+   *[other] { $count } réglages copiés vers { $target }, appliquez pour les écrire sur l'appareil
+}
+compare-copied-profile = { $count ->
+    [one] { $count } réglage copié dans le profil { $name }
+   *[other] { $count } réglages copiés dans le profil { $name }
+}
+compare-skipped = { $count ->
+    [one] { $count } réglage n'a pas été copié, sa touche manque ou a le rapid trigger désactivé
+   *[other] { $count } réglages n'ont pas été copiés, leur touche manque ou a le rapid trigger désactivé
+}
+
+# fenêtre des appareils
+dashboard-title = Appareils
+dashboard-rescan = Rechercher à nouveau
+dashboard-read-all = Lire toutes les configurations
+dashboard-read-all-hint = se connecter à chaque appareil pour afficher ses touches et son firmware
+dashboard-device = Appareil
+dashboard-port = Port
+dashboard-status = État
+dashboard-keys = Touches
+dashboard-firmware = Firmware
+dashboard-target-hint = envoyer à cet appareil
+dashboard-usb = usb { $id }
+dashboard-manufacturer = par { $manufacturer }
+dashboard-serial-number = numéro de série { $serial_number }
+dashboard-error = Erreur
+dashboard-polling = Interrogation à { $rate } Hz
+dashboard-connected = Connecté
+dashboard-not-connected = Non connecté
+dashboard-keys-hint = touches à effet hall + numériques
+dashboard-firmware-unknown = inconnu
+dashboard-push = Envoyer
+dashboard-push-selected = configuration de l'appareil sélectionné
+dashboard-push-profile = profil { $name }
+dashboard-push-targets = { $count ->
+    [one] à { $count } appareil
+   *[other] à { $count } appareils
+}
+dashboard-last-push = Dernier envoi
+dashboard-push-pending = lecture de la configuration
+dashboard-push-applied = { $count ->
+    [one] appliqué à { $count } touche
+   *[other] appliqué à { $count } touches
+}
+dashboard-push-applied-partly = appliqué à { $keys } touches sur { $count }
+push-connection-lost = connexion perdue
+push-invalid-for-device = les réglages sont invalides pour cet appareil
+push-backup-error = impossible de sauvegarder la configuration : { $error }
+push-no-config = L'appareil sélectionné n'a pas de configuration à envoyer
+push-invalid = La configuration a des réglages invalides et n'a pas été envoyée
+push-done = { $count ->
+    [one] Configuration envoyée à { $count } appareil
+   *[other] Configuration envoyée à { $count } appareils
+}
+push-waiting = Configuration envoyée à { $applied } appareils sur { $count }, en attente des autres
+push-partial = Configuration envoyée à { $applied } appareils sur { $count }
+
+# profils automatiques
+autoswitch-title = Profils automatiques
+autoswitch-enable = Changer de profil selon l'application active
+autoswitch-unsupported = Le changement automatique de profil n'est pas pris en charge sur ce bureau
+autoswitch-focus-error = Impossible de savoir quelle application est active
+autoswitch-save-error = Impossible d'enregistrer les règles de changement de profil
+autoswitch-detector = Détection avec { $detector }
+autoswitch-undetectable = L'application active ne peut pas être détectée
+autoswitch-focused = Active : { $process } ({ $title })
+autoswitch-focused-nothing = Active : rien
+autoswitch-nothing = rien
+autoswitch-override = Contrôle manuel
+autoswitch-override-hint = garder le profil actuel quelle que soit l'application active
+autoswitch-match = Critère
+autoswitch-contains = Contient
+autoswitch-profile = Profil
+autoswitch-match-process = Processus
+autoswitch-match-title = Titre
+autoswitch-remove = Supprimer la règle
+autoswitch-remove-hint = supprimer
+autoswitch-add-rule = Ajouter une règle
+autoswitch-add-focused = Ajouter { $process }
+autoswitch-fallback = Sinon
+autoswitch-keep-profile = garder le profil
+autoswitch-log = Changements
+autoswitch-log-empty = Aucun changement pour l'instant
+autoswitch-not-applied = le profil n'a pas pu être appliqué
+
+# import et export
+interop-title = Importer / Exporter
+interop-format = Format
+interop-format-commands = Commandes série
+interop-format-json = JSON Crustility
+interop-open = Ouvrir
+interop-save = Enregistrer
+interop-text-hint = Ouvrez un fichier ou collez son contenu
+interop-export = Exporter l'appareil
+interop-export-hint = écrire les réglages de l'appareil sélectionné
+interop-import-device = Importer dans l'appareil
+interop-import-device-hint = charger les réglages dans l'appareil sélectionné, appliquez pour les écrire
+interop-profile-name = Nom du profil
+interop-import-profile = Importer comme profil
+interop-saved = { $path } enregistré
+interop-save-error = Impossible d'enregistrer { $path }
+interop-open-error = Impossible d'ouvrir { $path }
+interop-exported = { $count ->
+    [one] Exporté sans { $count } réglage qui ne se traduit pas
+   *[other] Exporté sans { $count } réglages qui ne se traduisent pas
+}
+interop-imported = { $count ->
+    [one] Importé sans { $count } réglage qui ne se traduit pas
+   *[other] Importé sans { $count } réglages qui ne se traduisent pas
+}
+interop-export-report = Laissé de côté à l'export
+interop-import-report = Laissé de côté à l'import
+interop-imported-profile = Profil { $name } importé
+interop-import-error = Impossible d'importer le { $format }
+interop-key-count = L'import a { $import } touches, l'appareil { $device }
+interop-imported-device = { $count ->
+    [one] Réglages de { $count } touche importés, appliquez pour les écrire sur l'appareil
+   *[other] Réglages de { $count } touches importés, appliquez pour les écrire sur l'appareil
+}
+interop-all-translated = Tous les réglages ont été traduits
+interop-setting = Réglage
+interop-value = Valeur
+interop-reason = Raison
+interop-reason-digital-keys = les touches numériques n'ont pas encore de réglages
+interop-reason-out-of-range = hors de la plage acceptée par le firmware
+interop-reason-not-a-setting = pas un réglage
+interop-reason-not-a-key-setting = pas un réglage de touche
+interop-reason-past-key-count = { $count ->
+    [one] le fichier indique seulement { $count } touche
+   *[other] le fichier indique seulement { $count } touches
+}
+interop-reason-unknown-setting = réglage inconnu
+interop-reason-invalid-value = valeur invalide
+interop-reason-calibration = la calibration appartient à l'interrupteur et reste sur l'appareil
+interop-reason-rapid-trigger-off = le Rapid Trigger est désactivé
+
+# permissions
+permission-title = Permission refusée
+permission-denied = crustility n'a pas le droit d'ouvrir { $port }.
+permission-groups = Les ports série appartiennent à un groupe et seuls ses membres ou l'utilisateur connecté avec une règle udev correspondante peuvent les ouvrir.
+permission-in-group = Vous êtes déjà dans le groupe { $group }, déconnectez-vous puis reconnectez-vous si vous y avez été ajouté récemment.
+permission-join-group = Vous pouvez vous ajouter au groupe { $group } et vous reconnecter :
+permission-udev = Ou installez une règle udev qui vous donne accès à ce minipad :
+permission-rule = Règle
+permission-waiting = En attente d'authentification
+permission-install = Installer la règle udev
+permission-retry = Réessayer
@@ -1,8 +1,35 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
+#[cfg(feature = "gui")]
 mod app;
-mod config;
-mod device;
+#[cfg(feature = "gui")]
+mod focus;
+#[cfg(feature = "gui")]
+mod overlay;
+#[cfg(feature = "gui")]
+mod rpc;
+#[cfg(feature = "gui")]
+mod rules;
 
+#[cfg(feature = "gui")]
 fn main() {
     app::run().unwrap();
 }
+
+/// Without the gui only list what is connected
+#[cfg(not(feature = "gui"))]
+fn main() {
+    env_logger::init();
+    let mut devices = crustility_core::device::Devices::default();
+    devices.scan();
+    for handle in devices.iter() {
+        let Some(device) = devices.get(handle) else {
+            continue;
+        };
+        println!(
+            "{} {} {}",
+            handle.identity(),
+            device.port_name(),
+            device.name()
+        );
+    }
+}
@@ -0,0 +1,311 @@
+use std::io::{Read, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use thiserror::Error;
+use tungstenite::{Message, WebSocket};
+
+pub const DEFAULT_PORT: u16 = 7342;
+/// updates per second sent to the overlay
+pub const DEFAULT_RATE: u32 = 60;
+pub const MIN_RATE: u32 = 1;
+pub const MAX_RATE: u32 = 240;
+
+/// how often the accept loop checks if the server was stopped
+const ACCEPT_INTERVAL: Duration = Duration::from_millis(100);
+/// how long a client may take to send its request headers
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+const MAX_REQUEST_LEN: usize = 8192;
+
+const PAGE: &str = include_str!("overlay.html");
+
+/// State of one key sent to the overlay
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct KeyState {
+    /// name of the key code
+    pub label: String,
+    /// distance from the bottom in mm
+    pub position: f64,
+    pub pressed: bool,
+    /// lower hysteresis in mm
+    pub actuation: f64,
+    /// upper hysteresis in mm
+    pub release: f64,
+}
+
+/// Everything the overlay shows, sent as json
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Frame {
+    pub device: String,
+    /// in mm
+    pub travel: f64,
+    pub keys: Vec<KeyState>,
+}
+
+/// Serves the overlay page and broadcasts key states to it over a websocket
+///
+/// Only listens on localhost, the server stops when dropped
+pub struct Server {
+    port: u16,
+    interval: Duration,
+    last_sent: Option<Instant>,
+    clients: Arc<Mutex<Vec<Sender<Arc<str>>>>>,
+    stop: Arc<AtomicBool>,
+}
+
+impl Server {
+    pub fn start(port: u16, rate: u32) -> Result<Self, Error> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))?;
+        listener.set_nonblocking(true)?;
+        let clients = Arc::new(Mutex::new(Vec::new()));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let thread_clients = clients.clone();
+        let thread_stop = stop.clone();
+        std::thread::spawn(move || accept(&listener, &thread_clients, &thread_stop));
+
+        log::info!("overlay server listening on localhost:{port}");
+        let mut server = Self {
+            port,
+            interval: Duration::ZERO,
+            last_sent: None,
+            clients,
+            stop,
+        };
+        server.set_rate(rate);
+        Ok(server)
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    pub fn set_rate(&mut self, rate: u32) {
+        self.interval = Duration::from_secs(1) / rate.clamp(MIN_RATE, MAX_RATE);
+    }
+
+    /// Number of connected overlays
+    pub fn clients(&self) -> usize {
+        self.clients.lock().map_or(0, |clients| clients.len())
+    }
+
+    /// Send a frame to every overlay, frames faster than the update rate are dropped
+    pub fn publish(&mut self, frame: &Frame) {
+        if self
+            .last_sent
+            .is_some_and(|last| last.elapsed() < self.interval)
+        {
+            return;
+        }
+        let Ok(mut clients) = self.clients.lock() else {
+            return;
+        };
+        if clients.is_empty() {
+            return;
+        }
+        self.last_sent = Some(Instant::now());
+        let Ok(message) = serde_json::to_string(frame) else {
+            return;
+        };
+        let message: Arc<str> = message.into();
+        clients.retain(|client| client.send(message.clone()).is_ok());
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        // disconnects the websocket threads
+        if let Ok(mut clients) = self.clients.lock() {
+            clients.clear();
+        }
+    }
+}
+
+fn accept(listener: &TcpListener, clients: &Arc<Mutex<Vec<Sender<Arc<str>>>>>, stop: &AtomicBool) {
+    while !stop.load(Ordering::Relaxed) {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                let clients = clients.clone();
+                std::thread::spawn(move || {
+                    if let Err(e) = serve(stream, &clients) {
+                        log::debug!("overlay client: {e}");
+                    }
+                });
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                std::thread::sleep(ACCEPT_INTERVAL)
+            }
+            Err(e) => log::warn!("could not accept an overlay client: {e}"),
+        }
+    }
+}
+
+/// Answer one connection, either with the page or as a websocket
+fn serve(stream: TcpStream, clients: &Mutex<Vec<Sender<Arc<str>>>>) -> Result<(), Error> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+
+    // peek so the websocket handshake still sees the whole request
+    let mut buf = vec![0; MAX_REQUEST_LEN];
+    let deadline = Instant::now() + REQUEST_TIMEOUT;
+    let head = loop {
+        let len = stream.peek(&mut buf)?;
+        let data = String::from_utf8_lossy(&buf[..len]).into_owned();
+        if let Some(end) = data.find("\r\n\r\n") {
+            break data[..end].to_string();
+        }
+        if len == 0 || len == buf.len() || Instant::now() >= deadline {
+            return Err(Error::BadRequest);
+        }
+        std::thread::sleep(Duration::from_millis(5));
+    };
+
+    let is_websocket =
+        header(&head, "upgrade").is_some_and(|value| value.eq_ignore_ascii_case("websocket"));
+    if is_websocket {
+        // any web page could open a websocket to localhost, only the overlay page may
+        let port = stream.local_addr()?.port();
+        if let Some(origin) = header(&head, "origin").filter(|o| !allowed_origin(o, port)) {
+            let origin = origin.to_string();
+            respond(stream, &head, "403 Forbidden", "text/plain", "forbidden")?;
+            return Err(Error::Origin(origin));
+        }
+        let socket = tungstenite::accept(stream).map_err(|_| Error::Handshake)?;
+        let (sender, receiver) = std::sync::mpsc::channel();
+        clients.lock().map_err(|_| Error::Closed)?.push(sender);
+        broadcast(socket, &receiver)
+    } else {
+        page(stream, &head)
+    }
+}
+
+/// Value of a header of the request head, the first one if it is repeated
+fn header<'a>(head: &'a str, name: &str) -> Option<&'a str> {
+    head.lines().skip(1).find_map(|line| {
+        let (header, value) = line.split_once(':')?;
+        header
+            .trim()
+            .eq_ignore_ascii_case(name)
+            .then_some(value.trim())
+    })
+}
+
+/// If a page from `origin` may connect, which is only the overlay page itself
+fn allowed_origin(origin: &str, port: u16) -> bool {
+    ["localhost", "127.0.0.1"]
+        .iter()
+        .any(|host| origin == format!("http://{host}:{port}"))
+}
+
+fn page(stream: TcpStream, head: &str) -> Result<(), Error> {
+    let path = head.split_whitespace().nth(1).unwrap_or_default();
+    match path.split('?').next() {
+        Some("/" | "/index.html") => {
+            respond(stream, head, "200 OK", "text/html; charset=utf-8", PAGE)
+        }
+        _ => respond(stream, head, "404 Not Found", "text/plain", "not found"),
+    }
+}
+
+fn respond(
+    mut stream: TcpStream,
+    head: &str,
+    status: &str,
+    content_type: &str,
+    body: &str,
+) -> Result<(), Error> {
+    // the request was only peeked at, take it off the socket
+    let mut request = vec![0; head.len() + 4];
+    stream.read_exact(&mut request)?;
+
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\n\
+         Cache-Control: no-store\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    Ok(())
+}
+
+/// Send frames to a websocket until either side goes away
+fn broadcast(mut socket: WebSocket<TcpStream>, frames: &Receiver<Arc<str>>) -> Result<(), Error> {
+    // short reads so pings and close frames get handled between frames
+    socket
+        .get_ref()
+        .set_read_timeout(Some(Duration::from_millis(1)))?;
+    loop {
+        let frame = match frames.recv_timeout(Duration::from_millis(100)) {
+            Ok(frame) => Some(frame),
+            Err(RecvTimeoutError::Timeout) => None,
+            Err(RecvTimeoutError::Disconnected) => {
+                let _ = socket.close(None);
+                return Ok(());
+            }
+        };
+        // only the newest frame matters when the client falls behind
+        if let Some(frame) = frames.try_iter().last().or(frame) {
+            socket.send(Message::Text(frame.to_string()))?;
+        }
+
+        match socket.read() {
+            Ok(Message::Close(_)) => return Ok(()),
+            Ok(_) => (),
+            Err(tungstenite::Error::Io(e))
+                if matches!(
+                    e.kind(),
+                    std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut
+                ) => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("overlay server io")]
+    Io(#[from] std::io::Error),
+    #[error("invalid http request")]
+    BadRequest,
+    #[error("websocket handshake failed")]
+    Handshake,
+    #[error("refused a websocket from {0}")]
+    Origin(String),
+    #[error(transparent)]
+    WebSocket(Box<tungstenite::Error>),
+    #[error("the overlay server was stopped")]
+    Closed,
+}
+
+impl From<tungstenite::Error> for Error {
+    fn from(e: tungstenite::Error) -> Self {
+        Error::WebSocket(Box::new(e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn origins() {
+        assert!(allowed_origin("http://localhost:7342", 7342));
+        assert!(allowed_origin("http://127.0.0.1:7342", 7342));
+        assert!(!allowed_origin("http://localhost:8080", 7342));
+        assert!(!allowed_origin("https://localhost:7342", 7342));
+        assert!(!allowed_origin("http://localhost:7342.evil.example", 7342));
+        assert!(!allowed_origin("null", 7342));
+    }
+
+    #[test]
+    fn headers() {
+        let head = "GET / HTTP/1.1\r\nHost: localhost\r\nUPGRADE: websocket\r\nOrigin:  null ";
+        assert_eq!(header(head, "upgrade"), Some("websocket"));
+        assert_eq!(header(head, "origin"), Some("null"));
+        assert_eq!(header(head, "cookie"), None);
+    }
+}
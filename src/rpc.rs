@@ -0,0 +1,541 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, TcpListener};
+#[cfg(unix)]
+use std::os::unix::net::UnixListener;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{Receiver, Sender};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crustility_core::storage;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use thiserror::Error;
+
+/// how often the accept loop checks if the server was stopped
+const ACCEPT_INTERVAL: Duration = Duration::from_millis(100);
+pub const DEFAULT_PORT: u16 = 7341;
+/// file in the config directory the token tcp clients authenticate with is written to
+const TOKEN_FILE: &str = "rpc-token";
+
+// error codes of the json-rpc spec
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+/// the request was fine, the device or profile it is about wasn't
+pub const SERVER_ERROR: i64 = -32000;
+
+/// Where the server listens
+#[derive(Debug, Clone, PartialEq)]
+pub enum Endpoint {
+    #[cfg(unix)]
+    Socket(PathBuf),
+    /// port on localhost
+    Tcp(u16),
+}
+
+impl Endpoint {
+    /// Unix socket in the runtime directory, localhost where there are none
+    pub fn default_for_platform() -> Self {
+        #[cfg(unix)]
+        {
+            let dir = dirs::runtime_dir().unwrap_or_else(std::env::temp_dir);
+            Endpoint::Socket(dir.join("crustility.sock"))
+        }
+        #[cfg(not(unix))]
+        Endpoint::Tcp(DEFAULT_PORT)
+    }
+}
+
+impl std::fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            #[cfg(unix)]
+            Endpoint::Socket(path) => write!(f, "{}", path.display()),
+            Endpoint::Tcp(port) => write!(f, "localhost:{port}"),
+        }
+    }
+}
+
+/// Error object of a response
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+impl RpcError {
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct AuthParams {
+    token: String,
+}
+
+/// Incoming message, only the fields crustility uses
+#[derive(Debug, Deserialize)]
+struct Message {
+    jsonrpc: String,
+    /// missing for notifications, which get no response
+    #[serde(default)]
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+/// Sending half of a client connection
+///
+/// Messages are queued for a writer thread so the ui never waits on a slow client
+#[derive(Debug, Clone)]
+pub struct Connection {
+    id: u64,
+    sender: Sender<String>,
+}
+
+impl PartialEq for Connection {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Connection {
+    /// Queue a message, returns `false` once the client is gone
+    fn send(&self, message: &Value) -> bool {
+        self.sender.send(message.to_string()).is_ok()
+    }
+
+    /// Send a notification, returns `false` once the client is gone
+    pub fn notify(&self, method: &str, params: Value) -> bool {
+        self.send(&json!({ "jsonrpc": "2.0", "method": method, "params": params }))
+    }
+}
+
+/// Call waiting to be answered by the ui thread
+#[derive(Debug)]
+pub struct Request {
+    pub connection: Connection,
+    /// `None` for notifications
+    id: Option<Value>,
+    pub method: String,
+    pub params: Value,
+}
+
+impl Request {
+    /// Deserialize the parameters into the type the method expects
+    pub fn params<T: serde::de::DeserializeOwned>(&self) -> Result<T, RpcError> {
+        let params = match &self.params {
+            Value::Null => json!({}),
+            params => params.clone(),
+        };
+        serde_json::from_value(params).map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string()))
+    }
+
+    pub fn reply(self, result: Result<Value, RpcError>) {
+        let Some(id) = self.id else {
+            return;
+        };
+        let response = match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(error) => json!({ "jsonrpc": "2.0", "id": id, "error": error }),
+        };
+        self.connection.send(&response);
+    }
+}
+
+enum Listener {
+    #[cfg(unix)]
+    Unix(UnixListener),
+    Tcp(TcpListener),
+}
+
+/// Local JSON-RPC 2.0 server for external tools, one json message per line
+///
+/// Requests are handed to the ui thread, which owns the devices, and answered from there.
+/// Any local process and web page can reach a tcp port, so over tcp the first call has to be
+/// `auth {token}` with the token from [`token_path`], which changes every time the server starts.
+/// The server stops when dropped
+pub struct Server {
+    endpoint: Endpoint,
+    requests: Receiver<Request>,
+    stop: Arc<AtomicBool>,
+}
+
+impl Server {
+    pub fn start(endpoint: Endpoint) -> Result<Self, Error> {
+        let listener = match &endpoint {
+            #[cfg(unix)]
+            Endpoint::Socket(path) => {
+                // a socket left behind by a crashed instance would make bind fail
+                if path.exists() {
+                    if std::os::unix::net::UnixStream::connect(path).is_ok() {
+                        return Err(Error::InUse(endpoint.to_string()));
+                    }
+                    std::fs::remove_file(path)?;
+                }
+                Listener::Unix(UnixListener::bind(path)?)
+            }
+            Endpoint::Tcp(port) => Listener::Tcp(TcpListener::bind((Ipv4Addr::LOCALHOST, *port))?),
+        };
+        let token = match &listener {
+            #[cfg(unix)]
+            Listener::Unix(_) => None,
+            Listener::Tcp(_) => Some(write_token()?),
+        };
+
+        let (sender, requests) = std::sync::mpsc::channel();
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = stop.clone();
+        std::thread::spawn(move || {
+            if let Err(e) = accept(listener, token, &sender, &thread_stop) {
+                log::error!("rpc server stopped: {e}");
+            }
+        });
+
+        log::info!("rpc server listening on {endpoint}");
+        Ok(Self {
+            endpoint,
+            requests,
+            stop,
+        })
+    }
+
+    pub fn endpoint(&self) -> &Endpoint {
+        &self.endpoint
+    }
+
+    pub fn try_recv(&self) -> Option<Request> {
+        self.requests.try_recv().ok()
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        #[cfg(unix)]
+        if let Endpoint::Socket(path) = &self.endpoint {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// File the token of the tcp server is written to
+pub fn token_path() -> Result<PathBuf, Error> {
+    Ok(storage::config_dir()?.join(TOKEN_FILE))
+}
+
+/// Make up a new token and write it where only the user can read it
+fn write_token() -> Result<String, Error> {
+    use std::hash::{BuildHasher, Hasher};
+
+    // every `RandomState` is keyed from the os random source, that is good enough here
+    let token = (0..2)
+        .map(|_| {
+            let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+            hasher.write_u128(
+                std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_nanos(),
+            );
+            format!("{:016x}", hasher.finish())
+        })
+        .collect::<String>();
+
+    let path = token_path()?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options.open(path)?.write_all(token.as_bytes())?;
+    Ok(token)
+}
+
+fn accept(
+    listener: Listener,
+    token: Option<String>,
+    requests: &Sender<Request>,
+    stop: &AtomicBool,
+) -> Result<(), Error> {
+    static NEXT_CONNECTION: AtomicU64 = AtomicU64::new(0);
+
+    // non blocking so the loop notices when the server is stopped
+    match &listener {
+        #[cfg(unix)]
+        Listener::Unix(listener) => listener.set_nonblocking(true)?,
+        Listener::Tcp(listener) => listener.set_nonblocking(true)?,
+    }
+    while !stop.load(Ordering::Relaxed) {
+        let id = NEXT_CONNECTION.fetch_add(1, Ordering::Relaxed);
+        let result = match &listener {
+            #[cfg(unix)]
+            Listener::Unix(listener) => listener.accept().and_then(|(stream, _)| {
+                stream.set_nonblocking(false)?;
+                spawn_connection(id, stream.try_clone()?, stream, None, requests);
+                Ok(())
+            }),
+            Listener::Tcp(listener) => listener.accept().and_then(|(stream, _)| {
+                stream.set_nonblocking(false)?;
+                spawn_connection(id, stream.try_clone()?, stream, token.clone(), requests);
+                Ok(())
+            }),
+        };
+        match result {
+            Ok(()) => (),
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                std::thread::sleep(ACCEPT_INTERVAL)
+            }
+            Err(e) => log::warn!("could not accept an rpc client: {e}"),
+        }
+    }
+    Ok(())
+}
+
+fn spawn_connection(
+    id: u64,
+    reader: impl Read + Send + 'static,
+    mut writer: impl Write + Send + 'static,
+    token: Option<String>,
+    requests: &Sender<Request>,
+) {
+    let (sender, outgoing) = std::sync::mpsc::channel::<String>();
+    let connection = Connection { id, sender };
+    let requests = requests.clone();
+
+    // the connection closes once the reader returned and every queued message was written
+    std::thread::spawn(move || {
+        for message in outgoing {
+            if writeln!(writer, "{message}").is_err() {
+                return;
+            }
+        }
+    });
+    std::thread::spawn(move || read_requests(reader, connection, token.as_deref(), &requests));
+}
+
+/// Hand the requests of a connection to the ui until the client or the ui is gone
+///
+/// Whatever is not json-rpc, like a browser sending http to the port, ends the connection
+/// after one error, as does a wrong token
+fn read_requests(
+    reader: impl Read,
+    connection: Connection,
+    mut token: Option<&str>,
+    requests: &Sender<Request>,
+) {
+    for line in BufReader::new(reader).lines() {
+        let Ok(line) = line else {
+            return;
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let message = match parse(&line) {
+            Ok(message) => message,
+            Err(error) => {
+                connection.send(&json!({ "jsonrpc": "2.0", "id": null, "error": error }));
+                return;
+            }
+        };
+        let request = Request {
+            connection: connection.clone(),
+            id: message.id,
+            method: message.method,
+            params: message.params,
+        };
+
+        if let Some(expected) = token {
+            let authenticated = request.method == "auth"
+                && request
+                    .params::<AuthParams>()
+                    .is_ok_and(|params| params.token == expected);
+            if !authenticated {
+                request.reply(Err(RpcError::new(
+                    INVALID_REQUEST,
+                    "the first call has to be auth with the token of the server",
+                )));
+                return;
+            }
+            token = None;
+            request.reply(Ok(Value::Bool(true)));
+            continue;
+        }
+        // the ui is gone
+        if requests.send(request).is_err() {
+            return;
+        }
+    }
+}
+
+fn parse(line: &str) -> Result<Message, RpcError> {
+    let value: Value =
+        serde_json::from_str(line).map_err(|e| RpcError::new(PARSE_ERROR, e.to_string()))?;
+    let message: Message =
+        serde_json::from_value(value).map_err(|e| RpcError::new(INVALID_REQUEST, e.to_string()))?;
+    if message.jsonrpc != "2.0" {
+        return Err(RpcError::new(
+            INVALID_REQUEST,
+            "only json-rpc 2.0 is supported",
+        ));
+    }
+    Ok(message)
+}
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("rpc server io")]
+    Io(#[from] std::io::Error),
+    #[error("another instance is already listening on {0}")]
+    InUse(String),
+    #[error(transparent)]
+    Storage(#[from] storage::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Feed lines to a connection, returning what it wrote and the requests for the ui
+    fn read(input: &str, token: Option<&str>) -> (Vec<Value>, Vec<Request>) {
+        let (sender, outgoing) = std::sync::mpsc::channel();
+        let (request_sender, requests) = std::sync::mpsc::channel();
+        read_requests(
+            input.as_bytes(),
+            Connection { id: 0, sender },
+            token,
+            &request_sender,
+        );
+        drop(request_sender);
+        let requests = requests.into_iter().collect();
+        let written = outgoing
+            .try_iter()
+            .map(|message| serde_json::from_str(&message).unwrap())
+            .collect();
+        (written, requests)
+    }
+
+    fn error_code(message: &Value) -> Option<i64> {
+        message["error"]["code"].as_i64()
+    }
+
+    #[test]
+    fn parse_messages() {
+        let message = parse(r#"{"jsonrpc": "2.0", "id": 1, "method": "devices.list"}"#).unwrap();
+        assert_eq!(message.id, Some(json!(1)));
+        assert_eq!(message.method, "devices.list");
+        assert_eq!(message.params, Value::Null);
+
+        let message = parse(r#"{"jsonrpc": "2.0", "method": "a", "params": {"b": 1}}"#).unwrap();
+        assert_eq!(message.id, None);
+        assert_eq!(message.params, json!({ "b": 1 }));
+
+        let code = |line: &str| parse(line).unwrap_err().code;
+        assert_eq!(code("{"), PARSE_ERROR);
+        assert_eq!(code("GET / HTTP/1.1"), PARSE_ERROR);
+        assert_eq!(code(r#"{"jsonrpc": "2.0", "id": 1}"#), INVALID_REQUEST);
+        assert_eq!(code("[1]"), INVALID_REQUEST);
+        assert_eq!(
+            code(r#"{"jsonrpc": "1.0", "id": 1, "method": "a"}"#),
+            INVALID_REQUEST
+        );
+    }
+
+    #[test]
+    fn requests_reach_the_ui() {
+        let (written, requests) = read(
+            concat!(
+                r#"{"jsonrpc": "2.0", "id": 1, "method": "config.get", "params": {"device": "a"}}"#,
+                "\n\n",
+                r#"{"jsonrpc": "2.0", "method": "sensors.unsubscribe"}"#,
+                "\n",
+            ),
+            None,
+        );
+        assert!(written.is_empty());
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].method, "config.get");
+        assert_eq!(requests[0].params, json!({ "device": "a" }));
+        assert_eq!(requests[1].method, "sensors.unsubscribe");
+    }
+
+    #[test]
+    fn replies() {
+        let (sender, outgoing) = std::sync::mpsc::channel();
+        let connection = Connection { id: 0, sender };
+        let request = |id| Request {
+            connection: connection.clone(),
+            id,
+            method: "a".to_string(),
+            params: Value::Null,
+        };
+
+        request(Some(json!(7))).reply(Ok(json!("ok")));
+        request(Some(json!("x"))).reply(Err(RpcError::new(SERVER_ERROR, "no")));
+        // notifications are not answered
+        request(None).reply(Ok(json!("ok")));
+
+        let written = outgoing
+            .try_iter()
+            .map(|message| serde_json::from_str::<Value>(&message).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(written.len(), 2);
+        assert_eq!(
+            written[0],
+            json!({ "jsonrpc": "2.0", "id": 7, "result": "ok" })
+        );
+        assert_eq!(written[1]["id"], "x");
+        assert_eq!(error_code(&written[1]), Some(SERVER_ERROR));
+    }
+
+    #[test]
+    fn invalid_messages_close_the_connection() {
+        let (written, requests) = read(
+            concat!(
+                "POST / HTTP/1.1\n",
+                r#"{"jsonrpc": "2.0", "id": 1, "method": "config.set"}"#,
+                "\n",
+            ),
+            None,
+        );
+        assert_eq!(written.len(), 1);
+        assert_eq!(error_code(&written[0]), Some(PARSE_ERROR));
+        assert!(requests.is_empty());
+    }
+
+    #[test]
+    fn token() {
+        let call = r#"{"jsonrpc": "2.0", "id": 2, "method": "devices.list"}"#;
+
+        let (written, requests) = read(&format!("{call}\n"), Some("secret"));
+        assert_eq!(error_code(&written[0]), Some(INVALID_REQUEST));
+        assert!(requests.is_empty());
+
+        let wrong =
+            r#"{"jsonrpc": "2.0", "id": 1, "method": "auth", "params": {"token": "guess"}}"#;
+        let (written, requests) = read(&format!("{wrong}\n{call}\n"), Some("secret"));
+        assert_eq!(written.len(), 1);
+        assert_eq!(error_code(&written[0]), Some(INVALID_REQUEST));
+        assert!(requests.is_empty());
+
+        let auth =
+            r#"{"jsonrpc": "2.0", "id": 1, "method": "auth", "params": {"token": "secret"}}"#;
+        let (written, requests) = read(&format!("{auth}\n{call}\n"), Some("secret"));
+        assert_eq!(
+            written,
+            [json!({ "jsonrpc": "2.0", "id": 1, "result": true })]
+        );
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, "devices.list");
+    }
+}
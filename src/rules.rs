@@ -0,0 +1,131 @@
+use serde::{Deserialize, Serialize};
+
+use crate::focus::Focus;
+use crustility_core::storage;
+
+const RULES_FILE: &str = "autoswitch.toml";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MatchOn {
+    Process,
+    Title,
+}
+
+/// Profile to switch to while a matching application is focused
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rule {
+    #[serde(default = "enabled")]
+    pub enabled: bool,
+    pub matches: MatchOn,
+    /// matched case insensitively anywhere in the process name or title
+    pub pattern: String,
+    pub profile: String,
+}
+
+fn enabled() -> bool {
+    true
+}
+
+impl Rule {
+    pub fn new(matches: MatchOn, pattern: String, profile: String) -> Self {
+        Self {
+            enabled: true,
+            matches,
+            pattern,
+            profile,
+        }
+    }
+
+    pub fn matches(&self, focus: &Focus) -> bool {
+        let haystack = match self.matches {
+            MatchOn::Process => &focus.process,
+            MatchOn::Title => &focus.title,
+        };
+        let pattern = self.pattern.trim().to_lowercase();
+        self.enabled && !pattern.is_empty() && haystack.to_lowercase().contains(&pattern)
+    }
+}
+
+/// Rules for switching profiles, the first matching rule wins
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Rules {
+    pub enabled: bool,
+    /// profile for applications no rule matches, keeps the current one when empty
+    pub fallback: Option<String>,
+    #[serde(rename = "rule")]
+    pub rules: Vec<Rule>,
+}
+
+impl Rules {
+    pub fn load() -> Self {
+        storage::load_or_default(RULES_FILE)
+    }
+
+    pub fn save(&self) -> Result<(), storage::Error> {
+        storage::save(RULES_FILE, self)
+    }
+
+    /// Profile to use while `focus` is focused
+    pub fn profile_for(&self, focus: Option<&Focus>) -> Option<&str> {
+        focus
+            .and_then(|focus| self.rules.iter().find(|rule| rule.matches(focus)))
+            .map(|rule| rule.profile.as_str())
+            .or(self.fallback.as_deref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn focus(process: &str, title: &str) -> Focus {
+        Focus {
+            process: process.to_string(),
+            title: title.to_string(),
+            pid: None,
+        }
+    }
+
+    fn rules(fallback: Option<&str>) -> Rules {
+        let mut disabled = Rule::new(MatchOn::Process, "osu".to_string(), "off".to_string());
+        disabled.enabled = false;
+        Rules {
+            enabled: true,
+            fallback: fallback.map(str::to_string),
+            rules: vec![
+                disabled,
+                Rule::new(MatchOn::Process, " OSU ".to_string(), "osu".to_string()),
+                Rule::new(MatchOn::Title, "ranked".to_string(), "ranked".to_string()),
+                Rule::new(MatchOn::Process, String::new(), "empty".to_string()),
+            ],
+        }
+    }
+
+    #[test]
+    fn profile_for() {
+        let rules = rules(Some("default"));
+        let cases = [
+            // disabled rules are skipped and patterns are trimmed and case insensitive
+            (Some(focus("osu!", "ranked play")), Some("osu")),
+            (Some(focus("game", "Ranked match")), Some("ranked")),
+            // the title rule doesn't look at the process name
+            (Some(focus("ranked", "menu")), Some("default")),
+            // an empty pattern matches nothing
+            (Some(focus("editor", "notes")), Some("default")),
+            (None, Some("default")),
+        ];
+        for (focus, profile) in cases {
+            assert_eq!(rules.profile_for(focus.as_ref()), profile, "{focus:?}");
+        }
+    }
+
+    #[test]
+    fn profile_for_without_fallback() {
+        let rules = rules(None);
+        assert_eq!(rules.profile_for(Some(&focus("editor", ""))), None);
+        assert_eq!(rules.profile_for(None), None);
+        assert_eq!(rules.profile_for(Some(&focus("OSU", ""))), Some("osu"));
+    }
+}